@@ -0,0 +1,89 @@
+// Build-use-discard cycles, as in a game loop that builds a fresh queue every frame:
+// push 10k elements, pop half of them, drop the heap. Compares `FibonacciHeap`, `AllocFibonacciHeap` in the global
+// allocator and `BumpFibonacciHeap` in an arena that is reset after every frame,
+// reporting the time per frame and the calls into the global allocator per frame.
+//
+//     cargo bench --bench arena --features bumpalo
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use bumpalo::Bump;
+use fibheap::{AllocFibonacciHeap, BumpFibonacciHeap, FibonacciHeap};
+
+const FRAMES: usize = 2_000;
+const PUSHES: usize = 10_000;
+
+struct CountingAllocator;
+
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        CALLS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CALLS.fetch_add(1, Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        CALLS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn run(name: &str, mut frame: impl FnMut(&mut Rng) -> u64) {
+    let mut rng = Rng(0xbb67ae8584caa73b);
+    let calls = CALLS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let mut checksum = 0u64;
+    for _ in 0..FRAMES {
+        checksum = checksum.wrapping_add(frame(&mut rng));
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name:<7} {:>8.2?}/frame  {:>8.1} allocator calls/frame  (checksum {checksum})",
+        elapsed / FRAMES as u32,
+        (CALLS.load(Ordering::Relaxed) - calls) as f64 / FRAMES as f64,
+    );
+}
+
+fn main() {
+    run("vec", |rng| {
+        let mut heap = FibonacciHeap::new();
+        (0..PUSHES).for_each(|_| heap.push(black_box(rng.next())));
+        (0..PUSHES / 2).map(|_| heap.pop().unwrap()).fold(0, u64::wrapping_add)
+    });
+    run("global", |rng| {
+        let mut heap = AllocFibonacciHeap::new();
+        (0..PUSHES).for_each(|_| heap.push(black_box(rng.next())));
+        (0..PUSHES / 2).map(|_| heap.pop().unwrap()).fold(0, u64::wrapping_add)
+    });
+    let mut arena = Bump::new();
+    run("bump", |rng| {
+        let mut heap = BumpFibonacciHeap::new_in(&arena);
+        (0..PUSHES).for_each(|_| heap.push(black_box(rng.next())));
+        let checksum = (0..PUSHES / 2).map(|_| heap.pop().unwrap()).fold(0, u64::wrapping_add);
+        drop(heap);
+        arena.reset();
+        checksum
+    });
+}
@@ -0,0 +1,76 @@
+// Batches of 10k pushes alternating with bursts of 5k pops, on a heap of about 100k elements:
+// - push: every item pushed on its own, the next `pop` consolidating the batch;
+// - bulk: each batch added with `bulk_push` and consolidated right away, outside of the pops.
+// Reports the insert throughput, the time spent consolidating outside of the pops and the pop latency.
+//
+//     cargo bench --bench bulk_push
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use fibheap::FibonacciHeap;
+
+const BATCHES: usize = 500;
+const BATCH: usize = 10_000;
+const POPS: usize = 5_000;
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn run(name: &str, bulk: bool) {
+    let mut rng = Rng(0x5be0cd19137e2179);
+    let mut heap = FibonacciHeap::from_vec((0..100_000).map(|_| rng.next()).collect());
+    let mut insert = Duration::ZERO;
+    let mut link = Duration::ZERO;
+    let mut latencies = Vec::with_capacity(BATCHES * POPS);
+    let mut checksum = 0u64;
+    for _ in 0..BATCHES {
+        let batch: Vec<u64> = (0..BATCH).map(|_| rng.next()).collect();
+        let start = Instant::now();
+        if bulk {
+            heap.bulk_push(black_box(batch));
+        } else {
+            for value in batch {
+                heap.push(black_box(value));
+            }
+        }
+        insert += start.elapsed();
+        if bulk {
+            let start = Instant::now();
+            heap.consolidate();
+            link += start.elapsed();
+        }
+        for _ in 0..POPS {
+            let start = Instant::now();
+            let value = heap.pop();
+            latencies.push(start.elapsed());
+            checksum = checksum.wrapping_add(value.unwrap());
+        }
+        // keep the heap at its base size without timing it
+        for _ in 0..BATCH - POPS {
+            heap.pop();
+        }
+    }
+    latencies.sort_unstable();
+    let quantile = |q: f64| latencies[((latencies.len() - 1) as f64 * q) as usize];
+    println!(
+        "{name:<4} insert {:>5.1} Mitems/s  consolidate {:>8.2?}  pop p50 {:>8.2?}  p99.99 {:>8.2?}  max {:>8.2?}  (checksum {checksum})",
+        (BATCHES * BATCH) as f64 / insert.as_secs_f64() / 1e6,
+        link,
+        quantile(0.5),
+        quantile(0.9999),
+        latencies.last().unwrap(),
+    );
+}
+
+fn main() {
+    run("push", false);
+    run("bulk", true);
+}
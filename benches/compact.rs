@@ -0,0 +1,51 @@
+// Memory and pop throughput of `FibonacciHeap` and `CompactFibonacciHeap` on 10M `u64` elements:
+// the heap is built by pushing, popped once so that it is consolidated, measured with `memory_usage`, then drained.
+//
+//     cargo bench --bench compact
+use std::hint::black_box;
+use std::time::Instant;
+
+use fibheap::{CompactFibonacciHeap, FibonacciHeap, Heap, HeapMemoryUsage};
+
+const LEN: usize = 10_000_000;
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn run<H: Heap<u64> + Default>(name: &str, memory_usage: impl Fn(&H) -> HeapMemoryUsage) {
+    let mut rng = Rng(0x6a09e667f3bcc908);
+    let mut heap = H::default();
+    for _ in 0..LEN {
+        heap.push(rng.next());
+    }
+    heap.pop();
+    let usage = memory_usage(&heap);
+
+    let start = Instant::now();
+    let mut checksum = 0u64;
+    while let Some(value) = heap.pop() {
+        checksum = checksum.wrapping_add(black_box(value));
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name:<8} {:>6.1} B/element (payload {}, overhead {}, unused {} MB)  drain {elapsed:>8.2?} ({:.2} Mpops/s, checksum {checksum})",
+        usage.total as f64 / (LEN - 1) as f64,
+        usage.payload >> 20,
+        usage.node_overhead >> 20,
+        usage.unused_capacity >> 20,
+        (LEN - 1) as f64 / elapsed.as_secs_f64() / 1e6,
+    );
+}
+
+fn main() {
+    run::<FibonacciHeap<u64>>("fibheap", FibonacciHeap::memory_usage);
+    run::<CompactFibonacciHeap<u64>>("compact", CompactFibonacciHeap::memory_usage);
+}
@@ -0,0 +1,257 @@
+// Criterion suite comparing `FibonacciHeap` (lazy, and with eager consolidation as "eager"), `CompactFibonacciHeap`
+// and `PairingHeap` against `std::collections::BinaryHeap` (used as a min-heap through `Reverse`)
+// on the same workloads, at several sizes and for 8-byte and 64-byte payloads:
+// - push: fill an empty heap;
+// - pop: drain a filled heap;
+// - mixed: a push and a pop per step on a filled heap;
+// - heapsort: build from a vector and drain it;
+// - append: merge 64 heaps of equal size into one, then pop once;
+// - dijkstra: shortest paths on a grid graph, with a push per relaxed edge and stale entries skipped on pop
+//   (neither backend has `decrease_key`).
+//
+//     cargo bench --bench compare
+//     cargo bench --bench compare -- 'mixed/.*/u64'
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use fibheap::{CompactFibonacciHeap, FibonacciHeap, Heap, PairingHeap};
+
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+trait Payload: Ord + Copy {
+    const NAME: &'static str;
+
+    fn from_key(key: u64) -> Self;
+}
+
+impl Payload for u64 {
+    const NAME: &'static str = "u64";
+
+    fn from_key(key: u64) -> Self {
+        key
+    }
+}
+
+impl Payload for [u64; 8] {
+    const NAME: &'static str = "64B";
+
+    fn from_key(key: u64) -> Self {
+        [key, 1, 2, 3, 4, 5, 6, 7]
+    }
+}
+
+trait Backend<P>: Heap<P> + Default {
+    const NAME: &'static str;
+
+    fn from_vec(values: Vec<P>) -> Self;
+}
+
+impl<P: Ord> Backend<P> for FibonacciHeap<P> {
+    const NAME: &'static str = "fibheap";
+
+    fn from_vec(values: Vec<P>) -> Self {
+        FibonacciHeap::from_vec(values)
+    }
+}
+
+// `FibonacciHeap` linking in `push` and `append` instead of `pop`.
+struct Eager<P>(FibonacciHeap<P>);
+
+impl<P: Ord> Default for Eager<P> {
+    fn default() -> Self {
+        Self::from_vec(vec![])
+    }
+}
+
+impl<P: Ord> Heap<P> for Eager<P> {
+    fn push(&mut self, value: P) {
+        self.0.push(value)
+    }
+
+    fn pop(&mut self) -> Option<P> {
+        self.0.pop()
+    }
+
+    fn peek(&self) -> Option<&P> {
+        self.0.peek()
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn append(&mut self, other: Self) {
+        self.0.append(other.0)
+    }
+}
+
+impl<P: Ord> Backend<P> for Eager<P> {
+    const NAME: &'static str = "eager";
+
+    fn from_vec(values: Vec<P>) -> Self {
+        let mut heap = FibonacciHeap::from_vec(values);
+        heap.set_eager_consolidation(true);
+        Self(heap)
+    }
+}
+
+impl<P: Ord> Backend<P> for CompactFibonacciHeap<P> {
+    const NAME: &'static str = "compact";
+
+    fn from_vec(values: Vec<P>) -> Self {
+        CompactFibonacciHeap::from_vec(values)
+    }
+}
+
+impl<P: Ord> Backend<P> for PairingHeap<P> {
+    const NAME: &'static str = "pairing";
+
+    fn from_vec(values: Vec<P>) -> Self {
+        PairingHeap::from_vec(values)
+    }
+}
+
+impl<P: Ord> Backend<P> for BinaryHeap<Reverse<P>> {
+    const NAME: &'static str = "binaryheap";
+
+    fn from_vec(values: Vec<P>) -> Self {
+        values.into_iter().map(Reverse).collect()
+    }
+}
+
+fn keys(len: usize, seed: u64) -> Vec<u64> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        })
+        .collect()
+}
+
+fn filled<P: Payload, H: Backend<P>>(len: usize) -> H {
+    let mut heap = H::default();
+    keys(len, 0x9e3779b97f4a7c15).into_iter().for_each(|key| heap.push(P::from_key(key)));
+    heap
+}
+
+fn workloads<P: Payload, H: Backend<P>>(c: &mut Criterion) {
+    for len in SIZES {
+        let id = |workload: &str| (format!("{workload}/{}", P::NAME), BenchmarkId::new(H::NAME, len));
+        let values: Vec<P> = keys(len, 0x3c6ef372fe94f82b).into_iter().map(P::from_key).collect();
+
+        let (group, bench) = id("push");
+        c.benchmark_group(group).throughput(Throughput::Elements(len as u64)).bench_function(bench, |b| {
+            b.iter(|| {
+                let mut heap = H::default();
+                values.iter().for_each(|&value| heap.push(black_box(value)));
+                heap
+            })
+        });
+
+        let (group, bench) = id("pop");
+        c.benchmark_group(group).throughput(Throughput::Elements(len as u64)).bench_function(bench, |b| {
+            b.iter_batched(|| filled::<P, H>(len), |mut heap| while black_box(heap.pop()).is_some() {}, BatchSize::LargeInput)
+        });
+
+        let (group, bench) = id("mixed");
+        c.benchmark_group(group).throughput(Throughput::Elements(2 * len as u64)).bench_function(bench, |b| {
+            b.iter_batched(
+                || filled::<P, H>(len),
+                |mut heap| {
+                    for &value in &values {
+                        heap.push(black_box(value));
+                        black_box(heap.pop());
+                    }
+                    heap
+                },
+                BatchSize::LargeInput,
+            )
+        });
+
+        let (group, bench) = id("heapsort");
+        c.benchmark_group(group).throughput(Throughput::Elements(len as u64)).bench_function(bench, |b| {
+            b.iter_batched(|| values.clone(), |values| {
+                let mut heap = H::from_vec(values);
+                while black_box(heap.pop()).is_some() {}
+            }, BatchSize::LargeInput)
+        });
+
+        let (group, bench) = id("append");
+        c.benchmark_group(group).throughput(Throughput::Elements(len as u64)).bench_function(bench, |b| {
+            b.iter_batched(
+                || values.chunks(len / 64).map(|chunk| H::from_vec(chunk.to_vec())).collect::<Vec<_>>(),
+                |heaps| {
+                    let mut merged = H::default();
+                    heaps.into_iter().for_each(|heap| merged.append(heap));
+                    black_box(merged.pop());
+                    merged
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+}
+
+// Shortest distances from the corner of a `side` x `side` grid with pseudo-random edge weights.
+fn dijkstra<H: Backend<(u64, u32)>>(side: usize, weights: &[u64]) -> u64 {
+    let mut dist = vec![u64::MAX; side * side];
+    let mut heap = H::default();
+    dist[0] = 0;
+    heap.push((0, 0));
+    while let Some((d, node)) = heap.pop() {
+        let node = node as usize;
+        if d > dist[node] {
+            continue;
+        }
+        let (row, col) = (node / side, node % side);
+        let neighbours = [(row > 0, node.wrapping_sub(side)), (row + 1 < side, node + side), (col > 0, node.wrapping_sub(1)), (col + 1 < side, node + 1)];
+        for (_, next) in neighbours.into_iter().filter(|&(exists, _)| exists) {
+            let candidate = d + weights[next];
+            if candidate < dist[next] {
+                dist[next] = candidate;
+                heap.push((candidate, next as u32));
+            }
+        }
+    }
+    dist.iter().sum()
+}
+
+fn dijkstra_workloads<H: Backend<(u64, u32)>>(c: &mut Criterion) {
+    for len in SIZES {
+        let side = (len as f64).sqrt() as usize;
+        let weights: Vec<u64> = keys(side * side, 0xa54ff53a5f1d36f1).into_iter().map(|key| key % 100 + 1).collect();
+        c.benchmark_group("dijkstra")
+            .throughput(Throughput::Elements((side * side) as u64))
+            .bench_function(BenchmarkId::new(H::NAME, side * side), |b| b.iter(|| dijkstra::<H>(side, &weights)));
+    }
+}
+
+fn benches(c: &mut Criterion) {
+    workloads::<u64, FibonacciHeap<u64>>(c);
+    workloads::<u64, Eager<u64>>(c);
+    workloads::<u64, CompactFibonacciHeap<u64>>(c);
+    workloads::<u64, PairingHeap<u64>>(c);
+    workloads::<u64, BinaryHeap<Reverse<u64>>>(c);
+    workloads::<[u64; 8], FibonacciHeap<[u64; 8]>>(c);
+    workloads::<[u64; 8], Eager<[u64; 8]>>(c);
+    workloads::<[u64; 8], CompactFibonacciHeap<[u64; 8]>>(c);
+    workloads::<[u64; 8], PairingHeap<[u64; 8]>>(c);
+    workloads::<[u64; 8], BinaryHeap<Reverse<[u64; 8]>>>(c);
+    dijkstra_workloads::<FibonacciHeap<(u64, u32)>>(c);
+    dijkstra_workloads::<Eager<(u64, u32)>>(c);
+    dijkstra_workloads::<CompactFibonacciHeap<(u64, u32)>>(c);
+    dijkstra_workloads::<PairingHeap<(u64, u32)>>(c);
+    dijkstra_workloads::<BinaryHeap<Reverse<(u64, u32)>>>(c);
+}
+
+criterion_group! {
+    name = compare;
+    config = Criterion::default().sample_size(10);
+    targets = benches
+}
+criterion_main!(compare);
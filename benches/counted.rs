@@ -0,0 +1,66 @@
+// Duplicate-heavy workload: millions of pushes drawn from a few hundred distinct values, then a full drain.
+// Reports time and peak heap memory for the plain and the counted heap.
+//
+//     cargo bench --bench counted
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use fibheap::{CountedFibonacciHeap, FibonacciHeap};
+
+struct PeakAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc;
+
+const PUSHES: usize = 2_000_000;
+const DISTINCT: u64 = 300;
+
+fn values() -> impl Iterator<Item = u64> {
+    let mut x = 0x9e3779b97f4a7c15u64;
+    (0..PUSHES).map(move |_| {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        x % DISTINCT
+    })
+}
+
+fn measure(name: &str, run: impl FnOnce() -> u64) {
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let start = Instant::now();
+    let checksum = run();
+    let elapsed = start.elapsed();
+    let peak = PEAK.load(Ordering::Relaxed) - baseline;
+    println!("{name:<22} {elapsed:>10.2?}  peak {:>8.2} MiB  (checksum {checksum})", peak as f64 / (1 << 20) as f64);
+}
+
+fn main() {
+    measure("FibonacciHeap", || {
+        let mut heap = FibonacciHeap::new();
+        values().for_each(|v| heap.push(v));
+        heap.sum()
+    });
+    measure("CountedFibonacciHeap", || {
+        let mut heap = CountedFibonacciHeap::new();
+        values().for_each(|v| heap.push(v));
+        std::iter::from_fn(|| heap.pop()).sum()
+    });
+}
@@ -0,0 +1,54 @@
+// Cost of taking a snapshot of a 1M-element heap once per batch of 100 pushes, with a deep clone and with
+// `CowFibonacciHeap`, whose clone is shared until the next push copies it.
+//
+//     cargo bench --bench cow
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use fibheap::{CowFibonacciHeap, FibonacciHeap};
+
+const LEN: u64 = 1_000_000;
+const SNAPSHOTS: u64 = 100;
+
+fn time(mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn main() {
+    let mut heap = FibonacciHeap::from_vec((0..LEN).rev().collect());
+    heap.pop();
+    let mut cow = CowFibonacciHeap::from(heap.clone());
+
+    let clone = time(|| drop(black_box(heap.clone())));
+    let cow_clone = time(|| drop(black_box(cow.clone())));
+    println!("single clone        deep {clone:>10.2?}  cow {cow_clone:>10.2?}");
+
+    // read-mostly: a snapshot is taken and dropped before the next batch of changes, so nothing is ever copied
+    let deep = time(|| {
+        for i in 0..SNAPSHOTS {
+            let snapshot = black_box(heap.clone());
+            drop(snapshot);
+            (0..100).for_each(|j| heap.push(LEN + i * 100 + j));
+        }
+    });
+    let shared = time(|| {
+        for i in 0..SNAPSHOTS {
+            let snapshot = black_box(cow.clone());
+            drop(snapshot);
+            (0..100).for_each(|j| cow.push(LEN + i * 100 + j));
+        }
+    });
+    println!("{SNAPSHOTS} snapshots       deep {deep:>10.2?}  cow {shared:>10.2?}");
+
+    // a snapshot still alive at the next change costs the cow heap one copy, like a deep clone
+    let mut kept = vec![];
+    let held = time(|| {
+        for i in 0..SNAPSHOTS {
+            kept.push(cow.clone());
+            cow.push(LEN * 2 + i);
+        }
+    });
+    println!("{SNAPSHOTS} held snapshots  cow {held:>10.2?}");
+}
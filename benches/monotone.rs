@@ -0,0 +1,68 @@
+// Dijkstra with integer weights on a random graph, using the monotone heap versus the general one.
+//
+//     cargo bench --bench monotone
+use std::time::{Duration, Instant};
+
+use fibheap::{FibonacciHeap, MonotoneFibonacciHeap};
+
+const NODES: usize = 200_000;
+const EDGES_PER_NODE: usize = 8;
+
+fn random_graph() -> Vec<Vec<(u32, u32)>> {
+    let mut x = 0x9e3779b97f4a7c15u64;
+    let mut next = move || {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        x
+    };
+    (0..NODES)
+        .map(|_| (0..EDGES_PER_NODE).map(|_| ((next() % NODES as u64) as u32, (next() % 1000) as u32)).collect())
+        .collect()
+}
+
+// Entries are `(distance << 32) | node`, so both heaps order them by distance.
+fn dijkstra(graph: &[Vec<(u32, u32)>], mut push: impl FnMut(u64), mut pop: impl FnMut() -> Option<u64>) -> Vec<u64> {
+    let mut dist = vec![u64::MAX; graph.len()];
+    dist[0] = 0;
+    push(0);
+    while let Some(entry) = pop() {
+        let (d, node) = (entry >> 32, (entry & u32::MAX as u64) as usize);
+        if d > dist[node] {
+            continue;
+        }
+        for &(next, weight) in &graph[node] {
+            let nd = d + weight as u64;
+            if nd < dist[next as usize] {
+                dist[next as usize] = nd;
+                push((nd << 32) | next as u64);
+            }
+        }
+    }
+    dist
+}
+
+fn time<R>(f: impl FnOnce() -> R) -> (R, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+fn main() {
+    let graph = random_graph();
+
+    let mut general = FibonacciHeap::new();
+    let (expected, elapsed) = time(|| {
+        let heap = std::cell::RefCell::new(&mut general);
+        dijkstra(&graph, |e| heap.borrow_mut().push(e), || heap.borrow_mut().pop())
+    });
+    println!("FibonacciHeap         {elapsed:>10.2?}");
+
+    let mut monotone = MonotoneFibonacciHeap::new();
+    let (dist, elapsed) = time(|| {
+        let heap = std::cell::RefCell::new(&mut monotone);
+        dijkstra(&graph, |e| heap.borrow_mut().push(e), || heap.borrow_mut().pop())
+    });
+    println!("MonotoneFibonacciHeap {elapsed:>10.2?}");
+    assert_eq!(dist, expected);
+}
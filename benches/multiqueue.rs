@@ -0,0 +1,50 @@
+// Throughput of MultiQueue against a single-lock SharedFibonacciHeap under a mixed push/pop load.
+//
+//     cargo bench --features sync --bench multiqueue
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use fibheap::{MultiQueue, SharedFibonacciHeap};
+
+const THREADS: usize = 16;
+const OPS_PER_THREAD: u64 = 200_000;
+const PREFILL: u64 = 100_000;
+
+fn run<Q: Send + Sync + 'static>(queue: Q, push: fn(&Q, u64), pop: fn(&Q) -> Option<u64>) -> Duration {
+    let queue = Arc::new(queue);
+    for i in 0..PREFILL {
+        push(&queue, i.wrapping_mul(0x9e3779b97f4a7c15) >> 20);
+    }
+    let start = Instant::now();
+    let workers: Vec<_> = (0..THREADS as u64)
+        .map(|t| {
+            let queue = Arc::clone(&queue);
+            thread::spawn(move || {
+                let mut x = t + 1;
+                for _ in 0..OPS_PER_THREAD / 2 {
+                    x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                    push(&queue, x >> 20);
+                    pop(&queue);
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    start.elapsed()
+}
+
+fn report(name: &str, elapsed: Duration) {
+    let ops = (THREADS as u64 * OPS_PER_THREAD) as f64;
+    println!("{name:<28} {:>8.2?}  {:>6.2} Mops/s", elapsed, ops / elapsed.as_secs_f64() / 1e6);
+}
+
+fn main() {
+    report("SharedFibonacciHeap", run(SharedFibonacciHeap::new(), |q, v| q.push(v), |q| q.try_pop()));
+    for shards in [THREADS, 2 * THREADS, 4 * THREADS] {
+        let elapsed = run(MultiQueue::new(shards), |q, v| q.push(v), |q| q.pop());
+        report(&format!("MultiQueue ({shards} shards)"), elapsed);
+    }
+}
@@ -0,0 +1,48 @@
+// Consolidating a heap of 10M singleton roots (as left by `from_vec` or a huge `bulk_push`),
+// serially with `consolidate` and with `par_consolidate` on rayon pools of 1 and 8 threads.
+// Building the heap is not timed. The speedup is bounded by the number of cores actually available.
+//
+//     cargo bench --bench par_consolidate --features rayon
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use fibheap::FibonacciHeap;
+
+const ROOTS: usize = 10_000_000;
+const RUNS: usize = 5;
+
+fn values() -> Vec<u64> {
+    let mut state = 0x510e527fade682d1u64;
+    (0..ROOTS)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        })
+        .collect()
+}
+
+fn run(name: &str, values: &[u64], consolidate: impl Fn(&mut FibonacciHeap<u64>)) {
+    let mut best = Duration::MAX;
+    let mut checksum = 0;
+    for _ in 0..RUNS {
+        let mut heap = FibonacciHeap::from_vec(values.to_vec());
+        let start = Instant::now();
+        consolidate(black_box(&mut heap));
+        best = best.min(start.elapsed());
+        checksum += heap.root_count() as u64 + heap.peek().unwrap();
+        drop(heap);
+    }
+    println!("{name:<18} best of {RUNS}: {best:>9.2?}  (checksum {checksum})");
+}
+
+fn main() {
+    let values = values();
+    run("serial", &values, FibonacciHeap::consolidate);
+    for threads in [1, 8] {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+        run(&format!("parallel {threads} threads"), &values, |heap| pool.install(|| heap.par_consolidate()));
+    }
+    println!("({} cores available)", std::thread::available_parallelism().map_or(1, |n| n.get()));
+}
@@ -0,0 +1,75 @@
+// Worst-case latency of a single `pop`, with and without `set_max_links_per_pop`.
+// The trace is 10M operations: a heap of about 100k elements under alternating pushes and pops,
+// interrupted every 50k steps (a push and one or two pops) by a burst of 50k pushes that the next `pop` has to consolidate.
+//
+// Single samples are at the mercy of the scheduler, so besides the maximum the count of pops slower than 1ms is reported:
+// without the cap there is about one per burst.
+//
+//     cargo bench --bench pop_latency
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use fibheap::FibonacciHeap;
+
+const OPS: usize = 10_000_000;
+const BURST_EVERY: usize = 50_000;
+const BURST: usize = 50_000;
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn run(max_links: Option<usize>) {
+    let mut rng = Rng(0x1f83d9abfb41bd6b);
+    let mut heap = FibonacciHeap::from_vec((0..100_000).map(|_| rng.next()).collect());
+    heap.set_max_links_per_pop(max_links);
+    let mut latencies = Vec::with_capacity(OPS / 2);
+    let mut checksum = 0u64;
+    let mut ops = 0;
+    let start = Instant::now();
+    for step in 0.. {
+        if ops >= OPS {
+            break;
+        }
+        if step % BURST_EVERY == 0 {
+            for _ in 0..BURST {
+                heap.push(rng.next());
+            }
+            ops += BURST;
+        }
+        heap.push(black_box(rng.next()));
+        // one pop per push, plus one more while the heap is above its base size
+        let pops = if heap.len() > 100_000 { 2 } else { 1 };
+        for _ in 0..pops {
+            let before = Instant::now();
+            let value = heap.pop();
+            latencies.push(before.elapsed());
+            checksum = checksum.wrapping_add(value.unwrap());
+        }
+        ops += 1 + pops;
+    }
+    let elapsed = start.elapsed();
+    latencies.sort_unstable();
+    let quantile = |q: f64| latencies[((latencies.len() - 1) as f64 * q) as usize];
+    let cap = max_links.map_or("none".to_string(), |max| max.to_string());
+    println!(
+        "cap {cap:<5} {ops} ops: {elapsed:>8.2?}  pop p50 {:>8.2?}  p99.9 {:>8.2?}  p99.99 {:>8.2?}  max {:>8.2?}  over 1ms: {}  (checksum {checksum})",
+        quantile(0.5),
+        quantile(0.999),
+        quantile(0.9999),
+        latencies.last().copied().unwrap_or(Duration::ZERO),
+        latencies.iter().filter(|&&latency| latency > Duration::from_millis(1)).count(),
+    );
+}
+
+fn main() {
+    run(None);
+    run(Some(64));
+}
@@ -0,0 +1,74 @@
+// Push/pop throughput.
+// - pure: fill the heap with 1M pseudo-random values, then drain it;
+// - mixed: 64-byte payloads, a push-heavy phase (3 pushes per pop) followed by a pop-heavy one (3 pops per push).
+//
+//     cargo bench --bench push_pop
+use std::hint::black_box;
+use std::time::Instant;
+
+use fibheap::FibonacciHeap;
+
+const LEN: usize = 1_000_000;
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn report(name: &str, ops: usize, start: Instant, checksum: u64) {
+    let elapsed = start.elapsed();
+    println!(
+        "{name:<6} {ops} ops: {elapsed:>8.2?} ({:.1} Mops/s, checksum {checksum})",
+        ops as f64 / elapsed.as_secs_f64() / 1e6
+    );
+}
+
+fn pure() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    let values: Vec<u64> = (0..LEN).map(|_| rng.next()).collect();
+
+    let start = Instant::now();
+    let mut heap = FibonacciHeap::new();
+    for &value in &values {
+        heap.push(black_box(value));
+    }
+    let mut checksum = 0u64;
+    while let Some(value) = heap.pop() {
+        checksum = checksum.wrapping_add(value);
+    }
+    report("pure", 2 * LEN, start, checksum);
+}
+
+fn mixed() {
+    let mut rng = Rng(0x3c6ef372fe94f82b);
+    let mut heap = FibonacciHeap::new();
+    let mut checksum = 0u64;
+    let mut ops = 0;
+    let start = Instant::now();
+    for (pushes, pops) in [(3, 1), (1, 3)] {
+        for _ in 0..LEN / 4 {
+            for _ in 0..pushes {
+                let key = rng.next();
+                heap.push(black_box([key, 1, 2, 3, 4, 5, 6, 7]));
+            }
+            for _ in 0..pops {
+                if let Some(payload) = heap.pop() {
+                    checksum = checksum.wrapping_add(payload[0]);
+                }
+            }
+            ops += pushes + pops;
+        }
+    }
+    report("mixed", ops, start, checksum);
+}
+
+fn main() {
+    pure();
+    mixed();
+}
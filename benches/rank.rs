@@ -0,0 +1,37 @@
+// `rank` and `select` of the 100th smallest element, on consolidated heaps of growing size: the cost follows the
+// rank asked for, not the size of the heap. A percentile query (rank n/2) costs O(n) like any scan.
+//
+//     cargo bench --bench rank
+use std::hint::black_box;
+use std::time::Instant;
+
+use fibheap::FibonacciHeap;
+
+const QUERIES: u32 = 10_000;
+
+fn main() {
+    for n in [10_000u64, 100_000, 1_000_000] {
+        let mut x = 0x9e3779b97f4a7c15u64;
+        let mut heap = FibonacciHeap::new();
+        for _ in 0..n {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            heap.push(x % n);
+        }
+        heap.pop();
+        let probe = *heap.select(100).unwrap();
+
+        let start = Instant::now();
+        for _ in 0..QUERIES {
+            black_box(heap.rank(black_box(&probe)));
+        }
+        let rank = start.elapsed() / QUERIES;
+        let start = Instant::now();
+        for _ in 0..QUERIES {
+            black_box(heap.select(black_box(100)));
+        }
+        let select = start.elapsed() / QUERIES;
+        println!("n = {n:>9}  rank {rank:>10.2?}  select {select:>10.2?}");
+    }
+}
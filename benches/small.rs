@@ -0,0 +1,59 @@
+// Heaps of a few elements, where a flat root list saves the linking of a Fibonacci heap:
+// - fill+drain: push `size` elements into a reused heap, then pop them all;
+// - mixed: a push and a pop per step on a heap holding `size` elements.
+// Reports nanoseconds per operation at sizes 4, 16, 64 and 1024 (the last two above the flat limit of 16).
+//
+//     cargo bench --bench small
+use std::hint::black_box;
+use std::time::Instant;
+
+use fibheap::FibonacciHeap;
+
+const OPS: usize = 4_000_000;
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn fill_drain(size: usize) -> f64 {
+    let mut rng = Rng(0x6a09e667f3bcc908);
+    let mut heap = FibonacciHeap::new();
+    let mut checksum = 0u64;
+    let start = Instant::now();
+    for _ in 0..OPS / (2 * size) {
+        for _ in 0..size {
+            heap.push(black_box(rng.next()));
+        }
+        while let Some(value) = heap.pop() {
+            checksum = checksum.wrapping_add(value);
+        }
+    }
+    black_box(checksum);
+    start.elapsed().as_nanos() as f64 / OPS as f64
+}
+
+fn mixed(size: usize) -> f64 {
+    let mut rng = Rng(0xbb67ae8584caa73b);
+    let mut heap = FibonacciHeap::from_vec((0..size).map(|_| rng.next()).collect());
+    let mut checksum = 0u64;
+    let start = Instant::now();
+    for _ in 0..OPS / 2 {
+        heap.push(black_box(rng.next()));
+        checksum = checksum.wrapping_add(heap.pop().unwrap());
+    }
+    black_box(checksum);
+    start.elapsed().as_nanos() as f64 / OPS as f64
+}
+
+fn main() {
+    for size in [4, 16, 64, 1024] {
+        println!("size {size:>4}: fill+drain {:>6.1} ns/op  mixed {:>6.1} ns/op", fill_drain(size), mixed(size));
+    }
+}
@@ -0,0 +1,34 @@
+// Heap construction plus ordered drain, measured through the sort helpers against the std sorts.
+//
+//     cargo bench --bench sort
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+fn random_values(len: usize) -> Vec<u64> {
+    let mut x = 0x9e3779b97f4a7c15u64;
+    (0..len)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x
+        })
+        .collect()
+}
+
+fn time(mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn main() {
+    for len in [1_000, 100_000, 1_000_000] {
+        let values = random_values(len);
+        println!("n = {len}");
+        println!("  fibheap::sort          {:>10.2?}", time(|| drop(black_box(fibheap::sort(values.clone())))));
+        println!("  fibheap::sort_in_place {:>10.2?}", time(|| fibheap::sort_in_place(black_box(&mut values.clone()))));
+        println!("  slice::sort            {:>10.2?}", time(|| black_box(&mut values.clone()).sort()));
+        println!("  slice::sort_unstable   {:>10.2?}", time(|| black_box(&mut values.clone()).sort_unstable()));
+    }
+}
@@ -0,0 +1,64 @@
+// Building a heap from 64 sorted runs with `from_sorted_runs`, versus `from_vec` on the concatenated runs, then
+// popping every element. Reports the comparisons and time spent building and popping.
+//
+//     cargo bench --bench sorted_runs
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::hint::black_box;
+use std::time::Instant;
+
+use fibheap::FibonacciHeap;
+
+const RUNS: usize = 64;
+
+thread_local! {
+    static COMPARISONS: Cell<u64> = const { Cell::new(0) };
+}
+
+// Counts its comparisons.
+#[derive(Clone, PartialEq)]
+struct Counted(u64);
+
+impl PartialOrd for Counted {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        COMPARISONS.set(COMPARISONS.get() + 1);
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+fn measure(name: &str, runs: &[Vec<Counted>], build: fn(Vec<Vec<Counted>>) -> FibonacciHeap<Counted>) {
+    let runs = runs.to_vec();
+    COMPARISONS.set(0);
+    let start = Instant::now();
+    let mut heap = build(black_box(runs));
+    let (built, build_comparisons) = (start.elapsed(), COMPARISONS.replace(0));
+    let start = Instant::now();
+    while let Some(value) = heap.pop() {
+        black_box(value);
+    }
+    let (popped, pop_comparisons) = (start.elapsed(), COMPARISONS.get());
+    println!("{name:<16} build {build_comparisons:>9} comparisons {built:>9.2?}  pop {pop_comparisons:>10} comparisons {popped:>9.2?}");
+}
+
+fn main() {
+    for n in [10_000u64, 100_000, 1_000_000] {
+        let mut x = 0x9e3779b97f4a7c15u64;
+        let runs: Vec<Vec<Counted>> = (0..RUNS)
+            .map(|_| {
+                let mut run: Vec<u64> = (0..n / RUNS as u64)
+                    .map(|_| {
+                        x ^= x << 13;
+                        x ^= x >> 7;
+                        x ^= x << 17;
+                        x % n
+                    })
+                    .collect();
+                run.sort_unstable();
+                run.into_iter().map(Counted).collect()
+            })
+            .collect();
+        println!("n = {n}");
+        measure("from_sorted_runs", &runs, FibonacciHeap::from_sorted_runs);
+        measure("from_vec", &runs, |runs| FibonacciHeap::from_vec(runs.concat()));
+    }
+}
@@ -1,5 +1,7 @@
 use fibheap::FibonacciHeap;
 
+// `into_iter` kept from the original example, where the heap was not yet an iterator itself
+#[allow(clippy::useless_conversion)]
 fn main() {
     const SIZE: usize = 100000;
     let mut vec = Vec::with_capacity(SIZE);
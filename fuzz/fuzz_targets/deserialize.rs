@@ -0,0 +1,50 @@
+// Feeds arbitrary bytes to every way of reading a heap back: serde through bincode and JSON, plain and with
+// `DeserializeOptions`, and rkyv's validated `access` followed by `deserialize`. Malformed input must come back as an error, never as a panic, and any heap
+// that is accepted must pass `validate()` and hold exactly the elements that were read.
+#![no_main]
+
+use fibheap::{ArchivedFibonacciHeap, DeserializeOptions, FibonacciHeap};
+use libfuzzer_sys::fuzz_target;
+use rkyv::rancor::Error;
+use rkyv::util::AlignedVec;
+use rkyv::Archived;
+
+// Checks a decoded heap against the elements it was built from, given in any order.
+fn check(heap: FibonacciHeap<u32>, mut values: Vec<u32>) {
+    assert_eq!(heap.validate(), Ok(()));
+    assert_eq!(heap.len(), values.len());
+    values.sort_unstable();
+    assert_eq!(heap.into_sorted_vec(), values);
+}
+
+fuzz_target!(|data: &[u8]| {
+    use bincode::Options;
+
+    // bincode trusts length prefixes, so bound the input it may claim; the heap itself caps preallocation
+    let bincode = bincode::options().with_limit(1 << 20).with_fixint_encoding().allow_trailing_bytes();
+    if let Ok(heap) = bincode.deserialize::<FibonacciHeap<u32>>(data) {
+        check(heap, bincode.deserialize(data).unwrap());
+    }
+
+    if let Ok(heap) = serde_json::from_slice::<FibonacciHeap<u32>>(data) {
+        check(heap, serde_json::from_slice(data).unwrap());
+    }
+
+    // the limit holds without bincode's own, and a validated heap was read in ascending order
+    let options = DeserializeOptions { max_len: Some(256), validate: true };
+    let bincode = bincode::options().with_fixint_encoding().allow_trailing_bytes();
+    if let Ok(heap) = options.deserialize::<u32, _>(&mut bincode::Deserializer::from_slice(data, bincode)) {
+        let values: Vec<u32> = bincode.deserialize(data).unwrap();
+        assert!(values.len() <= 256 && values.is_sorted());
+        check(heap, values);
+    }
+
+    // `access` wants the archive aligned, as it would be when read from a file into an `AlignedVec`
+    let mut bytes = AlignedVec::<16>::with_capacity(data.len());
+    bytes.extend_from_slice(data);
+    if let Ok(archived) = rkyv::access::<ArchivedFibonacciHeap<Archived<u32>>, Error>(&bytes) {
+        let values = archived.iter().map(|value| value.to_native()).collect();
+        let heap: FibonacciHeap<u32> = rkyv::deserialize::<_, Error>(archived).unwrap();
+        check(heap, values);
+    }
+});
@@ -0,0 +1,116 @@
+// Reads the input as a sequence of heap operations, runs them on a `FibonacciHeap<u32>` and on a sorted `Vec`,
+// and checks that both agree after every step, along with `validate()`.
+//
+// Each operation is one byte, followed by its arguments: a value is one byte, so that ties are common,
+// and a batch is a length byte followed by that many values. Missing bytes read as zero.
+#![no_main]
+
+use fibheap::FibonacciHeap;
+use libfuzzer_sys::fuzz_target;
+
+struct Input<'a>(&'a [u8]);
+
+impl Input<'_> {
+    fn byte(&mut self) -> u8 {
+        let (&byte, rest) = self.0.split_first().unwrap_or((&0, &[]));
+        self.0 = rest;
+        byte
+    }
+
+    fn value(&mut self) -> u32 {
+        self.byte().into()
+    }
+
+    fn values(&mut self) -> Vec<u32> {
+        let len = self.byte() % 64;
+        (0..len).map(|_| self.value()).collect()
+    }
+}
+
+// Sorted in descending order, so that the minimum is popped from the end.
+#[derive(Default)]
+struct Model(Vec<u32>);
+
+impl Model {
+    fn push(&mut self, value: u32) {
+        let ix = self.0.partition_point(|&other| other > value);
+        self.0.insert(ix, value);
+    }
+
+    fn pop(&mut self) -> Option<u32> {
+        self.0.pop()
+    }
+
+    fn peek(&self) -> Option<&u32> {
+        self.0.last()
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut input = Input(data);
+    let mut heap = FibonacciHeap::new();
+    let mut model = Model::default();
+    while !input.0.is_empty() {
+        match input.byte() % 12 {
+            0..=2 => {
+                let value = input.value();
+                heap.push(value);
+                model.push(value);
+            }
+            3..=5 => assert_eq!(heap.pop(), model.pop()),
+            6 => {
+                let value = input.value();
+                model.push(value);
+                assert_eq!(heap.push_pop(value), model.pop().unwrap());
+            }
+            7 => {
+                let value = input.value();
+                // pushes `value` even into an empty heap
+                let expected = model.pop();
+                model.push(value);
+                assert_eq!(heap.replace_top(value), expected);
+            }
+            8 => {
+                // give the other heap some trees before melding it in
+                let mut other = FibonacciHeap::new();
+                let mut other_model = Model::default();
+                for value in input.values() {
+                    other.push(value);
+                    other_model.push(value);
+                }
+                for _ in 0..input.byte() % 8 {
+                    assert_eq!(other.pop(), other_model.pop());
+                }
+                assert_eq!(other.validate(), Ok(()));
+                heap.append(other);
+                other_model.0.into_iter().for_each(|value| model.push(value));
+            }
+            9 => {
+                let values = input.values();
+                values.iter().for_each(|&value| model.push(value));
+                heap.bulk_push(values);
+            }
+            10 => {
+                let values = input.values();
+                model = Model::default();
+                values.iter().for_each(|&value| model.push(value));
+                let settings = (heap.eager_consolidation(), heap.max_links_per_pop());
+                heap = FibonacciHeap::from_vec(values);
+                heap.set_eager_consolidation(settings.0);
+                heap.set_max_links_per_pop(settings.1);
+            }
+            _ => match input.byte() % 4 {
+                0 => heap.consolidate(),
+                1 => heap.set_eager_consolidation(!heap.eager_consolidation()),
+                2 => heap.set_max_links_per_pop(Some(usize::from(input.byte() % 16))),
+                _ => heap.set_max_links_per_pop(None),
+            },
+        }
+        assert_eq!(heap.len(), model.0.len());
+        assert_eq!(heap.peek(), model.peek());
+        assert_eq!(heap.validate(), Ok(()));
+    }
+    let mut expected = model.0;
+    expected.reverse();
+    assert_eq!(heap.into_sorted_vec(), expected);
+});
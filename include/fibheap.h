@@ -0,0 +1,96 @@
+#ifndef FIBHEAP_H
+#define FIBHEAP_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Status returned by the fallible functions (`fibheap_status` in C).
+ */
+typedef enum {
+  FIBHEAP_STATUS_OK = 0,
+  /**
+   * A heap argument was null.
+   */
+  FIBHEAP_STATUS_NULL = 1,
+  /**
+   * The key was NaN, which has no place in the order.
+   */
+  FIBHEAP_STATUS_NAN = 2,
+  /**
+   * Both arguments of `fibheap_merge` were the same heap.
+   */
+  FIBHEAP_STATUS_ALIASED = 3,
+  /**
+   * A panic was caught at the boundary; the heap may have lost elements but remains safe to use and free.
+   */
+  FIBHEAP_STATUS_PANIC = 4,
+} fibheap_status;
+
+/**
+ * Opaque heap of `(key, data)` pairs, smallest key first (`fibheap_t` in C). Equal keys pop in unspecified order.
+ */
+typedef struct fibheap_t fibheap_t;
+
+/**
+ * Allocates an empty heap. Returns null if a panic occurred.
+ */
+fibheap_t *fibheap_new(void);
+
+/**
+ * Frees `heap`; null is ignored. The data pointers it still holds are not touched.
+ *
+ * # Safety
+ * `heap` must be null or a pointer returned by `fibheap_new` that has not been freed yet.
+ */
+void fibheap_free(fibheap_t *heap);
+
+/**
+ * Inserts `data` with priority `key`.
+ *
+ * # Safety
+ * `heap` must be null or a live heap.
+ */
+fibheap_status fibheap_push(fibheap_t *heap, double key, void *data);
+
+/**
+ * Removes the entry with the smallest key and stores it in `out_key` and `out_data` (either may be null).
+ * Returns `false`, leaving the outputs untouched, if the heap is empty or null.
+ *
+ * # Safety
+ * `heap` must be null or a live heap; the outputs must be null or valid for writes.
+ */
+bool fibheap_pop(fibheap_t *heap,
+                 double *out_key,
+                 void **out_data);
+
+/**
+ * Like `fibheap_pop`, without removing the entry.
+ *
+ * # Safety
+ * As for `fibheap_pop`.
+ */
+bool fibheap_peek(const fibheap_t *heap, double *out_key, void **out_data);
+
+/**
+ * Number of entries; 0 for null.
+ *
+ * # Safety
+ * `heap` must be null or a live heap.
+ */
+size_t fibheap_len(const fibheap_t *heap);
+
+/**
+ * Moves every entry of `src` into `dst`. `src` is left empty, and still has to be freed.
+ *
+ * # Safety
+ * Both arguments must be null or live heaps.
+ */
+fibheap_status fibheap_merge(fibheap_t *dst, fibheap_t *src);
+
+#endif  /* FIBHEAP_H */
@@ -0,0 +1,109 @@
+use core::cmp::Ordering;
+use core::mem;
+
+use fibheap::FibonacciHeap;
+use pyo3::exceptions::{PyIndexError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+
+// Payload with its priority; `seq` breaks ties in insertion order.
+struct Entry {
+    priority: f64,
+    seq: u64,
+    item: PyObject,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (self.priority, self.seq).partial_cmp(&(other.priority, other.seq))
+    }
+}
+
+/// Min-heap of arbitrary Python objects ordered by a float priority.
+///
+/// The priority is passed to `push`, or computed by the `key` callable given to the constructor.
+/// Equal priorities pop in insertion order. NaN priorities are rejected with `ValueError`.
+#[pyclass(module = "fibheap")]
+struct FibHeap {
+    heap: FibonacciHeap<Entry>,
+    key: Option<PyObject>,
+    next_seq: u64,
+}
+
+#[pymethods]
+impl FibHeap {
+    #[new]
+    #[pyo3(signature = (key=None))]
+    fn new(key: Option<PyObject>) -> Self {
+        Self { heap: FibonacciHeap::new(), key, next_seq: 0 }
+    }
+
+    /// Pushes `item` with the given priority, or with `key(item)` when the heap has a key and no priority is given.
+    #[pyo3(signature = (item, priority=None))]
+    fn push(&mut self, py: Python<'_>, item: PyObject, priority: Option<f64>) -> PyResult<()> {
+        let priority = match (priority, &self.key) {
+            (Some(priority), _) => priority,
+            (None, Some(key)) => key.call1(py, (item.clone_ref(py),))?.extract(py)?,
+            (None, None) => return Err(PyTypeError::new_err("push() needs a priority when the heap has no key")),
+        };
+        if priority.is_nan() {
+            return Err(PyValueError::new_err("priority must not be NaN"));
+        }
+        self.heap.push(Entry { priority, seq: self.next_seq, item });
+        self.next_seq += 1;
+        Ok(())
+    }
+
+    /// Removes and returns the `(priority, item)` pair with the smallest priority.
+    fn pop(&mut self) -> PyResult<(f64, PyObject)> {
+        let Entry { priority, item, .. } = self.heap.pop().ok_or_else(|| PyIndexError::new_err("pop from an empty heap"))?;
+        Ok((priority, item))
+    }
+
+    /// Returns the `(priority, item)` pair with the smallest priority without removing it.
+    fn peek(&self, py: Python<'_>) -> PyResult<(f64, PyObject)> {
+        let entry = self.heap.peek().ok_or_else(|| PyIndexError::new_err("peek into an empty heap"))?;
+        Ok((entry.priority, entry.item.clone_ref(py)))
+    }
+
+    /// Moves every entry of `other` into this heap, leaving `other` empty.
+    fn merge(slf: &Bound<'_, Self>, other: &Bound<'_, Self>) -> PyResult<()> {
+        if slf.is(other) {
+            return Err(PyValueError::new_err("cannot merge a heap into itself"));
+        }
+        let taken = mem::take(&mut other.borrow_mut().heap);
+        let mut this = slf.borrow_mut();
+        // Entries keep their sequence numbers, so ties stay in insertion order within each heap;
+        // move the counter past the other heap's so that later pushes still come last.
+        let other_seq = other.borrow().next_seq;
+        this.next_seq = this.next_seq.max(other_seq);
+        this.heap.append(taken);
+        Ok(())
+    }
+
+    fn __len__(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Iterates over `(priority, item)` pairs in ascending order, over a snapshot taken now; the heap is left as is.
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let entries = self.heap.iter_sorted().map(|entry| (entry.priority, entry.item.clone_ref(py)));
+        PyList::new(py, entries.collect::<Vec<_>>())?.into_any().try_iter().map(Bound::into_any)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("FibHeap(len={})", self.heap.len())
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "fibheap")]
+fn fibheap_py(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<FibHeap>()
+}
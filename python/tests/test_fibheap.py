@@ -0,0 +1,72 @@
+import math
+
+import pytest
+
+from fibheap import FibHeap
+
+
+def test_push_pop_in_priority_order():
+    heap = FibHeap()
+    for priority, item in [(3.0, "c"), (1.0, "a"), (2.0, "b"), (1.0, "a2")]:
+        heap.push(item, priority)
+    assert len(heap) == 4
+    assert heap.peek() == (1.0, "a")
+    assert [heap.pop() for _ in range(4)] == [(1.0, "a"), (1.0, "a2"), (2.0, "b"), (3.0, "c")]
+    assert len(heap) == 0
+    assert not heap
+
+
+def test_arbitrary_payloads():
+    heap = FibHeap()
+    payload = {"job": [1, 2]}
+    heap.push(payload, 0)
+    heap.push(None, -1)
+    assert heap.pop() == (-1.0, None)
+    assert heap.pop()[1] is payload
+
+
+def test_key_callable():
+    heap = FibHeap(key=len)
+    for word in ["ccc", "a", "bb"]:
+        heap.push(word)
+    heap.push("zzzz", 0.5)
+    assert [item for _, item in heap] == ["zzzz", "a", "bb", "ccc"]
+
+
+def test_errors():
+    heap = FibHeap()
+    with pytest.raises(IndexError):
+        heap.pop()
+    with pytest.raises(IndexError):
+        heap.peek()
+    with pytest.raises(ValueError):
+        heap.push("x", math.nan)
+    with pytest.raises(TypeError):
+        heap.push("x")
+    with pytest.raises(TypeError):
+        FibHeap(key=lambda item: "not a number").push("x")
+    with pytest.raises(ValueError):
+        heap.merge(heap)
+    assert len(heap) == 0
+
+
+def test_merge():
+    left, right = FibHeap(), FibHeap()
+    left.push("l", 2)
+    right.push("r0", 0)
+    right.push("r2", 2)
+    left.merge(right)
+    assert len(left) == 3
+    assert len(right) == 0
+    left.push("late", 2)
+    assert [left.pop()[1] for _ in range(4)] == ["r0", "l", "r2", "late"]
+
+
+def test_iteration_does_not_consume():
+    heap = FibHeap()
+    for priority in [5, 1, 4, 2, 3]:
+        heap.push(str(priority), priority)
+    heap.pop()
+    assert [priority for priority, _ in heap] == [2.0, 3.0, 4.0, 5.0]
+    assert len(heap) == 4
+    assert repr(heap) == "FibHeap(len=4)"
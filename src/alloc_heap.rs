@@ -0,0 +1,422 @@
+use core::{fmt, mem};
+
+use allocator_api2::alloc::{Allocator, Global};
+use allocator_api2::vec::Vec;
+#[cfg(feature = "bumpalo")]
+use bumpalo::Bump;
+
+use crate::lt;
+
+struct Node<T, A: Allocator> {
+    value: T,
+    children: Vec<Node<T, A>, A>,
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for Node<T, A> {
+    fn clone(&self) -> Self {
+        Self { value: self.value.clone(), children: self.children.clone() }
+    }
+}
+
+/// Fibonacci heap whose roots, children and scratch space all live in the allocator `A`.
+///
+/// Built on the `allocator-api2` crate, so it works on stable with its allocator trait;
+/// enabling `allocator-api2/nightly` makes that trait the standard `core::alloc::Allocator`.
+/// Heaps in different allocator types are different types, so they cannot be appended to each other.
+///
+/// Like [`FibonacciHeap`](crate::FibonacciHeap), debug builds panic on incomparable elements such as NaN,
+/// and a comparison that panics during `pop` leaves the heap empty, with every element dropped once.
+///
+/// # Example
+/// ```
+/// use allocator_api2::alloc::Global;
+/// use fibheap::AllocFibonacciHeap;
+///
+/// let mut heap = AllocFibonacciHeap::with_capacity_in(4, Global);
+/// heap.push(3);
+/// heap.push(1);
+/// assert_eq!(heap.pop(), Some(1));
+/// ```
+///
+/// Mixing allocator types doesn't compile:
+/// ```compile_fail
+/// use allocator_api2::alloc::Global;
+/// use fibheap::AllocFibonacciHeap;
+///
+/// let mut heap = AllocFibonacciHeap::<u8, Global>::new_in(Global);
+/// heap.append(AllocFibonacciHeap::<u8, &Global>::new_in(&Global));
+/// ```
+pub struct AllocFibonacciHeap<T, A: Allocator + Clone = Global> {
+    roots: Vec<Node<T, A>, A>,
+    top_index: usize,
+    len: usize,
+    // degree -> root, kept between pops so that consolidation doesn't allocate each time
+    degrees: Vec<Option<Node<T, A>>, A>,
+    alloc: A,
+}
+
+/// [`AllocFibonacciHeap`] whose storage lives in a `bumpalo` arena, for heaps that are built, used and thrown away
+/// together with everything else allocated for a frame.
+///
+/// Growing a vector in the arena is a bump of a pointer and freeing is a no-op, so a heap costs no individual frees;
+/// the memory comes back when the arena is reset or dropped. Dropping the heap still drops every element.
+///
+/// `append` accepts a heap from another arena: its trees are moved over as they are, children vectors staying in the
+/// arena that allocated them, so the borrow checker keeps both arenas alive as long as the merged heap.
+///
+/// # Example
+/// ```
+/// use bumpalo::Bump;
+/// use fibheap::BumpFibonacciHeap;
+///
+/// let (frame, other) = (Bump::new(), Bump::new());
+/// let mut heap = BumpFibonacciHeap::new_in(&frame);
+/// heap.push(3);
+/// heap.push(1);
+/// let mut more = BumpFibonacciHeap::new_in(&other);
+/// more.push(2);
+/// heap.append(more);
+/// assert_eq!(heap.into_vec().as_slice(), [1, 2, 3]);
+/// ```
+#[cfg(feature = "bumpalo")]
+pub type BumpFibonacciHeap<'bump, T> = AllocFibonacciHeap<T, &'bump Bump>;
+
+impl<T: PartialOrd> AllocFibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<T: PartialOrd, A: Allocator + Clone> AllocFibonacciHeap<T, A> {
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_capacity_in(0, alloc)
+    }
+
+    /// Creates a heap whose root list has room for `capacity` elements before reallocating.
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self {
+            roots: Vec::with_capacity_in(capacity, alloc.clone()),
+            top_index: 0,
+            len: 0,
+            degrees: Vec::new_in(alloc.clone()),
+            alloc,
+        }
+    }
+
+    /// Builds a heap from `vec`, in the allocator of `vec`.
+    pub fn from_vec_in(vec: Vec<T, A>) -> Self {
+        let mut heap = Self::with_capacity_in(vec.len(), vec.allocator().clone());
+        for value in vec {
+            heap.push(value);
+        }
+        heap
+    }
+
+    /// Pops every element into a vector in the heap's allocator, in ascending order.
+    pub fn into_vec(mut self) -> Vec<T, A> {
+        let mut vec = Vec::with_capacity_in(self.len, self.alloc.clone());
+        while let Some(value) = self.pop() {
+            vec.push(value);
+        }
+        vec
+    }
+
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over references to the elements in unspecified order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let mut stack: alloc::vec::Vec<&Node<T, A>> = self.roots.iter().collect();
+        core::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(&node.children);
+            Some(&node.value)
+        })
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.roots.get(self.top_index).map(|node| &node.value)
+    }
+
+    #[deprecated(note = "renamed to `peek` for parity with `BinaryHeap`")]
+    pub fn top(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    pub fn push(&mut self, value: T) {
+        if self.peek().is_some_and(|top| lt(&value, top)) {
+            self.top_index = self.roots.len();
+        }
+        self.roots.push(Node { value, children: Vec::new_in(self.alloc.clone()) });
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let Node { value, children } = self.roots.swap_remove(self.top_index);
+        self.roots.extend(children);
+        self.len -= 1;
+        self.consolidate();
+        Some(value)
+    }
+
+    pub fn append(&mut self, mut other: Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.peek().is_none_or(|top| lt(&other.roots[other.top_index].value, top)) {
+            self.top_index = self.roots.len() + other.top_index;
+        }
+        self.roots.append(&mut other.roots);
+        self.len += mem::take(&mut other.len);
+    }
+
+    fn consolidate(&mut self) {
+        self.clear_on_unwind(Self::link_roots);
+    }
+
+    fn link_roots(&mut self) {
+        let degrees = &mut self.degrees;
+        for mut node in self.roots.drain(..) {
+            loop {
+                let degree = node.children.len();
+                if degree >= degrees.len() {
+                    degrees.resize_with(degree + 1, || None);
+                }
+                match degrees[degree].take() {
+                    Some(mut other) => {
+                        if lt(&other.value, &node.value) {
+                            mem::swap(&mut node, &mut other);
+                        }
+                        node.children.push(other);
+                    }
+                    None => {
+                        degrees[degree] = Some(node);
+                        break;
+                    }
+                }
+            }
+        }
+        self.top_index = 0;
+        for node in degrees.iter_mut().filter_map(Option::take) {
+            if self.roots.get(self.top_index).is_some_and(|top| lt(&node.value, &top.value)) {
+                self.top_index = self.roots.len();
+            }
+            self.roots.push(node);
+        }
+    }
+
+    // Runs `f`, emptying the heap (and dropping every element once) if it unwinds, as `FibonacciHeap` does: a
+    // comparison that panics halfway through linking leaves elements in the degree table and `len` stale.
+    fn clear_on_unwind<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        struct Guard<'a, T, A: Allocator + Clone>(&'a mut AllocFibonacciHeap<T, A>);
+
+        impl<T, A: Allocator + Clone> Drop for Guard<'_, T, A> {
+            fn drop(&mut self) {
+                self.0.top_index = 0;
+                self.0.len = 0;
+                self.0.roots.clear();
+                self.0.degrees.clear();
+            }
+        }
+
+        let guard = Guard(self);
+        let result = f(guard.0);
+        mem::forget(guard);
+        result
+    }
+}
+
+/// Deep copy in a clone of the allocator, preserving the tree structure.
+impl<T: Clone, A: Allocator + Clone> Clone for AllocFibonacciHeap<T, A> {
+    fn clone(&self) -> Self {
+        Self {
+            roots: self.roots.clone(),
+            top_index: self.top_index,
+            len: self.len,
+            degrees: Vec::new_in(self.alloc.clone()),
+            alloc: self.alloc.clone(),
+        }
+    }
+}
+
+/// Prints a summary (`AllocFibonacciHeap { len: 3, top: Some(1), roots: 2, .. }`) in O(1), like
+/// [`FibonacciHeap`](crate::FibonacciHeap)'s `{:?}`.
+impl<T: fmt::Debug, A: Allocator + Clone> fmt::Debug for AllocFibonacciHeap<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AllocFibonacciHeap")
+            .field("len", &self.len)
+            .field("top", &self.roots.get(self.top_index).map(|node| &node.value))
+            .field("roots", &self.roots.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: PartialOrd> Default for AllocFibonacciHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+    use crate::FibonacciHeap;
+    use allocator_api2::alloc::AllocError;
+    use core::alloc::Layout;
+    use core::ptr::NonNull;
+    use std::cell::Cell;
+
+    // Delegates to the global allocator, counting what goes through it.
+    #[derive(Default)]
+    struct Counting {
+        allocations: Cell<usize>,
+        live: Cell<usize>,
+    }
+
+    unsafe impl Allocator for Counting {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.allocations.set(self.allocations.get() + 1);
+            self.live.set(self.live.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.live.set(self.live.get() - 1);
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    #[test]
+    fn matches_heap_in_custom_allocator() {
+        let counting = Counting::default();
+        let mut rng = Rng(0x47b5481dbefa4fa4);
+        let mut heap = AllocFibonacciHeap::new_in(&counting);
+        let mut reference = FibonacciHeap::new();
+        for _ in 0..10_000 {
+            if rng.below(3) > 0 {
+                let value = rng.below(1000);
+                heap.push(value);
+                reference.push(value);
+            } else {
+                assert_eq!(heap.pop(), reference.pop());
+            }
+            assert_eq!(heap.peek(), reference.peek());
+            assert_eq!(heap.len(), reference.len());
+        }
+        let mut values = Vec::new_in(&counting);
+        values.extend([5, 0, 7]);
+        let mut other = AllocFibonacciHeap::from_vec_in(values);
+        other.push(3);
+        heap.append(other);
+        [5, 0, 7, 3].into_iter().for_each(|value| reference.push(value));
+        assert_eq!(heap.into_vec().as_slice(), reference.into_vec());
+
+        assert!(counting.allocations.get() > 1000);
+        assert_eq!(counting.live.get(), 0);
+    }
+
+    #[cfg(feature = "bumpalo")]
+    #[test]
+    fn bump_arena() {
+        use crate::testing::DropCounter;
+        use std::rc::Rc;
+
+        let drops = Rc::new(Cell::new(0));
+        let mut arena = Bump::new();
+        for frame in 0..3 {
+            let mut heap = BumpFibonacciHeap::new_in(&arena);
+            let mut reference = FibonacciHeap::new();
+            let mut rng = Rng(0x1f83d9abfb41bd6b + frame);
+            for round in 0..2000 {
+                let value = rng.below(500) as i32;
+                heap.push(DropCounter::new(value, &drops));
+                reference.push(value);
+                if round % 3 == 0 {
+                    assert_eq!(heap.pop().map(|counter| counter.value), reference.pop());
+                }
+            }
+            assert!(arena.allocated_bytes() > 0);
+            drop(heap);
+            // every value pushed, whether popped or still queued, has been dropped
+            assert_eq!(drops.get(), 2000 * (frame as usize + 1));
+            arena.reset();
+        }
+    }
+
+    #[test]
+    fn panicking_comparison_empties_the_heap() {
+        use crate::test_util::Tracker;
+        use std::panic::{self, AssertUnwindSafe};
+
+        for fuse in [1, 2, 5, 9] {
+            let tracker = Tracker::new();
+            let mut heap = AllocFibonacciHeap::new();
+            (0..200u32).for_each(|value| heap.push(tracker.track(value * 7919 % 200)));
+            heap.pop();
+            tracker.panic_on_comparison(fuse);
+            assert!(panic::catch_unwind(AssertUnwindSafe(|| heap.pop())).is_err(), "fuse {fuse}");
+            tracker.defuse();
+            // the elements the panic caught halfway are dropped, and the heap is still usable
+            assert_eq!((heap.len(), tracker.live()), (0, 0), "fuse {fuse}");
+            assert!(heap.pop().is_none());
+            heap.push(tracker.track(1));
+            assert_eq!(heap.pop().map(|tracked| tracked.value), Some(1));
+            tracker.assert_balanced();
+        }
+    }
+
+    #[test]
+    fn incomparable_elements() {
+        let build = || {
+            let mut heap = AllocFibonacciHeap::new();
+            [2.0, f64::NAN, 1.0].into_iter().for_each(|value| heap.push(value));
+            heap
+        };
+        if cfg!(debug_assertions) {
+            let payload = std::panic::catch_unwind(build).unwrap_err();
+            assert_eq!(payload.downcast_ref(), Some(&"fibheap: incomparable elements, such as NaN, are not supported"));
+        } else {
+            assert_eq!(build().len(), 3);
+        }
+    }
+
+    #[test]
+    fn iter_clone_and_debug() {
+        let mut heap = AllocFibonacciHeap::new();
+        (0..100).rev().for_each(|value| heap.push(value));
+        heap.pop();
+        let mut values: std::vec::Vec<_> = heap.iter().copied().collect();
+        values.sort_unstable();
+        assert_eq!(values, (1..100).collect::<std::vec::Vec<_>>());
+        assert_eq!(format!("{heap:?}"), format!("AllocFibonacciHeap {{ len: 99, top: Some(1), roots: {}, .. }}", heap.roots.len()));
+        let copy = heap.clone();
+        assert_eq!(copy.into_vec(), heap.into_vec());
+    }
+
+    #[test]
+    fn append_into_empty() {
+        let mut heap = AllocFibonacciHeap::new();
+        heap.append(AllocFibonacciHeap::new());
+        assert!(heap.is_empty());
+        let mut other = AllocFibonacciHeap::new();
+        other.push(2);
+        other.push(1);
+        heap.append(other);
+        assert_eq!(heap.peek(), Some(&1));
+        heap.push(0);
+        assert_eq!(heap.into_vec().as_slice(), [0, 1, 2]);
+    }
+}
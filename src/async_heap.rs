@@ -0,0 +1,306 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+
+use futures_core::Stream;
+
+use crate::FibonacciHeap;
+
+struct State<T> {
+    heap: FibonacciHeap<T>,
+    // Pending pops in arrival order; a push wakes (and removes) exactly the front one.
+    waiters: VecDeque<(u64, Waker)>,
+    next_waiter: u64,
+    closed: bool,
+}
+
+impl<T> State<T> {
+    fn take_waiter(&mut self) -> Option<Waker> {
+        self.waiters.pop_front().map(|(_, waker)| waker)
+    }
+
+    // Forgets a pending pop that is going away without taking an element.
+    fn cancel(&mut self, waiter: Option<u64>) -> Option<Waker> {
+        let id = waiter?;
+        match self.waiters.iter().position(|&(w, _)| w == id) {
+            Some(pos) => {
+                self.waiters.remove(pos);
+                None
+            }
+            // It was already woken for an element it will never take, so pass the wakeup on.
+            None => self.take_waiter(),
+        }
+    }
+}
+
+impl<T: PartialOrd> State<T> {
+    fn poll_pop(&mut self, waiter: &mut Option<u64>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let value = self.heap.pop();
+        if value.is_some() || self.closed {
+            if let Some(id) = waiter.take() {
+                self.waiters.retain(|&(w, _)| w != id);
+            }
+            return Poll::Ready(value);
+        }
+        match waiter.and_then(|id| self.waiters.iter_mut().find(|(w, _)| *w == id)) {
+            Some((_, waker)) => waker.clone_from(cx.waker()),
+            None => {
+                let id = self.next_waiter;
+                self.next_waiter += 1;
+                self.waiters.push_back((id, cx.waker().clone()));
+                *waiter = Some(id);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+/// Heap shared between tasks, whose pops wait asynchronously for elements.
+///
+/// Every clone refers to the same heap and [`push`](Self::push) may be called from any task or thread;
+/// handles are `Send` and `Sync` when `T` is `Send`.
+/// Each push wakes exactly one pending pop, in the order the pops started waiting.
+/// The handle itself is a [`Stream`] of popped elements, which ends once the heap is closed and drained.
+///
+/// # Example
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::StreamExt;
+/// use fibheap::AsyncFibonacciHeap;
+///
+/// let mut jobs = AsyncFibonacciHeap::new();
+/// for job in [3, 1, 2] {
+///     jobs.push(job);
+/// }
+/// jobs.close();
+/// let mut done = vec![];
+/// while let Some(job) = jobs.next().await {
+///     done.push(job);
+/// }
+/// assert_eq!(done, [1, 2, 3]);
+/// # });
+/// ```
+pub struct AsyncFibonacciHeap<T> {
+    state: Arc<Mutex<State<T>>>,
+    // registration of this handle's own `Stream` polling
+    waiter: Option<u64>,
+}
+
+impl<T: PartialOrd> AsyncFibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self::from(FibonacciHeap::new())
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().heap.is_empty()
+    }
+
+    pub fn push(&self, value: T) {
+        let mut state = self.lock();
+        state.heap.push(value);
+        let waker = state.take_waiter();
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    pub fn try_pop(&self) -> Option<T> {
+        self.lock().heap.pop()
+    }
+
+    /// Pops the minimum, waiting until an element is available; resolves to `None` once closed and empty.
+    pub fn pop(&self) -> Pop<'_, T> {
+        Pop { heap: self, waiter: None }
+    }
+
+    /// Wakes every pending pop; from then on pops stop waiting and resolve to `None` when the heap is empty.
+    pub fn close(&self) {
+        let mut state = self.lock();
+        state.closed = true;
+        let waiters = std::mem::take(&mut state.waiters);
+        drop(state);
+        waiters.into_iter().for_each(|(_, waker)| waker.wake());
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap()
+    }
+}
+
+impl<T> Clone for AsyncFibonacciHeap<T> {
+    fn clone(&self) -> Self {
+        Self { state: Arc::clone(&self.state), waiter: None }
+    }
+}
+
+impl<T> Drop for AsyncFibonacciHeap<T> {
+    fn drop(&mut self) {
+        cancel(&self.state, self.waiter);
+    }
+}
+
+impl<T: PartialOrd> Default for AsyncFibonacciHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialOrd> From<FibonacciHeap<T>> for AsyncFibonacciHeap<T> {
+    fn from(heap: FibonacciHeap<T>) -> Self {
+        let state = State { heap, waiters: VecDeque::new(), next_waiter: 0, closed: false };
+        Self { state: Arc::new(Mutex::new(state)), waiter: None }
+    }
+}
+
+impl<T: PartialOrd> Stream for AsyncFibonacciHeap<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let this = self.get_mut();
+        this.state.lock().unwrap().poll_pop(&mut this.waiter, cx)
+    }
+}
+
+/// Future returned by [`AsyncFibonacciHeap::pop`].
+pub struct Pop<'a, T> {
+    heap: &'a AsyncFibonacciHeap<T>,
+    waiter: Option<u64>,
+}
+
+impl<T: PartialOrd> Future for Pop<'_, T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let this = self.get_mut();
+        this.heap.lock().poll_pop(&mut this.waiter, cx)
+    }
+}
+
+impl<T> fmt::Debug for Pop<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pop").field("waiting", &self.waiter.is_some()).finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for Pop<'_, T> {
+    fn drop(&mut self) {
+        cancel(&self.heap.state, self.waiter);
+    }
+}
+
+fn cancel<T>(state: &Mutex<State<T>>, waiter: Option<u64>) {
+    if waiter.is_none() {
+        return;
+    }
+    // Never panic in drop over a poisoned lock.
+    let waker = match state.lock() {
+        Ok(mut state) => state.cancel(waiter),
+        Err(_) => None,
+    };
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{poll, StreamExt};
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn pop_waits_for_push() {
+        let heap = AsyncFibonacciHeap::new();
+        let consumer = tokio::spawn({
+            let heap = heap.clone();
+            async move { heap.pop().await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        heap.push(7);
+        assert_eq!(consumer.await.unwrap(), Some(7));
+    }
+
+    #[tokio::test]
+    async fn close_ends_waiters_and_stream() {
+        let mut heap = AsyncFibonacciHeap::<i32>::new();
+        let waiters: Vec<_> = (0..3)
+            .map(|_| {
+                let heap = heap.clone();
+                tokio::spawn(async move { heap.pop().await })
+            })
+            .collect();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        heap.close();
+        for waiter in waiters {
+            assert_eq!(waiter.await.unwrap(), None);
+        }
+        assert_eq!(heap.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn cancelled_waiter_passes_wakeup_on() {
+        let heap = AsyncFibonacciHeap::new();
+        let mut first = Box::pin(heap.pop());
+        assert!(poll!(first.as_mut()).is_pending());
+        let second = tokio::spawn({
+            let heap = heap.clone();
+            async move { heap.pop().await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // The push wakes `first`, which is then dropped without taking the element.
+        heap.push(1);
+        drop(first);
+        assert_eq!(timeout(Duration::from_secs(5), second).await.unwrap().unwrap(), Some(1));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn producers_and_stream_consumer() {
+        let mut heap = AsyncFibonacciHeap::new();
+        // While no producer is running, the stream yields in priority order.
+        for value in (0..1000).rev() {
+            heap.push(value);
+        }
+        for expected in 0..1000 {
+            assert_eq!(heap.next().await, Some(expected));
+        }
+
+        let producers: Vec<_> = (0..4)
+            .map(|p| {
+                let heap = heap.clone();
+                tokio::spawn(async move {
+                    for i in 0..1000 {
+                        heap.push(i * 4 + p);
+                        tokio::task::yield_now().await;
+                    }
+                })
+            })
+            .collect();
+        let closer = tokio::spawn({
+            let heap = heap.clone();
+            async move {
+                for producer in producers {
+                    producer.await.unwrap();
+                }
+                heap.close();
+            }
+        });
+
+        let mut received = vec![];
+        while let Some(value) = heap.next().await {
+            received.push(value);
+        }
+        closer.await.unwrap();
+        received.sort_unstable();
+        assert_eq!(received, (0..4000).collect::<Vec<_>>());
+    }
+}
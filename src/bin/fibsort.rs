@@ -0,0 +1,165 @@
+// Sorts lines like `sort`, through the heaps of this crate.
+//
+//     fibsort [--numeric] [--reverse] [--unique] [--top-k N] [FILE]
+//
+// Lines are read from FILE, or from stdin without one, and written out in ascending order:
+// - `--numeric` compares lines as numbers (lines that parse to the same number keep their text and are ordered by it),
+// - `--reverse` writes them in descending order,
+// - `--unique` writes each distinct line once,
+// - `--top-k N` writes only the first N lines of the output, holding no more than N of them while reading
+//   (unless combined with `--unique`).
+use std::cmp::{Ordering, Reverse};
+use std::fmt;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::process::ExitCode;
+
+use fibheap::{CountedFibonacciHeap, FibonacciHeap, GroupedTopK, Retain};
+
+const USAGE: &str = "usage: fibsort [--numeric] [--reverse] [--unique] [--top-k N] [FILE]";
+
+#[derive(Default)]
+struct Options {
+    numeric: bool,
+    reverse: bool,
+    unique: bool,
+    top_k: Option<usize>,
+    file: Option<String>,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--numeric" | "-n" => options.numeric = true,
+                "--reverse" | "-r" => options.reverse = true,
+                "--unique" | "-u" => options.unique = true,
+                "--top-k" | "-k" => {
+                    let n = args.next().ok_or("--top-k needs a number")?;
+                    options.top_k = Some(n.parse().map_err(|_| format!("invalid --top-k: {n}"))?);
+                }
+                "--help" | "-h" => return Err(USAGE.to_owned()),
+                _ if arg.starts_with('-') && arg != "-" => return Err(format!("unknown option {arg}\n{USAGE}")),
+                _ if options.file.is_none() => options.file = Some(arg),
+                _ => return Err(format!("more than one input file\n{USAGE}")),
+            }
+        }
+        Ok(options)
+    }
+}
+
+// A line compared by the number it holds, then by its text.
+#[derive(Clone)]
+struct Number {
+    value: f64,
+    text: String,
+}
+
+impl Number {
+    fn parse(text: String) -> Result<Self, String> {
+        match text.trim().parse() {
+            Ok(value) => Ok(Number { value, text }),
+            Err(_) => Err(format!("not a number: {text:?}")),
+        }
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.total_cmp(&other.value).then_with(|| self.text.cmp(&other.text))
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Number {}
+
+// Equal numbers have equal texts, so the text alone is a consistent hash.
+impl Hash for Number {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.text.hash(state);
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+// Drains a heap holding every line, or each distinct line once.
+fn drain<T: Ord + Hash + Clone + 'static>(lines: Vec<T>, unique: bool) -> Box<dyn Iterator<Item = T>> {
+    if unique {
+        let mut heap = CountedFibonacciHeap::new();
+        lines.into_iter().for_each(|line| heap.push(line));
+        Box::new(std::iter::from_fn(move || heap.pop_all_min().map(|(line, _)| line)))
+    } else {
+        Box::new(FibonacciHeap::from_vec(lines))
+    }
+}
+
+fn run<T: Ord + Hash + Clone + fmt::Display + 'static>(
+    lines: impl Iterator<Item = Result<T, String>>,
+    options: &Options,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let write_err = |err: io::Error| err.to_string();
+    match options.top_k {
+        Some(k) if !options.unique => {
+            let mut top = GroupedTopK::new(k, if options.reverse { Retain::Largest } else { Retain::Smallest });
+            for line in lines {
+                top.insert((), line?);
+            }
+            for line in top.into_iter().flat_map(|(_, lines)| lines) {
+                writeln!(out, "{line}").map_err(write_err)?;
+            }
+        }
+        _ => {
+            let lines = lines.collect::<Result<Vec<T>, String>>()?;
+            let sorted: Box<dyn Iterator<Item = T>> = if options.reverse {
+                Box::new(drain(lines.into_iter().map(Reverse).collect(), options.unique).map(|Reverse(line)| line))
+            } else {
+                drain(lines, options.unique)
+            };
+            for line in sorted.take(options.top_k.unwrap_or(usize::MAX)) {
+                writeln!(out, "{line}").map_err(write_err)?;
+            }
+        }
+    }
+    out.flush().map_err(write_err)
+}
+
+fn main() -> ExitCode {
+    let result = Options::parse(std::env::args().skip(1)).and_then(|options| {
+        let input: Box<dyn BufRead> = match options.file.as_deref() {
+            None | Some("-") => Box::new(io::stdin().lock()),
+            Some(path) => Box::new(BufReader::new(File::open(path).map_err(|err| format!("{path}: {err}"))?)),
+        };
+        let lines = input.lines().map(|line| line.map_err(|err| err.to_string()));
+        let mut out = BufWriter::new(io::stdout().lock());
+        if options.numeric {
+            run(lines.map(|line| line.and_then(Number::parse)), &options, &mut out)
+        } else {
+            run(lines, &options, &mut out)
+        }
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("fibsort: {message}");
+            ExitCode::from(2)
+        }
+    }
+}
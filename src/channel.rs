@@ -0,0 +1,298 @@
+use std::fmt;
+use std::sync::mpsc::{RecvError, RecvTimeoutError, SendError, TryRecvError};
+use std::sync::PoisonError;
+use std::time::{Duration, Instant};
+
+use crate::lock::{self, Arc, Condvar, Mutex, MutexGuard};
+use crate::FibonacciHeap;
+
+struct State<T> {
+    heap: FibonacciHeap<T>,
+    senders: usize,
+    receiver_alive: bool,
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    available: Condvar,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap()
+    }
+
+    // For the drops, which also run while a panicking comparison unwinds out of `send` and must not panic again
+    // over the lock it poisoned.
+    fn lock_in_drop(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Creates a multi-producer, single-consumer channel whose receiver always yields the smallest pending value.
+///
+/// The error types are those of [`std::sync::mpsc`], with the same disconnect semantics:
+/// once every [`Sender`] is dropped, `recv` drains the remaining values and then fails with [`RecvError`].
+/// Both halves are `Send` and `Sync` when `T` is `Send`.
+///
+/// # Example
+/// ```
+/// use std::thread;
+///
+/// let (tx, rx) = fibheap::priority_channel();
+/// thread::spawn(move || {
+///     for job in [3, 1, 2] {
+///         tx.send(job).unwrap();
+///     }
+/// })
+/// .join()
+/// .unwrap();
+/// assert_eq!(rx.iter().collect::<Vec<_>>(), [1, 2, 3]);
+/// ```
+pub fn priority_channel<T: PartialOrd>() -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State { heap: FibonacciHeap::new(), senders: 1, receiver_alive: true }),
+        available: Condvar::new(),
+    });
+    (Sender { shared: Arc::clone(&shared) }, Receiver { shared })
+}
+
+/// Sending half of a [`priority_channel`]; clone it to send from several threads.
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: PartialOrd> Sender<T> {
+    /// Queues `value`, handing it back if the receiver has been dropped.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut state = self.shared.lock();
+        if !state.receiver_alive {
+            return Err(SendError(value));
+        }
+        state.heap.push(value);
+        drop(state);
+        self.shared.available.notify_one();
+        Ok(())
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Self { shared: Arc::clone(&self.shared) }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock_in_drop();
+        state.senders -= 1;
+        if state.senders == 0 {
+            drop(state);
+            self.shared.available.notify_all();
+        }
+    }
+}
+
+/// Receiving half of a [`priority_channel`].
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: PartialOrd> Receiver<T> {
+    /// Blocks until a value is pending and returns the smallest one.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(value) = state.heap.pop() {
+                return Ok(value);
+            }
+            if state.senders == 0 {
+                return Err(RecvError);
+            }
+            state = self.shared.available.wait(state).unwrap();
+        }
+    }
+
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut state = self.shared.lock();
+        match state.heap.pop() {
+            Some(value) => Ok(value),
+            None if state.senders == 0 => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Like [`recv`](Self::recv), but gives up after `timeout`; a timeout too large to be a deadline waits forever.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let Some(deadline) = Instant::now().checked_add(timeout) else {
+            return self.recv().map_err(|RecvError| RecvTimeoutError::Disconnected);
+        };
+        let mut state = self.shared.lock();
+        let mut timed_out = false;
+        loop {
+            if let Some(value) = state.heap.pop() {
+                return Ok(value);
+            }
+            if state.senders == 0 {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            let now = Instant::now();
+            if timed_out || now >= deadline {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            let shared = &*self.shared;
+            (state, timed_out) = lock::wait_timeout(&shared.available, &shared.state, state, deadline - now);
+        }
+    }
+
+    /// Blocking iterator that ends once every sender is dropped and the channel is drained.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(move || self.recv().ok())
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.lock_in_drop().receiver_alive = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn ordered_after_producers_finish() {
+        let (tx, rx) = priority_channel();
+        let producers: Vec<_> = (0..4)
+            .map(|p| {
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for i in 0..1000 {
+                        tx.send(i * 4 + p).unwrap();
+                    }
+                })
+            })
+            .collect();
+        drop(tx);
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        for expected in 0..4000 {
+            assert_eq!(rx.recv(), Ok(expected));
+        }
+        assert_eq!(rx.recv(), Err(RecvError));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+    }
+
+    #[test]
+    fn concurrent_producers_and_consumer() {
+        let (tx, rx) = priority_channel();
+        let producers: Vec<_> = (0..4)
+            .map(|p| {
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for i in 0..1000 {
+                        tx.send(i * 4 + p).unwrap();
+                    }
+                })
+            })
+            .collect();
+        drop(tx);
+
+        let mut received: Vec<i32> = rx.iter().collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        // Each producer sends in increasing order, so its own values can never come out of order.
+        for p in 0..4 {
+            let own: Vec<_> = received.iter().filter(|&&v| v % 4 == p).collect();
+            assert!(own.windows(2).all(|w| w[0] < w[1]));
+        }
+        received.sort_unstable();
+        assert_eq!(received, (0..4000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn recv_blocks_until_send() {
+        let (tx, rx) = priority_channel();
+        let consumer = thread::spawn(move || rx.recv());
+        thread::sleep(Duration::from_millis(20));
+        tx.send(7).unwrap();
+        assert_eq!(consumer.join().unwrap(), Ok(7));
+    }
+
+    #[test]
+    fn timeouts_and_disconnect() {
+        let (tx, rx) = priority_channel::<i32>();
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+        assert_eq!(rx.recv_timeout(Duration::from_millis(10)), Err(RecvTimeoutError::Timeout));
+
+        let waiter = thread::spawn(move || rx.recv_timeout(Duration::from_secs(60)));
+        thread::sleep(Duration::from_millis(20));
+        drop(tx);
+        assert_eq!(waiter.join().unwrap(), Err(RecvTimeoutError::Disconnected));
+    }
+
+    #[test]
+    fn unbounded_timeout() {
+        let (tx, rx) = priority_channel();
+        tx.send(2).unwrap();
+        assert_eq!(rx.recv_timeout(Duration::MAX), Ok(2));
+
+        let sender = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            tx.send(1).unwrap();
+        });
+        assert_eq!(rx.recv_timeout(Duration::MAX), Ok(1));
+        sender.join().unwrap();
+        assert_eq!(rx.recv_timeout(Duration::MAX), Err(RecvTimeoutError::Disconnected));
+    }
+
+    #[test]
+    fn drops_after_a_comparison_panicked() {
+        use std::cmp::Ordering;
+        use std::panic::{self, AssertUnwindSafe};
+
+        #[derive(PartialEq)]
+        struct Bomb(bool);
+
+        impl PartialOrd for Bomb {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                assert!(!self.0 && !other.0, "comparison failed");
+                Some(Ordering::Equal)
+            }
+        }
+
+        let (tx, rx) = priority_channel();
+        tx.send(Bomb(false)).unwrap();
+        let sender = tx.clone();
+        // the sender is dropped while the panic unwinds, with the lock poisoned
+        let result = panic::catch_unwind(AssertUnwindSafe(move || sender.send(Bomb(true))));
+        assert!(result.is_err());
+        drop(tx);
+        drop(rx);
+    }
+
+    #[test]
+    fn send_after_receiver_dropped() {
+        let (tx, rx) = priority_channel();
+        tx.send(1).unwrap();
+        drop(rx);
+        assert_eq!(tx.send(2), Err(SendError(2)));
+    }
+}
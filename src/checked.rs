@@ -0,0 +1,399 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Add, AddAssign};
+
+use crate::{FibonacciHeap, Heap, HeapMemoryUsage, Iter, IterSorted};
+
+// Elements of the reference listed in a failure report.
+const REPORT_LEN: usize = 16;
+
+/// [`FibonacciHeap`] that checks itself after every operation, for bug hunts.
+///
+/// Each operation is mirrored into a plain sorted `Vec` of clones of the elements. The wrapper panics with a report
+/// as soon as a result, `peek` or `len` disagrees with that reference, or [`validate`](FibonacciHeap::validate) fails.
+/// Each operation then costs O(n) on top of its usual cost. The methods have the same names and signatures as those of
+/// `FibonacciHeap`, so a type alias can switch between the two:
+/// ```
+/// # use fibheap::*;
+/// type Queue<T> = CheckedFibonacciHeap<T>; // FibonacciHeap<T> outside bug hunts
+///
+/// let mut queue: Queue<u32> = Queue::new();
+/// queue.push(2);
+/// queue.push(1);
+/// assert_eq!(queue.pop(), Some(1));
+/// ```
+///
+/// The elements must also be `Clone` and `Debug`. Elements that compare as equal count as the same element,
+/// whichever of them the heap hands out. Without the `paranoid` feature this type doesn't exist
+/// and [`FibonacciHeap`] does no checking beyond its debug assertions.
+pub struct CheckedFibonacciHeap<T> {
+    heap: FibonacciHeap<T>,
+    // clones of the elements, largest first so that the minimum pops off the end
+    model: Vec<T>,
+}
+
+impl<T> CheckedFibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { heap: FibonacciHeap::with_capacity(capacity), model: Vec::with_capacity(capacity) }
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.heap.reserve(additional);
+        self.model.reserve(additional);
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.heap.iter()
+    }
+
+    pub fn root_count(&self) -> usize {
+        self.heap.root_count()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+
+    #[deprecated(note = "renamed to `peek` for parity with `BinaryHeap`")]
+    pub fn top(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.model.clear();
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.heap.shrink_to_fit();
+    }
+
+    pub fn set_max_links_per_pop(&mut self, max: Option<usize>) {
+        self.heap.set_max_links_per_pop(max);
+    }
+
+    pub fn max_links_per_pop(&self) -> Option<usize> {
+        self.heap.max_links_per_pop()
+    }
+
+    pub fn eager_consolidation(&self) -> bool {
+        self.heap.eager_consolidation()
+    }
+
+    /// Memory used by the heap itself, leaving out the reference copy.
+    pub fn memory_usage(&self) -> HeapMemoryUsage {
+        self.heap.memory_usage()
+    }
+
+    /// The checked heap.
+    pub fn heap(&self) -> &FibonacciHeap<T> {
+        &self.heap
+    }
+
+    pub fn into_inner(self) -> FibonacciHeap<T> {
+        self.heap
+    }
+}
+
+impl<T: PartialOrd + Clone + fmt::Debug> CheckedFibonacciHeap<T> {
+    /// Wraps `heap`, taking its current elements as the reference.
+    pub fn from_heap(heap: FibonacciHeap<T>) -> Self {
+        let mut model: Vec<T> = heap.iter().cloned().collect();
+        model.sort_by(|a, b| b.partial_cmp(a).expect("fibheap: incomparable elements, such as NaN, are not supported"));
+        let checked = Self { heap, model };
+        checked.check("from_heap");
+        checked
+    }
+
+    pub fn from_vec(vec: Vec<T>) -> Self {
+        Self::from_heap(FibonacciHeap::from_vec(vec))
+    }
+
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        let Self { heap, mut model } = self;
+        let sorted = heap.into_sorted_vec();
+        model.reverse();
+        if sorted.len() != model.len() || sorted.iter().zip(&model).any(|(value, expected)| !same(Some(value), Some(expected))) {
+            panic!("fibheap paranoid check failed after `into_sorted_vec`: returned {sorted:?}, expected {model:?}");
+        }
+        sorted
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.heap.into_vec()
+    }
+
+    pub fn iter_sorted(&self) -> IterSorted<'_, T> {
+        self.heap.iter_sorted()
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.model_push(value.clone());
+        self.heap.push(value);
+        self.check("push");
+    }
+
+    pub fn bulk_push(&mut self, items: impl IntoIterator<Item = T>) {
+        let items: Vec<T> = items.into_iter().collect();
+        items.iter().for_each(|value| self.model_push(value.clone()));
+        self.heap.bulk_push(items);
+        self.check("bulk_push");
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.heap.pop();
+        let expected = self.model.pop();
+        self.check_result("pop", value.as_ref(), expected.as_ref());
+        self.check("pop");
+        value
+    }
+
+    pub fn push_pop(&mut self, value: T) -> T {
+        self.model_push(value.clone());
+        let expected = self.model.pop();
+        let value = self.heap.push_pop(value);
+        self.check_result("push_pop", Some(&value), expected.as_ref());
+        self.check("push_pop");
+        value
+    }
+
+    pub fn replace_top(&mut self, value: T) -> Option<T> {
+        let expected = self.model.pop();
+        self.model_push(value.clone());
+        let value = self.heap.replace_top(value);
+        self.check_result("replace_top", value.as_ref(), expected.as_ref());
+        self.check("replace_top");
+        value
+    }
+
+    /// Runs [`FibonacciHeap::reduce_pairwise`] unchecked: its result depends on the order of the merges.
+    pub fn reduce_pairwise<F: FnMut(T, T) -> T>(self, combine: F) -> Option<T> {
+        self.heap.reduce_pairwise(combine)
+    }
+
+    pub fn append(&mut self, other: Self) {
+        self.heap.append(other.heap);
+        other.model.into_iter().for_each(|value| self.model_push(value));
+        self.check("append");
+    }
+
+    pub fn into_forest(self) -> Vec<Self> {
+        self.heap.into_forest().into_iter().map(Self::from_heap).collect()
+    }
+
+    /// Checks that the split keeps every element, then each part on its own.
+    pub fn split_forest(&mut self, n: usize) -> Vec<Self> {
+        let parts: Vec<Self> = self.heap.split_forest(n).into_iter().map(Self::from_heap).collect();
+        for part in &parts {
+            for value in part.model.iter().rev() {
+                match self.model.iter().rposition(|other| same(Some(other), Some(value))) {
+                    Some(ix) => drop(self.model.remove(ix)),
+                    None => self.fail("split_forest", format!("split off {value:?}, which is not in the heap")),
+                }
+            }
+        }
+        self.check("split_forest");
+        parts
+    }
+
+    pub fn consolidate(&mut self) {
+        self.heap.consolidate();
+        self.check("consolidate");
+    }
+
+    pub fn set_eager_consolidation(&mut self, eager: bool) {
+        self.heap.set_eager_consolidation(eager);
+        self.check("set_eager_consolidation");
+    }
+
+    /// Applies `f` through [`FibonacciHeap::modify_all`], then takes the modified elements as the new reference,
+    /// since the calls to `f` can't be replayed on it.
+    pub fn modify_all<F: FnMut(&mut T)>(&mut self, f: F) {
+        self.heap.modify_all(f);
+        let heap = core::mem::take(&mut self.heap);
+        *self = Self::from_heap(heap);
+    }
+
+    fn model_push(&mut self, value: T) {
+        let ix = self.model.partition_point(|other| other > &value);
+        self.model.insert(ix, value);
+    }
+
+    fn check_result(&self, op: &str, value: Option<&T>, expected: Option<&T>) {
+        if !same(value, expected) {
+            self.fail(op, format!("returned {value:?}, expected {expected:?}"));
+        }
+    }
+
+    // Compares the heap with the reference and validates it.
+    fn check(&self, op: &str) {
+        if let Err(violation) = self.heap.validate() {
+            self.fail(op, format!("invalid heap: {violation}"));
+        }
+        if self.heap.len() != self.model.len() {
+            self.fail(op, format!("len is {}, expected {}", self.heap.len(), self.model.len()));
+        }
+        if !same(self.heap.peek(), self.model.last()) {
+            self.fail(op, format!("top is {:?}, expected {:?}", self.heap.peek(), self.model.last()));
+        }
+    }
+
+    fn fail(&self, op: &str, what: String) -> ! {
+        let smallest: Vec<&T> = self.model.iter().rev().take(REPORT_LEN).collect();
+        let more = if self.model.len() > REPORT_LEN { ", ..." } else { "" };
+        panic!(
+            "fibheap paranoid check failed after `{op}`: {what}\n  len: {} (reference {})\n  top: {:?} (reference {:?})\n  reference, smallest first: {smallest:?}{more}\n  heap: {:?}",
+            self.heap.len(),
+            self.model.len(),
+            self.heap.peek(),
+            self.model.last(),
+            self.heap,
+        );
+    }
+}
+
+// Whether both are `None`, or elements that compare as equal.
+fn same<T: PartialOrd>(a: Option<&T>, b: Option<&T>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.partial_cmp(b) == Some(Ordering::Equal),
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
+impl<T: PartialOrd + Clone + fmt::Debug> Iterator for CheckedFibonacciHeap<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.pop()
+    }
+}
+
+impl<T: PartialOrd + Clone + fmt::Debug> Add for CheckedFibonacciHeap<T> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self.append(other);
+        self
+    }
+}
+
+impl<T: PartialOrd + Clone + fmt::Debug> AddAssign for CheckedFibonacciHeap<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.append(other);
+    }
+}
+
+impl<T: PartialOrd + Clone + fmt::Debug> Add<Vec<T>> for CheckedFibonacciHeap<T> {
+    type Output = Self;
+
+    fn add(mut self, values: Vec<T>) -> Self {
+        self.bulk_push(values);
+        self
+    }
+}
+
+impl<T: PartialOrd + Clone + fmt::Debug> AddAssign<Vec<T>> for CheckedFibonacciHeap<T> {
+    fn add_assign(&mut self, values: Vec<T>) {
+        self.bulk_push(values);
+    }
+}
+
+impl<T: PartialOrd + Clone + fmt::Debug> Heap<T> for CheckedFibonacciHeap<T> {
+    fn push(&mut self, value: T) {
+        CheckedFibonacciHeap::push(self, value)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        CheckedFibonacciHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        CheckedFibonacciHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        CheckedFibonacciHeap::len(self)
+    }
+
+    fn append(&mut self, other: Self) {
+        CheckedFibonacciHeap::append(self, other)
+    }
+}
+
+impl<T: Clone> Clone for CheckedFibonacciHeap<T> {
+    fn clone(&self) -> Self {
+        Self { heap: self.heap.clone(), model: self.model.clone() }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CheckedFibonacciHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.heap, f)
+    }
+}
+
+impl<T> Default for CheckedFibonacciHeap<T> {
+    fn default() -> Self {
+        Self { heap: FibonacciHeap::new(), model: Vec::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn mirrors_a_random_workload() {
+        let mut rng = Rng(0x9b05688c2b3e6c1f);
+        let mut heap = CheckedFibonacciHeap::new();
+        for round in 0..5_000 {
+            match rng.below(10) {
+                0..=4 => heap.push(rng.below(100)),
+                5 | 6 => drop(heap.pop()),
+                7 => drop(heap.push_pop(rng.below(100))),
+                8 => heap.append(CheckedFibonacciHeap::from_vec((0..rng.below(20)).map(|_| rng.below(100)).collect())),
+                _ => heap.bulk_push((0..rng.below(20)).map(|_| rng.below(100))),
+            }
+            if round == 2_500 {
+                heap.set_eager_consolidation(true);
+            }
+        }
+        let mut parts = heap.split_forest(3);
+        parts.push(heap);
+        let mut merged = parts.into_iter().reduce(|a, b| a + b).unwrap();
+        merged.modify_all(|value| *value = 1000 - *value);
+        merged.into_sorted_vec();
+    }
+
+    #[test]
+    #[should_panic(expected = "fibheap paranoid check failed after `push`: invalid heap: root 1 is smaller than the top at root 3")]
+    fn reports_a_corrupted_heap() {
+        // changing a queued key behind the heap's back breaks its order
+        #[derive(Debug, Clone, PartialEq, PartialOrd)]
+        struct Key(Rc<Cell<u32>>);
+
+        let mut heap = CheckedFibonacciHeap::new();
+        let keys: Vec<Key> = (1..=3).map(|key| Key(Rc::new(Cell::new(key)))).collect();
+        keys.iter().for_each(|key| heap.push(key.clone()));
+        keys[0].0.set(10);
+        heap.push(Key(Rc::new(Cell::new(4))));
+    }
+}
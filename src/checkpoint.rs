@@ -0,0 +1,264 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::mem;
+
+use crate::FibonacciHeap;
+
+/// Checkpoint of a [`CheckpointFibonacciHeap`], returned by [`checkpoint`](CheckpointFibonacciHeap::checkpoint).
+///
+/// Checkpoints of one heap are ordered by when they were taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CheckpointId(u64);
+
+// How to undo one change: remove an element equal to a pushed one, or push a popped one back.
+#[derive(Debug)]
+enum Undo<T> {
+    Pushed(T),
+    Popped(T),
+}
+
+/// [`FibonacciHeap`] that can roll back to earlier checkpoints, for speculative bursts of changes that are often
+/// abandoned.
+///
+/// While a checkpoint is active, every change is logged with its inverse: pushed elements are cloned so that an
+/// equal element can be removed again, and popped ones so that they can be pushed back. Rolling back replays the
+/// inverses, newest first; it costs O(1) per undone pop and O(n) per undone push, which has to be searched for.
+/// Without active checkpoints nothing is logged or cloned.
+///
+/// Checkpoints nest: rolling back to or committing an outer checkpoint also ends every checkpoint taken after it.
+/// Committing the outermost one drops the log.
+///
+/// # Example
+/// ```
+/// use fibheap::CheckpointFibonacciHeap;
+///
+/// let mut heap = CheckpointFibonacciHeap::new();
+/// heap.push(3);
+/// let before = heap.checkpoint();
+/// heap.push(1);
+/// assert_eq!(heap.pop(), Some(1));
+/// assert_eq!(heap.pop(), Some(3));
+/// assert!(heap.rollback(before));
+/// assert_eq!(heap.heap().iter().collect::<Vec<_>>(), [&3]);
+/// ```
+#[derive(Debug)]
+pub struct CheckpointFibonacciHeap<T> {
+    heap: FibonacciHeap<T>,
+    log: Vec<Undo<T>>,
+    // active checkpoints, oldest first, with the length of the log when each was taken
+    checkpoints: Vec<(CheckpointId, usize)>,
+    next_id: u64,
+}
+
+impl<T: PartialOrd + Clone> CheckpointFibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+
+    /// The underlying heap, for read-only access such as iteration.
+    pub fn heap(&self) -> &FibonacciHeap<T> {
+        &self.heap
+    }
+
+    /// Number of changes logged for the active checkpoints.
+    pub fn log_len(&self) -> usize {
+        self.log.len()
+    }
+
+    /// Starts logging changes so that the heap can be rolled back to its current contents.
+    pub fn checkpoint(&mut self) -> CheckpointId {
+        let id = CheckpointId(self.next_id);
+        self.next_id += 1;
+        self.checkpoints.push((id, self.log.len()));
+        id
+    }
+
+    /// Restores the contents the heap had at checkpoint `id`, ending it and every later one.
+    ///
+    /// Returns `false`, changing nothing, if `id` isn't active (it was rolled back or committed already).
+    pub fn rollback(&mut self, id: CheckpointId) -> bool {
+        let Some(ix) = self.checkpoints.iter().position(|&(active, _)| active == id) else {
+            return false;
+        };
+        let start = self.checkpoints[ix].1;
+        self.checkpoints.truncate(ix);
+        for undo in self.log.drain(start..).rev() {
+            match undo {
+                Undo::Pushed(value) => {
+                    let removed = self.heap.remove_by(|x| x.partial_cmp(&value));
+                    debug_assert!(removed.is_some(), "fibheap: pushed element missing on rollback");
+                }
+                Undo::Popped(value) => self.heap.push(value),
+            }
+        }
+        true
+    }
+
+    /// Keeps the changes made since checkpoint `id`, ending it and every later one.
+    ///
+    /// Returns `false` if `id` isn't active. The log is only dropped once no checkpoint is left, since an earlier
+    /// checkpoint can still roll the same changes back.
+    pub fn commit(&mut self, id: CheckpointId) -> bool {
+        let Some(ix) = self.checkpoints.iter().position(|&(active, _)| active == id) else {
+            return false;
+        };
+        self.checkpoints.truncate(ix);
+        if self.checkpoints.is_empty() {
+            self.log.clear();
+        }
+        true
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.log(|| Undo::Pushed(value.clone()));
+        self.heap.push(value);
+    }
+
+    pub fn bulk_push(&mut self, items: impl IntoIterator<Item = T>) {
+        let items: Vec<T> = items.into_iter().collect();
+        if !self.checkpoints.is_empty() {
+            self.log.extend(items.iter().cloned().map(Undo::Pushed));
+        }
+        self.heap.bulk_push(items);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.heap.pop()?;
+        self.log(|| Undo::Popped(value.clone()));
+        Some(value)
+    }
+
+    /// See [`FibonacciHeap::push_pop`].
+    pub fn push_pop(&mut self, value: T) -> T {
+        match self.heap.peek() {
+            Some(top) if top.partial_cmp(&value) == Some(Ordering::Less) => self.replace_top(value).unwrap(),
+            _ => value,
+        }
+    }
+
+    pub fn replace_top(&mut self, value: T) -> Option<T> {
+        self.log(|| Undo::Pushed(value.clone()));
+        let old = self.heap.replace_top(value);
+        if let Some(old) = &old {
+            self.log(|| Undo::Popped(old.clone()));
+        }
+        old
+    }
+
+    pub fn append(&mut self, other: FibonacciHeap<T>) {
+        if !self.checkpoints.is_empty() {
+            self.log.extend(other.iter().cloned().map(Undo::Pushed));
+        }
+        self.heap.append(other);
+    }
+
+    pub fn clear(&mut self) {
+        if self.checkpoints.is_empty() {
+            self.heap.clear();
+        } else {
+            let values = mem::take(&mut self.heap).into_vec();
+            self.log.extend(values.into_iter().map(Undo::Popped));
+        }
+    }
+
+    fn log(&mut self, undo: impl FnOnce() -> Undo<T>) {
+        if !self.checkpoints.is_empty() {
+            self.log.push(undo());
+        }
+    }
+}
+
+impl<T> Default for CheckpointFibonacciHeap<T> {
+    fn default() -> Self {
+        Self { heap: FibonacciHeap::new(), log: Vec::new(), checkpoints: Vec::new(), next_id: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+    use crate::assert_valid;
+    use alloc::vec;
+
+    // One random change, applied to both heaps.
+    fn mutate(heap: &mut CheckpointFibonacciHeap<u64>, control: &mut FibonacciHeap<u64>, rng: &mut Rng) {
+        let value = rng.below(200);
+        match rng.below(8) {
+            0 | 1 => {
+                heap.push(value);
+                control.push(value);
+            }
+            2 | 3 => assert_eq!(heap.pop(), control.pop()),
+            4 => assert_eq!(heap.push_pop(value), control.push_pop(value)),
+            5 => assert_eq!(heap.replace_top(value), control.replace_top(value)),
+            6 => {
+                heap.bulk_push([value, value / 2]);
+                control.bulk_push([value, value / 2]);
+            }
+            _ if rng.below(10) == 0 => {
+                heap.clear();
+                control.clear();
+            }
+            _ => {
+                heap.append(FibonacciHeap::from_vec(vec![value, value + 1]));
+                control.append(FibonacciHeap::from_vec(vec![value, value + 1]));
+            }
+        }
+    }
+
+    fn sorted(heap: &FibonacciHeap<u64>) -> Vec<u64> {
+        heap.clone().into_sorted_vec()
+    }
+
+    #[test]
+    fn rollback_restores_contents() {
+        let mut rng = Rng(0x510e527fade682d1);
+        let mut heap = CheckpointFibonacciHeap::new();
+        let mut control = FibonacciHeap::new();
+        // active checkpoints, with a copy of what the heap held when each was taken
+        let mut saved: Vec<(CheckpointId, Vec<u64>)> = vec![];
+        for step in 0..3000 {
+            match rng.below(10) {
+                0 if saved.len() < 4 => saved.push((heap.checkpoint(), sorted(&control))),
+                1 if !saved.is_empty() => {
+                    let ix = rng.below(saved.len() as u64) as usize;
+                    let (id, contents) = saved[ix].clone();
+                    saved.truncate(ix);
+                    assert!(heap.rollback(id));
+                    assert!(!heap.rollback(id) && !heap.commit(id));
+                    assert_eq!(sorted(heap.heap()), contents, "step {step}");
+                    control = FibonacciHeap::from_vec(contents);
+                }
+                2 if !saved.is_empty() => {
+                    let ix = rng.below(saved.len() as u64) as usize;
+                    assert!(heap.commit(saved[ix].0));
+                    saved.truncate(ix);
+                }
+                _ => mutate(&mut heap, &mut control, &mut rng),
+            }
+            assert_eq!(heap.peek(), control.peek());
+            if saved.is_empty() {
+                assert_eq!(heap.log_len(), 0);
+            }
+        }
+        assert_valid!(heap.heap());
+        let mut heap = heap.heap;
+        while let Some(value) = heap.pop() {
+            assert_eq!(Some(value), control.pop());
+        }
+        assert!(control.is_empty());
+    }
+}
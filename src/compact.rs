@@ -0,0 +1,458 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem::{self, MaybeUninit};
+
+use crate::HeapMemoryUsage;
+
+// Link value meaning "no node".
+const NIL: u32 = u32::MAX;
+// A tree of degree d holds at least 2^d nodes and there are fewer than 2^32 of them.
+const MAX_DEGREE: usize = u32::BITS as usize;
+
+/// Min-heap with the core operations of [`FibonacciHeap`](crate::FibonacciHeap) (push, pop, peek, append, iteration),
+/// laid out for heaps of many small elements.
+///
+/// Elements live in one contiguous vector and the forest is kept in parallel vectors of `u32` indices
+/// (first child and next sibling of every node) plus a one-byte degree, so each element costs `size_of::<T>() + 9` bytes
+/// instead of a node holding its own children vector. Popped slots are recycled by later pushes.
+///
+/// Differences from `FibonacciHeap`: the heap holds at most `u32::MAX - 1` elements (`push` panics beyond that),
+/// and `append` costs O(m) in the size of the other heap, whose indices are shifted into `self`.
+/// If a comparison panics during `pop`, the remaining elements are leaked rather than dropped.
+///
+/// # Example
+/// ```
+/// use fibheap::CompactFibonacciHeap;
+///
+/// let mut heap = CompactFibonacciHeap::from_vec(vec![5u64, 3, 8]);
+/// heap.push(1);
+/// assert_eq!(heap.peek(), Some(&1));
+/// assert_eq!(heap.pop(), Some(1));
+/// assert_eq!(heap.into_sorted_vec(), [3, 5, 8]);
+/// ```
+pub struct CompactFibonacciHeap<T> {
+    // A slot is initialized exactly when it is reachable from `roots`.
+    values: Vec<MaybeUninit<T>>,
+    // first child of each slot; children are chained through `sibling`
+    child: Vec<u32>,
+    // next root, or next child of the parent
+    sibling: Vec<u32>,
+    degree: Vec<u8>,
+    // vacated slots, reused before the vectors grow
+    free: Vec<u32>,
+    roots: u32,
+    top: u32,
+    len: usize,
+}
+
+impl<T> CompactFibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self { values: vec![], child: vec![], sibling: vec![], degree: vec![], free: vec![], roots: NIL, top: NIL, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        if self.top == NIL {
+            return None;
+        }
+        Some(self.value(self.top))
+    }
+
+    /// Iterates over references to the elements in unspecified order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.slots().map(|ix| self.value(ix))
+    }
+
+    /// Removes every element, keeping the allocations.
+    pub fn clear(&mut self) {
+        self.drop_values();
+        self.values.clear();
+        self.child.clear();
+        self.sibling.clear();
+        self.degree.clear();
+        self.free.clear();
+        self.top = NIL;
+        self.len = 0;
+    }
+
+    /// Breaks down the heap memory held by the heap, like [`FibonacciHeap::memory_usage`](crate::FibonacciHeap::memory_usage).
+    ///
+    /// Node overhead is the 9 bytes of links and degree per element; vacated slots count as unused capacity.
+    pub fn memory_usage(&self) -> HeapMemoryUsage {
+        let total = self.values.capacity() * mem::size_of::<T>()
+            + (self.child.capacity() + self.sibling.capacity() + self.free.capacity()) * mem::size_of::<u32>()
+            + self.degree.capacity();
+        let payload = self.len * mem::size_of::<T>();
+        let node_overhead = self.len * (2 * mem::size_of::<u32>() + 1);
+        HeapMemoryUsage { payload, node_overhead, unused_capacity: total - payload - node_overhead, total }
+    }
+
+    // Indices of the reachable, hence initialized, slots, in unspecified order.
+    fn slots(&self) -> impl Iterator<Item = u32> + '_ {
+        // heads of the sibling lists still to walk
+        let mut lists = vec![self.roots];
+        core::iter::from_fn(move || loop {
+            let ix = lists.pop()?;
+            if ix != NIL {
+                lists.push(self.sibling[ix as usize]);
+                lists.push(self.child[ix as usize]);
+                return Some(ix);
+            }
+        })
+    }
+
+    fn value(&self, ix: u32) -> &T {
+        // SAFETY: only called with indices of reachable, hence initialized, slots.
+        unsafe { self.values[ix as usize].assume_init_ref() }
+    }
+
+    // Drops every reachable value and empties the root list, leaving the slots vacant.
+    fn drop_values(&mut self) {
+        let mut list = mem::replace(&mut self.roots, NIL);
+        if !mem::needs_drop::<T>() {
+            return;
+        }
+        // Walk every tree without a stack: drop the head of the work list and splice its children in front.
+        while list != NIL {
+            let ix = list as usize;
+            list = self.sibling[ix];
+            let child = self.child[ix];
+            if child != NIL {
+                let mut last = child;
+                while self.sibling[last as usize] != NIL {
+                    last = self.sibling[last as usize];
+                }
+                self.sibling[last as usize] = list;
+                list = child;
+            }
+            // SAFETY: `ix` was reachable and has just been unlinked, so it is dropped exactly once.
+            unsafe { self.values[ix].assume_init_drop() };
+        }
+    }
+}
+
+impl<T: PartialOrd> CompactFibonacciHeap<T> {
+    pub fn from_vec(vec: Vec<T>) -> Self {
+        let len = vec.len();
+        assert!(len < NIL as usize, "CompactFibonacciHeap holds fewer than u32::MAX elements");
+        let mut heap = Self {
+            values: vec.into_iter().map(MaybeUninit::new).collect(),
+            child: vec![NIL; len],
+            // all slots form the root list, in order
+            sibling: (1..=len as u32).map(|next| if next == len as u32 { NIL } else { next }).collect(),
+            degree: vec![0; len],
+            free: vec![],
+            roots: if len == 0 { NIL } else { 0 },
+            top: NIL,
+            len,
+        };
+        for ix in 0..len as u32 {
+            if heap.top == NIL || heap.value(ix) < heap.value(heap.top) {
+                heap.top = ix;
+            }
+        }
+        heap
+    }
+
+    /// Consumes the heap and returns its elements in ascending order.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut vec = Vec::with_capacity(self.len);
+        while let Some(value) = self.pop() {
+            vec.push(value);
+        }
+        vec
+    }
+
+    pub fn push(&mut self, value: T) {
+        let ix = match self.free.pop() {
+            Some(ix) => {
+                self.values[ix as usize].write(value);
+                self.child[ix as usize] = NIL;
+                self.sibling[ix as usize] = self.roots;
+                self.degree[ix as usize] = 0;
+                ix
+            }
+            None => {
+                let ix = self.values.len() as u32;
+                assert!(ix != NIL, "CompactFibonacciHeap holds fewer than u32::MAX elements");
+                self.values.push(MaybeUninit::new(value));
+                self.child.push(NIL);
+                self.sibling.push(self.roots);
+                self.degree.push(0);
+                ix
+            }
+        };
+        self.roots = ix;
+        self.len += 1;
+        if self.top == NIL || self.value(ix) < self.value(self.top) {
+            self.top = ix;
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.top == NIL {
+            return None;
+        }
+        let top = self.top;
+        // SAFETY: `top` is reachable from `roots`, hence initialized; it is unlinked below and its slot freed
+        // without being read again.
+        let value = unsafe { self.values[top as usize].assume_init_read() };
+        let (child, top_sibling) = (self.child[top as usize], self.sibling[top as usize]);
+        let roots = self.roots;
+        // Until consolidation finishes the heap looks empty, so a panicking comparison leaks the nodes
+        // instead of leaving links to the slot just vacated.
+        self.roots = NIL;
+        self.top = NIL;
+        self.len -= 1;
+        let len = mem::replace(&mut self.len, 0);
+
+        let mut table = [NIL; MAX_DEGREE];
+        for list in [roots, child] {
+            let mut ix = list;
+            while ix != NIL {
+                if ix == top {
+                    ix = top_sibling;
+                    continue;
+                }
+                let next = self.sibling[ix as usize];
+                self.link_into(&mut table, ix);
+                ix = next;
+            }
+        }
+        for ix in table.into_iter().filter(|&ix| ix != NIL) {
+            self.sibling[ix as usize] = self.roots;
+            self.roots = ix;
+            if self.top == NIL || self.value(ix) < self.value(self.top) {
+                self.top = ix;
+            }
+        }
+        self.len = len;
+        self.free.push(top);
+        Some(value)
+    }
+
+    /// Moves every element of `other` into `self`.
+    ///
+    /// Unlike [`FibonacciHeap::append`](crate::FibonacciHeap::append) this is not O(1):
+    /// `other`'s slots are moved behind those of `self` and its links shifted accordingly.
+    pub fn append(&mut self, mut other: Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            mem::swap(self, &mut other);
+            return;
+        }
+        let base = self.values.len() as u32;
+        assert!(
+            self.values.len() + other.values.len() < NIL as usize,
+            "CompactFibonacciHeap holds fewer than u32::MAX elements"
+        );
+        if other.value(other.top) < self.value(self.top) {
+            self.top = other.top + base;
+        }
+        let mut last = other.roots;
+        while other.sibling[last as usize] != NIL {
+            last = other.sibling[last as usize];
+        }
+        let shift = |link: u32| if link == NIL { NIL } else { link + base };
+        self.child.extend(other.child.iter().map(|&link| shift(link)));
+        self.sibling.extend(other.sibling.iter().map(|&link| shift(link)));
+        self.degree.append(&mut other.degree);
+        self.free.extend(other.free.iter().map(|&ix| ix + base));
+        self.values.append(&mut other.values);
+        // other's root list goes in front of ours; the values now belong to `self` and `other` has no reachable slots
+        self.sibling[(last + base) as usize] = self.roots;
+        self.roots = mem::replace(&mut other.roots, NIL) + base;
+        self.len += mem::replace(&mut other.len, 0);
+    }
+
+    // Merges the tree rooted at `ix` into the degree table, linking equal-degree trees under the smaller root.
+    fn link_into(&mut self, table: &mut [u32; MAX_DEGREE], mut ix: u32) {
+        loop {
+            let degree = self.degree[ix as usize] as usize;
+            let other = table[degree];
+            if other == NIL {
+                table[degree] = ix;
+                return;
+            }
+            table[degree] = NIL;
+            let (parent, child) = if self.value(other) < self.value(ix) { (other, ix) } else { (ix, other) };
+            self.sibling[child as usize] = self.child[parent as usize];
+            self.child[parent as usize] = child;
+            self.degree[parent as usize] += 1;
+            ix = parent;
+        }
+    }
+}
+
+impl<T> Default for CompactFibonacciHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Copies the slots as they are, so the clone pops in the same order as the original.
+///
+/// If a clone panics, the elements cloned so far are dropped and the original is left untouched.
+impl<T: Clone> Clone for CompactFibonacciHeap<T> {
+    fn clone(&self) -> Self {
+        // Drops the slots filled so far if `T::clone` panics.
+        struct Filled<'a, T> {
+            values: &'a mut [MaybeUninit<T>],
+            slots: Vec<u32>,
+        }
+
+        impl<T> Drop for Filled<'_, T> {
+            fn drop(&mut self) {
+                for &ix in &self.slots {
+                    // SAFETY: `ix` was written below and is listed once.
+                    unsafe { self.values[ix as usize].assume_init_drop() };
+                }
+            }
+        }
+
+        let mut values: Vec<MaybeUninit<T>> = (0..self.values.len()).map(|_| MaybeUninit::uninit()).collect();
+        let mut filled = Filled { values: &mut values, slots: Vec::with_capacity(self.len) };
+        for ix in self.slots() {
+            filled.values[ix as usize].write(self.value(ix).clone());
+            filled.slots.push(ix);
+        }
+        // every reachable slot is filled, and the values now belong to the clone
+        filled.slots.clear();
+        drop(filled);
+        Self {
+            values,
+            child: self.child.clone(),
+            sibling: self.sibling.clone(),
+            degree: self.degree.clone(),
+            free: self.free.clone(),
+            roots: self.roots,
+            top: self.top,
+            len: self.len,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CompactFibonacciHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompactFibonacciHeap").field("len", &self.len).field("top", &self.peek()).finish()
+    }
+}
+
+impl<T> Drop for CompactFibonacciHeap<T> {
+    fn drop(&mut self) {
+        self.drop_values();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{DropCounter, Rng};
+    use crate::FibonacciHeap;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn iter_and_clone() {
+        use crate::test_util::{Tracked, Tracker};
+        use std::panic::{self, AssertUnwindSafe};
+
+        let tracker = Tracker::new();
+        let mut heap = CompactFibonacciHeap::new();
+        (0..300u32).for_each(|value| heap.push(tracker.track(value * 7919 % 300)));
+        for _ in 0..50 {
+            heap.pop();
+        }
+        let mut values: Vec<u32> = heap.iter().map(|tracked| tracked.value).collect();
+        values.sort_unstable();
+        assert_eq!(values, (50..300).collect::<Vec<_>>());
+
+        for fuse in [1, 100, 250] {
+            tracker.panic_on_clone(fuse);
+            assert!(panic::catch_unwind(AssertUnwindSafe(|| heap.clone())).is_err(), "fuse {fuse}");
+            assert_eq!(tracker.live(), heap.len(), "fuse {fuse}");
+        }
+        tracker.defuse();
+        let copy = heap.clone();
+        let popped = |heap: CompactFibonacciHeap<Tracked<u32>>| heap.into_sorted_vec().into_iter().map(|tracked| tracked.value);
+        assert!(popped(copy).eq(popped(heap)));
+        tracker.assert_balanced();
+    }
+
+    #[test]
+    fn matches_heap() {
+        let mut rng = Rng(0x1f83d9abfb41bd6b);
+        let initial: Vec<u64> = (0..100).map(|_| rng.below(1000)).collect();
+        let mut compact = CompactFibonacciHeap::from_vec(initial.clone());
+        let mut reference = FibonacciHeap::from_vec(initial);
+        let rounds = if cfg!(miri) { 2000 } else { 50_000 };
+        for _ in 0..rounds {
+            match rng.below(8) {
+                0..=3 => {
+                    let value = rng.below(1000);
+                    compact.push(value);
+                    reference.push(value);
+                }
+                4 => {
+                    let values: Vec<u64> = (0..rng.below(20)).map(|_| rng.below(1000)).collect();
+                    compact.append(CompactFibonacciHeap::from_vec(values.clone()));
+                    reference.append(FibonacciHeap::from_vec(values));
+                }
+                _ => assert_eq!(compact.pop(), reference.pop()),
+            }
+            assert_eq!(compact.len(), reference.len());
+            assert_eq!(compact.peek(), reference.peek());
+        }
+        assert_eq!(compact.into_sorted_vec(), reference.into_sorted_vec());
+
+        let mut empty = CompactFibonacciHeap::<u64>::new();
+        assert_eq!(empty.pop(), None);
+        empty.append(CompactFibonacciHeap::from_vec(vec![2, 1]));
+        assert_eq!(empty.into_sorted_vec(), [1, 2]);
+    }
+
+    #[test]
+    fn memory_usage() {
+        let mut heap = CompactFibonacciHeap::from_vec((0..1000u64).rev().collect());
+        let usage = heap.memory_usage();
+        assert_eq!((usage.payload, usage.node_overhead, usage.unused_capacity), (8000, 9000, 0));
+        heap.pop();
+        let usage = heap.memory_usage();
+        assert_eq!(usage.payload, 999 * 8);
+        // the vacated slot, and the free list holding it
+        assert!(usage.unused_capacity >= 17 + 4);
+        assert_eq!(usage.total, usage.payload + usage.node_overhead + usage.unused_capacity);
+    }
+
+    #[test]
+    fn drops_only_live_values() {
+        let drops = Rc::new(Cell::new(0));
+        let mut heap = CompactFibonacciHeap::new();
+        for value in 0..100 {
+            heap.push(DropCounter::new(value, &drops));
+        }
+        for _ in 0..30 {
+            drop(heap.pop());
+        }
+        assert_eq!(drops.get(), 30);
+        // reuses vacated slots
+        for value in 100..110 {
+            heap.push(DropCounter::new(value, &drops));
+        }
+        heap.clear();
+        assert_eq!(drops.get(), 110);
+        heap.push(DropCounter::new(0, &drops));
+        drop(heap);
+        assert_eq!(drops.get(), 111);
+    }
+}
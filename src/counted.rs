@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::FibonacciHeap;
+
+/// Min-heap for duplicate-heavy workloads, storing each distinct value once together with its multiplicity.
+///
+/// The heap only ever holds distinct values and a hash map tracks how many copies of each are queued,
+/// so pushing a value that is already present is an O(1) counter increment and memory scales with the number of distinct values.
+/// `pop` hands out clones of the minimum until its count runs out.
+///
+/// # Example
+/// ```
+/// use fibheap::CountedFibonacciHeap;
+///
+/// let mut heap = CountedFibonacciHeap::new();
+/// heap.push_n(3, 2);
+/// heap.push(1);
+/// heap.push(3);
+/// assert_eq!(heap.len(), 4);
+/// assert_eq!(heap.pop(), Some(1));
+/// assert_eq!(heap.pop_all_min(), Some((3, 3)));
+/// assert!(heap.is_empty());
+/// ```
+#[derive(Debug)]
+pub struct CountedFibonacciHeap<T> {
+    heap: FibonacciHeap<T>,
+    counts: HashMap<T, usize>,
+    len: usize,
+}
+
+impl<T: PartialOrd + Eq + Hash + Clone> CountedFibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Total number of elements, counting multiplicity.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of distinct values.
+    pub fn distinct_len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+
+    #[deprecated(note = "renamed to `peek` for parity with `BinaryHeap`")]
+    pub fn top(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    /// The minimum together with how many copies of it are queued.
+    pub fn top_with_count(&self) -> Option<(&T, usize)> {
+        self.heap.peek().map(|top| (top, self.counts[top]))
+    }
+
+    /// Number of queued copies of `value`.
+    pub fn count(&self, value: &T) -> usize {
+        self.counts.get(value).copied().unwrap_or(0)
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.push_n(value, 1);
+    }
+
+    /// Pushes `n` copies of `value` at once.
+    pub fn push_n(&mut self, value: T, n: usize) {
+        if n == 0 {
+            return;
+        }
+        self.len += n;
+        if let Some(count) = self.counts.get_mut(&value) {
+            *count += n;
+            return;
+        }
+        self.counts.insert(value.clone(), n);
+        self.heap.push(value);
+    }
+
+    /// Pops one copy of the minimum, cloning it unless it is the last copy.
+    pub fn pop(&mut self) -> Option<T> {
+        let count = self.counts.get_mut(self.heap.peek()?).unwrap();
+        self.len -= 1;
+        if *count > 1 {
+            *count -= 1;
+            return self.heap.peek().cloned();
+        }
+        let value = self.heap.pop().unwrap();
+        self.counts.remove(&value);
+        Some(value)
+    }
+
+    /// Pops every copy of the minimum at once, returning it with its multiplicity.
+    pub fn pop_all_min(&mut self) -> Option<(T, usize)> {
+        let value = self.heap.pop()?;
+        let count = self.counts.remove(&value).unwrap();
+        self.len -= count;
+        Some((value, count))
+    }
+}
+
+impl<T: PartialOrd + Eq + Hash + Clone> Default for CountedFibonacciHeap<T> {
+    fn default() -> Self {
+        Self { heap: FibonacciHeap::new(), counts: HashMap::new(), len: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+
+    #[test]
+    fn empty() {
+        let mut heap = CountedFibonacciHeap::<u8>::new();
+        assert_eq!(heap.peek(), None);
+        assert_eq!(heap.pop(), None);
+        assert_eq!(heap.pop_all_min(), None);
+        heap.push_n(1, 0);
+        assert!(heap.is_empty());
+        assert_eq!(heap.distinct_len(), 0);
+    }
+
+    #[test]
+    fn matches_plain_heap() {
+        let mut rng = Rng(0x243f6a8885a308d3);
+        let mut counted = CountedFibonacciHeap::new();
+        let mut plain = FibonacciHeap::new();
+        for _ in 0..20_000 {
+            match rng.below(10) {
+                0..=5 => {
+                    let value = rng.below(300);
+                    counted.push(value);
+                    plain.push(value);
+                }
+                6 => {
+                    let (value, n) = (rng.below(300), rng.below(4) as usize);
+                    counted.push_n(value, n);
+                    (0..n).for_each(|_| plain.push(value));
+                }
+                7 => {
+                    if let Some((value, n)) = counted.pop_all_min() {
+                        for _ in 0..n {
+                            assert_eq!(plain.pop(), Some(value));
+                        }
+                        assert_ne!(plain.peek(), Some(&value));
+                    }
+                }
+                _ => assert_eq!(counted.pop(), plain.pop()),
+            }
+            assert_eq!(counted.len(), plain.len());
+            assert_eq!(counted.peek(), plain.peek());
+            assert!(counted.distinct_len() <= 300);
+        }
+        while let Some(value) = plain.pop() {
+            assert_eq!(counted.pop(), Some(value));
+        }
+        assert!(counted.is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Deref;
+
+use crate::{FibonacciHeap, Heap};
+
+/// [`FibonacciHeap`] whose `clone` is O(1): clones share one heap behind an `Arc` until one of them is changed,
+/// which first copies it for that clone alone.
+///
+/// This suits snapshots that are taken often and rarely outlive the next change, e.g. a copy handed to a
+/// reporting thread every second: the copy is only paid for when the original changes while a snapshot is alive.
+/// The observable behavior is that of a deep clone. Reads go through `Deref` to the shared heap, and
+/// [`make_mut`](Self::make_mut) gives access to every mutating method of `FibonacciHeap`.
+/// Like an `Arc<T>`, it is `Send` and `Sync` when `T` is both.
+///
+/// # Example
+/// ```
+/// use fibheap::CowFibonacciHeap;
+///
+/// let mut queue = CowFibonacciHeap::from_vec(vec![3, 1, 2]);
+/// let snapshot = queue.clone();
+/// assert!(queue.is_shared());
+/// queue.push(0);
+/// assert!(!queue.is_shared());
+/// assert_eq!(snapshot.iter_sorted().collect::<Vec<_>>(), [&1, &2, &3]);
+/// assert_eq!(queue.peek(), Some(&0));
+/// ```
+pub struct CowFibonacciHeap<T> {
+    heap: Arc<FibonacciHeap<T>>,
+}
+
+impl<T> CowFibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether other clones share the heap, so that the next change copies it.
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.heap) > 1
+    }
+
+    pub fn clear(&mut self) {
+        match Arc::get_mut(&mut self.heap) {
+            Some(heap) => heap.clear(),
+            None => *self = Self::new(),
+        }
+    }
+}
+
+impl<T: PartialOrd + Clone> CowFibonacciHeap<T> {
+    pub fn from_vec(vec: Vec<T>) -> Self {
+        Self::from(FibonacciHeap::from_vec(vec))
+    }
+
+    /// The heap for changing it, copied first if it is shared.
+    pub fn make_mut(&mut self) -> &mut FibonacciHeap<T> {
+        Arc::make_mut(&mut self.heap)
+    }
+
+    /// The heap, copied if it is shared.
+    pub fn into_inner(self) -> FibonacciHeap<T> {
+        Arc::unwrap_or_clone(self.heap)
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.make_mut().push(value);
+    }
+
+    /// Removes and returns the smallest element; an empty heap isn't copied.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.make_mut().pop()
+    }
+
+    /// Moves the elements of `other` into `self`, copying each of them first if it is shared.
+    pub fn append(&mut self, other: Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            *self = other;
+            return;
+        }
+        let other = other.into_inner();
+        self.make_mut().append(other);
+    }
+}
+
+impl<T> Deref for CowFibonacciHeap<T> {
+    type Target = FibonacciHeap<T>;
+
+    fn deref(&self) -> &FibonacciHeap<T> {
+        &self.heap
+    }
+}
+
+/// Shares the heap; no element is cloned.
+impl<T> Clone for CowFibonacciHeap<T> {
+    fn clone(&self) -> Self {
+        Self { heap: Arc::clone(&self.heap) }
+    }
+}
+
+impl<T> Default for CowFibonacciHeap<T> {
+    fn default() -> Self {
+        Self { heap: Arc::new(FibonacciHeap::new()) }
+    }
+}
+
+impl<T> From<FibonacciHeap<T>> for CowFibonacciHeap<T> {
+    fn from(heap: FibonacciHeap<T>) -> Self {
+        Self { heap: Arc::new(heap) }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CowFibonacciHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.heap.fmt(f)
+    }
+}
+
+impl<T: PartialOrd + Clone> Heap<T> for CowFibonacciHeap<T> {
+    fn push(&mut self, value: T) {
+        CowFibonacciHeap::push(self, value)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        CowFibonacciHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+
+    fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn append(&mut self, other: Self) {
+        CowFibonacciHeap::append(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+    use alloc::vec;
+
+    #[test]
+    fn clones_are_independent() {
+        let mut rng = Rng(0x3c6ef372fe94f82b);
+        let mut heap = CowFibonacciHeap::new();
+        let mut deep = FibonacciHeap::new();
+        let mut snapshots = vec![];
+        for step in 0..2000 {
+            let value = rng.below(1000);
+            match rng.below(5) {
+                0 => assert_eq!(heap.pop(), deep.pop()),
+                1 if step % 10 == 0 => {
+                    let other = CowFibonacciHeap::from_vec(vec![value, value + 1]);
+                    heap.append(other.clone());
+                    deep.append(FibonacciHeap::from_vec(vec![value, value + 1]));
+                    assert_eq!(other.len(), 2);
+                }
+                _ => {
+                    heap.push(value);
+                    deep.push(value);
+                }
+            }
+            if step % 50 == 0 {
+                // a snapshot and the deep clone it stands for, to compare at the end
+                snapshots.push((heap.clone(), deep.clone()));
+                assert!(heap.is_shared());
+            }
+            // same operations on the same forest, so even the shape matches
+            assert_eq!(heap.to_dot(), deep.to_dot());
+        }
+        for (snapshot, deep) in snapshots {
+            assert_eq!(*snapshot, deep);
+            assert_eq!(snapshot.into_inner().into_sorted_vec(), deep.into_sorted_vec());
+        }
+        assert!(!heap.is_shared());
+    }
+
+    #[test]
+    fn clear_and_pop_dont_copy() {
+        let mut heap = CowFibonacciHeap::from_vec(vec![1, 2]);
+        let snapshot = heap.clone();
+        heap.clear();
+        assert!(heap.is_empty() && !heap.is_shared());
+        assert_eq!(snapshot.len(), 2);
+
+        let empty = CowFibonacciHeap::<i32>::new();
+        let mut clone = empty.clone();
+        assert_eq!(clone.pop(), None);
+        assert!(clone.is_shared());
+    }
+
+    #[test]
+    fn shared_across_threads() {
+        let mut heap = CowFibonacciHeap::from_vec((0..1000).collect());
+        let snapshot = heap.clone();
+        let reader = std::thread::spawn(move || snapshot.iter_sorted().copied().collect::<Vec<u32>>());
+        heap.pop();
+        assert_eq!(reader.join().unwrap(), (0..1000).collect::<Vec<_>>());
+        assert_eq!(heap.len(), 999);
+    }
+}
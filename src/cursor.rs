@@ -0,0 +1,245 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem;
+
+use crate::{FibonacciHeap, Node};
+
+/// Cursor walking the forest of a heap, able to move whole subtrees in and out of it.
+///
+/// Obtained from [`FibonacciHeap::cursor_top`] or [`FibonacciHeap::cursor_root`]. The cursor always points at a
+/// node: moves that would leave the forest return `false` and stay put. Values can't be changed through it,
+/// and after each mutation `len()` and the tracked minimum of the heap are up to date again.
+///
+/// Detaching doesn't cut the parents of the subtree the way a textbook decrease-key does, so moving many
+/// subtrees around can leave trees of a degree larger than their size would allow, and later pops slower.
+///
+/// # Example
+/// ```
+/// use fibheap::FibonacciHeap;
+///
+/// let mut heap = FibonacciHeap::from_vec((0..8).collect());
+/// heap.pop();
+/// heap.consolidate();
+/// // the tree holding 4, 5, 6 and 7 is the last root
+/// let mut cursor = heap.cursor_root(2).unwrap();
+/// assert_eq!(*cursor.value(), 4);
+/// assert!(cursor.first_child() && cursor.next_sibling());
+/// assert_eq!((*cursor.value(), cursor.degree()), (6, 1));
+/// let subtree = cursor.detach_subtree();
+/// assert_eq!(subtree.into_sorted_vec(), [6, 7]);
+/// assert_eq!(heap.len(), 5);
+/// assert_eq!(heap.validate(), Ok(()));
+/// ```
+pub struct CursorMut<'a, T> {
+    heap: &'a mut FibonacciHeap<T>,
+    // the index of a root, then child indices down to the current node
+    path: Vec<usize>,
+}
+
+impl<T: PartialOrd> FibonacciHeap<T> {
+    /// A cursor at the root holding the minimum, or `None` if the heap is empty.
+    pub fn cursor_top(&mut self) -> Option<CursorMut<'_, T>> {
+        let top_index = self.top_index;
+        self.cursor_root(top_index)
+    }
+
+    /// A cursor at the root with index `n` in the root list, or `None` if there are no more roots than that.
+    pub fn cursor_root(&mut self, n: usize) -> Option<CursorMut<'_, T>> {
+        (n < self.roots.len()).then(|| CursorMut { heap: self, path: vec![n] })
+    }
+}
+
+impl<T: PartialOrd> CursorMut<'_, T> {
+    fn node(&self) -> &Node<T> {
+        self.heap.node_at(&self.path)
+    }
+
+    fn node_mut(&mut self) -> &mut Node<T> {
+        let mut node = &mut self.heap.roots[self.path[0]];
+        for &ix in &self.path[1..] {
+            node = &mut node.children[ix];
+        }
+        node
+    }
+
+    pub fn value(&self) -> &T {
+        self.node().value()
+    }
+
+    pub fn degree(&self) -> usize {
+        self.node().degree()
+    }
+
+    /// Whether the cursor is at a root, as opposed to a node with a parent.
+    pub fn is_root(&self) -> bool {
+        self.path.len() == 1
+    }
+
+    /// Moves to the parent of the current node; returns `false` at a root.
+    pub fn parent(&mut self) -> bool {
+        if self.is_root() {
+            return false;
+        }
+        self.path.pop();
+        true
+    }
+
+    /// Moves to the first child of the current node; returns `false` if it has none.
+    pub fn first_child(&mut self) -> bool {
+        if self.degree() == 0 {
+            return false;
+        }
+        self.path.push(0);
+        true
+    }
+
+    /// Moves to the next child of the same parent, or to the next root at a root; returns `false` after the last one.
+    pub fn next_sibling(&mut self) -> bool {
+        let (&ix, parent_path) = self.path.split_last().unwrap();
+        let siblings = if parent_path.is_empty() { self.heap.roots.len() } else { self.heap.node_at(parent_path).degree() };
+        if ix + 1 == siblings {
+            return false;
+        }
+        *self.path.last_mut().unwrap() += 1;
+        true
+    }
+
+    /// Removes the current node and everything below it, returning them as a heap of their own.
+    ///
+    /// The remaining siblings may be reordered, and the cursor is consumed; get a new one to keep walking.
+    pub fn detach_subtree(self) -> FibonacciHeap<T> {
+        let heap = self.heap;
+        let (&ix, parent_path) = self.path.split_last().unwrap();
+        let node = if parent_path.is_empty() {
+            let last = heap.roots.len() - 1;
+            let node = heap.roots.swap_remove(ix);
+            if ix == heap.top_index {
+                heap.find_top();
+            } else if heap.top_index == last {
+                heap.top_index = ix;
+            }
+            node
+        } else {
+            let mut parent = &mut heap.roots[parent_path[0]];
+            for &ix in &parent_path[1..] {
+                parent = &mut parent.children[ix];
+            }
+            parent.children.swap_remove(ix)
+        };
+        let subtree = FibonacciHeap::from_roots(vec![node]);
+        heap.len -= subtree.len;
+        if heap.eager && !heap.is_empty() {
+            heap.consolidate();
+        }
+        subtree
+    }
+
+    /// Makes the trees of `heap` children of the current node, which must not be greater than any of them.
+    ///
+    /// Debug builds check that and panic otherwise; release builds don't compare at all.
+    /// Like [`detach_subtree`](Self::detach_subtree) this consumes the cursor: with eager consolidation the grown
+    /// root may be linked below another one.
+    pub fn push_child_heap(mut self, mut heap: FibonacciHeap<T>) {
+        if cfg!(debug_assertions) {
+            let parent = self.node();
+            if let Some(root) = heap.roots.iter().position(|root| root.value() < parent.value()) {
+                panic!("fibheap: root {root} of the pushed heap is smaller than the node it's pushed under");
+            }
+        }
+        self.node_mut().children.append(&mut heap.roots);
+        self.heap.len += mem::take(&mut heap.len);
+        self.heap.counters.merge(heap.counters);
+        if self.heap.eager && self.is_root() {
+            // the degree table no longer matches this root
+            self.heap.consolidate();
+        }
+    }
+}
+
+impl<T: PartialOrd + fmt::Debug> fmt::Debug for CursorMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CursorMut").field("value", self.value()).field("degree", &self.degree()).field("is_root", &self.is_root()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+    use crate::{assert_heap_eq, assert_valid};
+
+    // Walks to a random node: a random root, then random children while the dice allow.
+    fn random_cursor<'a>(heap: &'a mut FibonacciHeap<u64>, rng: &mut Rng) -> CursorMut<'a, u64> {
+        let root = rng.below(heap.root_count() as u64) as usize;
+        let mut cursor = heap.cursor_root(root).unwrap();
+        while rng.below(3) > 0 && cursor.first_child() {
+            for _ in 0..rng.below(4) {
+                cursor.next_sibling();
+            }
+        }
+        cursor
+    }
+
+    #[test]
+    fn detach_and_reattach() {
+        let mut rng = Rng(0x853c49e6748fea9b);
+        for eager in [false, true] {
+            let mut heap = FibonacciHeap::new();
+            heap.set_eager_consolidation(eager);
+            heap.bulk_push((0..500).map(|_| rng.below(1000)));
+            heap.pop();
+            let mut expected = heap.clone().into_sorted_vec();
+            for _ in 0..200 {
+                let subtree = random_cursor(&mut heap, &mut rng).detach_subtree();
+                assert_valid!(subtree);
+                assert_valid!(heap);
+                assert_eq!(heap.len() + subtree.len(), expected.len());
+                if heap.is_empty() {
+                    heap.append(subtree);
+                    continue;
+                }
+                // back under the deepest node on some path that is no greater than the subtree's root
+                let top = *subtree.peek().unwrap();
+                let mut cursor = random_cursor(&mut heap, &mut rng);
+                while *cursor.value() > top && cursor.parent() {}
+                if *cursor.value() <= top {
+                    cursor.push_child_heap(subtree);
+                } else {
+                    heap.append(subtree);
+                }
+                assert_valid!(heap);
+                assert_eq!(heap.peek(), expected.first());
+            }
+            assert_eq!(heap.eager_consolidation(), eager);
+            expected.push(u64::MAX);
+            heap.push(u64::MAX);
+            assert_heap_eq!(heap, expected);
+        }
+    }
+
+    #[test]
+    fn navigation() {
+        let mut heap = FibonacciHeap::from_vec((0..8).collect());
+        heap.pop();
+        heap.consolidate();
+        let mut cursor = heap.cursor_top().unwrap();
+        assert!(cursor.is_root() && !cursor.parent() && !cursor.first_child());
+        assert_eq!(*cursor.value(), 1);
+        assert!(cursor.next_sibling() && cursor.next_sibling() && !cursor.next_sibling());
+        assert!(cursor.first_child() && cursor.next_sibling() && cursor.first_child());
+        assert_eq!((*cursor.value(), cursor.is_root()), (7, false));
+        assert!(!cursor.next_sibling() && cursor.parent() && cursor.parent() && !cursor.parent());
+        assert_eq!(*cursor.value(), 4);
+        assert!(heap.cursor_root(3).is_none());
+        assert!(FibonacciHeap::<i32>::new().cursor_top().is_none());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "smaller than the node it's pushed under")]
+    fn pushing_smaller_children() {
+        let mut heap = FibonacciHeap::from_vec(vec![5]);
+        heap.cursor_top().unwrap().push_child_heap(FibonacciHeap::from_vec(vec![7, 3]));
+    }
+}
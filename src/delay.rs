@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+use futures_core::Stream;
+use tokio::time::{sleep_until, Instant, Sleep};
+
+use crate::{TimerKey, TimerQueue};
+
+/// Identifies a value inserted into a [`DelayedFibonacciQueue`], to reset its deadline or cancel it.
+///
+/// A key stays the same across resets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DelayKey(u64);
+
+/// Queue that yields each value once its deadline has passed, like tokio's `DelayQueue`.
+///
+/// Values are ordered by deadline in a [`TimerQueue`], and values with identical deadlines come out in the order
+/// they were inserted. A single tokio [`Sleep`] waits for the earliest deadline: every poll re-arms it for the current
+/// front, and inserting or resetting a value ahead of the front wakes the task that last polled, so a task sleeping
+/// on a later deadline isn't left behind. Deadlines use tokio's clock, so `tokio::time::pause` applies.
+///
+/// Polling needs a tokio runtime with the timer enabled. Like `DelayQueue`, [`next`](Self::next) and the [`Stream`]
+/// resolve to `None` when the queue is empty, and yield again after later inserts.
+///
+/// # Example
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().enable_time().start_paused(true).build().unwrap().block_on(async {
+/// use std::time::Duration;
+/// use tokio::time::Instant;
+/// use fibheap::DelayedFibonacciQueue;
+///
+/// let start = Instant::now();
+/// let mut queue = DelayedFibonacciQueue::new();
+/// queue.insert("second", start + Duration::from_millis(20));
+/// let key = queue.insert("cancelled", start + Duration::from_millis(5));
+/// queue.insert("first", start + Duration::from_millis(10));
+/// assert_eq!(queue.cancel(key), Some("cancelled"));
+///
+/// assert_eq!(queue.next().await, Some("first"));
+/// assert_eq!(start.elapsed(), Duration::from_millis(10));
+/// assert_eq!(queue.next().await, Some("second"));
+/// assert_eq!(queue.next().await, None);
+/// # });
+/// ```
+pub struct DelayedFibonacciQueue<T> {
+    // keys of the values, scheduled at their current deadlines
+    timers: TimerQueue<u64>,
+    // each value with the timer that currently schedules it
+    values: HashMap<u64, (TimerKey, T)>,
+    next_key: u64,
+    // created on the first poll, which is known to run inside a runtime
+    sleep: Option<Pin<Box<Sleep>>>,
+    // the task that last polled and found nothing due
+    waker: Option<Waker>,
+}
+
+impl<T> DelayedFibonacciQueue<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The earliest pending deadline.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.timers.next_deadline().map(Instant::from_std)
+    }
+
+    pub fn insert(&mut self, value: T, deadline: Instant) -> DelayKey {
+        let key = self.next_key;
+        self.next_key += 1;
+        let front = self.next_deadline();
+        let timer = self.timers.schedule(deadline.into_std(), key);
+        self.values.insert(key, (timer, value));
+        self.wake_if_earlier(front, deadline);
+        DelayKey(key)
+    }
+
+    /// Moves a pending value to a new deadline, earlier or later; returns `false` if it has already been yielded or
+    /// cancelled.
+    ///
+    /// The value is ordered as if inserted now among values with the same deadline.
+    pub fn reset(&mut self, key: DelayKey, deadline: Instant) -> bool {
+        let front = self.next_deadline();
+        let Some((timer, _)) = self.values.get_mut(&key.0) else {
+            return false;
+        };
+        self.timers.cancel(*timer);
+        *timer = self.timers.schedule(deadline.into_std(), key.0);
+        self.wake_if_earlier(front, deadline);
+        true
+    }
+
+    /// Removes a pending value and returns it, or `None` if it has already been yielded or cancelled.
+    pub fn cancel(&mut self, key: DelayKey) -> Option<T> {
+        let (timer, value) = self.values.remove(&key.0)?;
+        self.timers.cancel(timer);
+        Some(value)
+    }
+
+    /// Waits for the earliest deadline and yields its value; `None` if the queue is empty.
+    pub async fn next(&mut self) -> Option<T> {
+        poll_fn(|cx| self.poll_expired(cx)).await
+    }
+
+    /// Yields the earliest value if its deadline has passed, and otherwise arms the timer for it and registers `cx`.
+    pub fn poll_expired(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        loop {
+            let Some(deadline) = self.next_deadline() else {
+                self.waker = None;
+                return Poll::Ready(None);
+            };
+            if let Some(key) = self.timers.pop_expired(Instant::now().into_std()) {
+                self.waker = None;
+                return Poll::Ready(self.values.remove(&key).map(|(_, value)| value));
+            }
+            let sleep = match &mut self.sleep {
+                Some(sleep) => {
+                    if sleep.deadline() != deadline {
+                        sleep.as_mut().reset(deadline);
+                    }
+                    sleep
+                }
+                None => self.sleep.insert(Box::pin(sleep_until(deadline))),
+            };
+            if sleep.as_mut().poll(cx).is_pending() {
+                self.waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+        }
+    }
+
+    // A task sleeping until `front` must come back to re-arm if `deadline` is now ahead of it.
+    fn wake_if_earlier(&mut self, front: Option<Instant>, deadline: Instant) {
+        if front.is_none_or(|front| deadline < front) {
+            if let Some(waker) = self.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<T> Default for DelayedFibonacciQueue<T> {
+    fn default() -> Self {
+        Self { timers: TimerQueue::new(), values: HashMap::new(), next_key: 0, sleep: None, waker: None }
+    }
+}
+
+// Values are never pinned: they only move in and out of the map.
+impl<T> Unpin for DelayedFibonacciQueue<T> {}
+
+impl<T> Stream for DelayedFibonacciQueue<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.get_mut().poll_expired(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+impl<T> fmt::Debug for DelayedFibonacciQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DelayedFibonacciQueue")
+            .field("len", &self.len())
+            .field("next_deadline", &self.next_deadline())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::task::{waker, ArcWake};
+    use futures::StreamExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn scripted_inserts_resets_and_cancellations() {
+        let start = Instant::now();
+        let mut queue = DelayedFibonacciQueue::new();
+        let mut keys = HashMap::new();
+        for (at, name) in [(30, "c1"), (10, "a"), (30, "c2"), (50, "e"), (20, "b"), (40, "d"), (30, "c3")] {
+            keys.insert(name, queue.insert(name, start + ms(at)));
+        }
+        assert_eq!(queue.cancel(keys["d"]), Some("d"));
+        assert!(queue.reset(keys["e"], start + ms(15)));
+        assert!(queue.reset(keys["a"], start + ms(35)));
+
+        let mut fired = vec![];
+        while let Some(name) = queue.next().await {
+            fired.push((start.elapsed(), name));
+            if name == "b" {
+                queue.insert("late", start + ms(25));
+                assert_eq!(queue.cancel(keys["c2"]), Some("c2"));
+                assert!(queue.reset(keys["c1"], start + ms(32)));
+            }
+        }
+        assert_eq!(
+            fired,
+            [(ms(15), "e"), (ms(20), "b"), (ms(25), "late"), (ms(30), "c3"), (ms(32), "c1"), (ms(35), "a")]
+        );
+        // everything has fired or been cancelled
+        assert!(!queue.reset(keys["a"], start));
+        assert_eq!(queue.cancel(keys["b"]), None);
+        assert!(queue.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn identical_and_past_deadlines() {
+        let start = Instant::now();
+        let mut queue = DelayedFibonacciQueue::new();
+        for i in 0..10 {
+            queue.insert(i, start + ms(5));
+        }
+        queue.insert(-1, start - ms(1));
+        assert_eq!(queue.next().await, Some(-1));
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        assert_eq!(queue.by_ref().take(10).collect::<Vec<_>>().await, (0..10).collect::<Vec<_>>());
+        assert_eq!(start.elapsed(), ms(5));
+        assert_eq!(queue.next().await, None);
+    }
+
+    struct CountingWaker(AtomicUsize);
+
+    impl ArcWake for CountingWaker {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn new_minimum_wakes_the_sleeping_task() {
+        let start = Instant::now();
+        let mut queue = DelayedFibonacciQueue::new();
+        queue.insert("late", start + ms(100));
+        let wakes = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = waker(Arc::clone(&wakes));
+        let mut cx = Context::from_waker(&waker);
+        assert!(queue.poll_expired(&mut cx).is_pending());
+
+        // behind the front: the task keeps sleeping
+        queue.insert("later", start + ms(200));
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 0);
+        // ahead of it: the task must come back and re-arm
+        let early = queue.insert("early", start + ms(20));
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+        assert!(queue.poll_expired(&mut cx).is_pending());
+        assert!(queue.reset(early, start + ms(10)));
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 2);
+
+        assert_eq!(queue.next().await, Some("early"));
+        assert_eq!(start.elapsed(), ms(10));
+        assert_eq!(queue.next().await, Some("late"));
+        assert_eq!(start.elapsed(), ms(100));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn insert_from_another_branch_rearms() {
+        let start = Instant::now();
+        let mut queue = DelayedFibonacciQueue::new();
+        queue.insert("late", start + ms(100));
+        let mut fired = vec![];
+        let mut inserted = false;
+        while fired.len() < 2 {
+            tokio::select! {
+                Some(name) = queue.next() => fired.push((start.elapsed(), name)),
+                _ = tokio::time::sleep_until(start + ms(10)), if !inserted => {
+                    queue.insert("early", start + ms(30));
+                    inserted = true;
+                }
+            }
+        }
+        assert_eq!(fired, [(ms(30), "early"), (ms(100), "late")]);
+    }
+}
@@ -0,0 +1,236 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{FibonacciHeap, Node};
+
+/// Text rendering of the forest of a heap, returned by [`FibonacciHeap::display_tree`].
+///
+/// One line per node, drawn with box-drawing characters; the root tracked as the minimum is marked `(min)`.
+/// Past [`max_depth`](Self::max_depth) levels or [`max_children`](Self::max_children) siblings, the rest is
+/// summed up in a line like `… 12 more`.
+///
+/// # Example
+/// ```
+/// use fibheap::FibonacciHeap;
+///
+/// let mut heap = FibonacciHeap::from_vec((0..8).collect());
+/// heap.pop();
+/// heap.consolidate();
+/// assert_eq!(
+///     heap.display_tree().max_children(2).to_string(),
+///     "\
+/// ├── 1 (min)
+/// ├── 2
+/// │   └── 3
+/// └── … 1 more"
+/// );
+/// ```
+pub struct DisplayTree<'a, T, F> {
+    roots: &'a [Node<T>],
+    top_index: usize,
+    value: F,
+    max_depth: usize,
+    max_children: usize,
+}
+
+impl<T> FibonacciHeap<T> {
+    /// Renders the forest as indented text, see [`DisplayTree`].
+    pub fn display_tree(&self) -> DisplayTree<'_, T, fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result>
+    where
+        T: fmt::Display,
+    {
+        self.display_tree_with(T::fmt)
+    }
+
+    /// Like [`display_tree`](Self::display_tree), with `value` writing each value, e.g. `|value, f| write!(f, "{value:?}")`.
+    pub fn display_tree_with<F: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result>(&self, value: F) -> DisplayTree<'_, T, F> {
+        DisplayTree { roots: &self.roots, top_index: self.top_index, value, max_depth: usize::MAX, max_children: usize::MAX }
+    }
+}
+
+impl<T, F> DisplayTree<'_, T, F> {
+    /// Shows only the roots and `depth - 1` levels below them.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Shows only the first `n` roots, and the first `n` children of every node.
+    pub fn max_children(mut self, n: usize) -> Self {
+        self.max_children = n;
+        self
+    }
+}
+
+// A line to write: a node, or the number of siblings left out.
+enum Line<'a, T> {
+    Node(&'a Node<T>),
+    More(usize),
+}
+
+impl<'a, T, F> DisplayTree<'a, T, F> {
+    // Pushes the lines for `nodes`, at `depth`, in reverse so that they pop in order.
+    fn push_level(&self, stack: &mut Vec<(Line<'a, T>, usize, bool)>, nodes: &'a [Node<T>], depth: usize) {
+        let lines = if depth >= self.max_depth {
+            vec![Line::More(nodes.len())]
+        } else {
+            let shown = nodes.len().min(self.max_children);
+            let mut lines: Vec<_> = nodes[..shown].iter().map(Line::Node).collect();
+            if shown < nodes.len() {
+                lines.push(Line::More(nodes.len() - shown));
+            }
+            lines
+        };
+        let last = lines.len().saturating_sub(1);
+        stack.extend(lines.into_iter().enumerate().rev().map(|(ix, line)| (line, depth, ix == last)));
+    }
+}
+
+impl<T, F: Clone> Clone for DisplayTree<'_, T, F> {
+    fn clone(&self) -> Self {
+        Self { value: self.value.clone(), ..*self }
+    }
+}
+
+/// Shows the limits, so neither `T` nor `F` need to be `Debug`.
+impl<T, F> fmt::Debug for DisplayTree<'_, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DisplayTree")
+            .field("max_depth", &self.max_depth)
+            .field("max_children", &self.max_children)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, F: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result> fmt::Display for DisplayTree<'_, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.roots.is_empty() {
+            return f.write_str("(empty)");
+        }
+        // lines left to write with their depth and whether they end their list of siblings,
+        // and the latter for every ancestor of the current line
+        let mut stack = vec![];
+        let mut last_ancestors: Vec<bool> = vec![];
+        self.push_level(&mut stack, self.roots, 0);
+        let mut first = true;
+        while let Some((line, depth, last)) = stack.pop() {
+            if !first {
+                f.write_str("\n")?;
+            }
+            first = false;
+            last_ancestors.truncate(depth);
+            for &ancestor_last in &last_ancestors {
+                f.write_str(if ancestor_last { "    " } else { "│   " })?;
+            }
+            f.write_str(if last { "└── " } else { "├── " })?;
+            match line {
+                Line::More(n) => write!(f, "… {n} more")?,
+                Line::Node(node) => {
+                    (self.value)(&node.value, f)?;
+                    if depth == 0 && core::ptr::eq(node, &self.roots[self.top_index]) {
+                        f.write_str(" (min)")?;
+                    }
+                    if !node.children.is_empty() {
+                        last_ancestors.push(last);
+                        self.push_level(&mut stack, &node.children, depth + 1);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SparePool;
+    use alloc::string::ToString;
+
+    fn tree(value: i32, children: Vec<Node<i32>>) -> Node<i32> {
+        Node { value, children }
+    }
+
+    fn heap(roots: Vec<Node<i32>>, top_index: usize) -> FibonacciHeap<i32> {
+        let len = crate::Iter::new(&roots, usize::MAX).count();
+        FibonacciHeap { roots, top_index, len, degrees: vec![], spare: SparePool::default(), max_links: usize::MAX, eager: false, counters: Default::default() }
+    }
+
+    fn shaped() -> FibonacciHeap<i32> {
+        let leaves = |values: core::ops::Range<i32>| values.map(|value| tree(value, vec![])).collect();
+        heap(vec![tree(5, vec![tree(6, leaves(7..9)), tree(9, vec![])]), tree(1, leaves(2..5)), tree(10, vec![])], 1)
+    }
+
+    #[test]
+    fn full_tree() {
+        assert_eq!(
+            shaped().display_tree().to_string(),
+            "\
+├── 5
+│   ├── 6
+│   │   ├── 7
+│   │   └── 8
+│   └── 9
+├── 1 (min)
+│   ├── 2
+│   ├── 3
+│   └── 4
+└── 10"
+        );
+        assert_eq!(FibonacciHeap::<i32>::new().display_tree().to_string(), "(empty)");
+        assert_eq!(heap(vec![tree(1, vec![])], 0).display_tree().to_string(), "└── 1 (min)");
+    }
+
+    #[test]
+    fn elided() {
+        assert_eq!(
+            shaped().display_tree().max_depth(2).max_children(2).to_string(),
+            "\
+├── 5
+│   ├── 6
+│   │   └── … 2 more
+│   └── 9
+├── 1 (min)
+│   ├── 2
+│   ├── 3
+│   └── … 1 more
+└── … 1 more"
+        );
+        assert_eq!(shaped().display_tree().max_depth(1).to_string(), "├── 5\n│   └── … 2 more\n├── 1 (min)\n│   └── … 3 more\n└── 10");
+        assert_eq!(shaped().display_tree().max_children(0).to_string(), "└── … 3 more");
+    }
+
+    #[test]
+    fn custom_values() {
+        let mut heap = FibonacciHeap::new();
+        ["b", "a\"", "c"].into_iter().for_each(|value| heap.push(value));
+        let dump = heap.display_tree_with(|value, f| write!(f, "{value:?}")).to_string();
+        assert_eq!(dump, "├── \"b\"\n├── \"a\\\"\" (min)\n└── \"c\"");
+    }
+
+    #[test]
+    fn deep_tree() {
+        // counts lines without keeping the output, which grows with the square of the depth
+        struct Lines(usize);
+
+        impl fmt::Write for Lines {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0 += s.matches('\n').count();
+                Ok(())
+            }
+        }
+
+        // a path deeper than a recursive renderer could follow on a test thread's stack
+        let mut node = tree(10_000, vec![]);
+        for value in (0..10_000).rev() {
+            node = tree(value, vec![node]);
+        }
+        let heap = heap(vec![node], 0);
+        let mut lines = Lines(0);
+        fmt::write(&mut lines, format_args!("{}", heap.display_tree())).unwrap();
+        // separators between the 10 001 lines
+        assert_eq!(lines.0, 10_000);
+        assert_eq!(heap.display_tree().max_depth(3).to_string(), "└── 0 (min)\n    └── 1\n        └── 2\n            └── … 1 more");
+    }
+}
@@ -0,0 +1,141 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+use core::fmt::{self, Write};
+
+use crate::FibonacciHeap;
+
+impl<T> FibonacciHeap<T> {
+    /// Renders the forest as a Graphviz digraph, e.g. for `dot -Tsvg`.
+    ///
+    /// Each tree of the root list is a cluster, in root-list order; nodes are labeled with their value and degree,
+    /// edges go from parent to child, and the root tracked as the minimum is filled. The output only depends on
+    /// the structure of the heap. Use [`to_dot_with`](Self::to_dot_with) for values that aren't `Display`.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let mut heap = FibonacciHeap::from_vec(vec![2, 1]);
+    /// heap.consolidate();
+    /// let dot = heap.to_dot();
+    /// assert!(dot.starts_with("digraph FibonacciHeap {"));
+    /// assert!(dot.contains("n0 [label=\"1\\ndegree 1\"") && dot.contains("n0 -> n1;"));
+    /// ```
+    pub fn to_dot(&self) -> String
+    where
+        T: fmt::Display,
+    {
+        self.to_dot_with(T::to_string)
+    }
+
+    /// Like [`to_dot`](Self::to_dot), with `label` turning each value into the text shown in its node.
+    /// The text is escaped, so it may contain quotes, backslashes and line breaks.
+    pub fn to_dot_with(&self, mut label: impl FnMut(&T) -> String) -> String {
+        let mut dot = String::from("digraph FibonacciHeap {\n    node [shape=box, style=rounded];\n");
+        // nodes are numbered in the order they are written: each tree in preorder, one tree after the other
+        let mut next_id = 0;
+        for (root_ix, root) in self.roots.iter().enumerate() {
+            // writing to a `String` can't fail
+            let _ = writeln!(dot, "    subgraph cluster_{root_ix} {{\n        style=dashed;");
+            // nodes left to write, with their parent's id
+            let mut stack = vec![(root, None)];
+            while let Some((node, parent)) = stack.pop() {
+                let id = next_id;
+                next_id += 1;
+                let _ = write!(dot, "        n{id} [label=\"");
+                escape(&mut dot, &label(&node.value));
+                let _ = write!(dot, "\\ndegree {}\"", node.degree());
+                if parent.is_none() && root_ix == self.top_index {
+                    dot.push_str(", style=\"rounded,filled\", fillcolor=gold");
+                }
+                dot.push_str("];\n");
+                if let Some(parent) = parent {
+                    let _ = writeln!(dot, "        n{parent} -> n{id};");
+                }
+                stack.extend(node.children.iter().rev().map(|child| (child, Some(id))));
+            }
+            dot.push_str("    }\n");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+// Appends `text` for use inside a quoted DOT string.
+fn escape(dot: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '"' => dot.push_str("\\\""),
+            '\\' => dot.push_str("\\\\"),
+            '\n' => dot.push_str("\\n"),
+            '\r' => {}
+            c => dot.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    #[test]
+    fn small_heap() {
+        let mut heap = FibonacciHeap::from_vec((0..8).collect());
+        heap.pop();
+        heap.consolidate();
+        assert_eq!(
+            heap.to_dot(),
+            r#"digraph FibonacciHeap {
+    node [shape=box, style=rounded];
+    subgraph cluster_0 {
+        style=dashed;
+        n0 [label="1\ndegree 0", style="rounded,filled", fillcolor=gold];
+    }
+    subgraph cluster_1 {
+        style=dashed;
+        n1 [label="2\ndegree 1"];
+        n2 [label="3\ndegree 0"];
+        n1 -> n2;
+    }
+    subgraph cluster_2 {
+        style=dashed;
+        n3 [label="4\ndegree 2"];
+        n4 [label="5\ndegree 0"];
+        n3 -> n4;
+        n5 [label="6\ndegree 1"];
+        n3 -> n5;
+        n6 [label="7\ndegree 0"];
+        n5 -> n6;
+    }
+}
+"#
+        );
+        assert_eq!(FibonacciHeap::<u8>::new().to_dot(), "digraph FibonacciHeap {\n    node [shape=box, style=rounded];\n}\n");
+    }
+
+    #[test]
+    fn escapes_labels() {
+        let heap = FibonacciHeap::from_vec(vec![r#"say "hi" \o/ <b>&amp;</b>"#, "two\r\nlines"]);
+        let dot = heap.to_dot_with(|value| value.to_string());
+        assert!(dot.contains(r#"[label="say \"hi\" \\o/ <b>&amp;</b>\ndegree 0""#), "{dot}");
+        assert!(dot.contains(r#"[label="two\nlines\ndegree 0""#), "{dot}");
+    }
+
+    #[test]
+    fn large_heap() {
+        let mut heap = FibonacciHeap::from_vec((0..10_000u32).map(|i| i * 7919 % 10_007).collect());
+        for _ in 0..100 {
+            heap.pop();
+        }
+        let dot = heap.to_dot();
+        assert_eq!(dot.matches('{').count(), dot.matches('}').count());
+        assert_eq!(dot.matches("subgraph cluster_").count(), heap.root_count());
+        assert_eq!(dot.matches(" [label=").count(), heap.len());
+        assert_eq!(dot.matches(" -> ").count(), heap.len() - heap.root_count());
+        assert_eq!(dot.matches("fillcolor").count(), 1);
+        // every node is declared once, numbered in order
+        assert!(dot.contains(&format!("    n{} [label=", heap.len() - 1)));
+        assert!(!dot.contains(&format!("    n{} [label=", heap.len())));
+    }
+}
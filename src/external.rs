@@ -0,0 +1,367 @@
+use std::cmp::Ordering;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{self, AtomicU64};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::FibonacciHeap;
+
+// Distinguishes the run files of heaps living in the same process.
+static NEXT_HEAP_ID: AtomicU64 = AtomicU64::new(0);
+
+// A sorted run on disk; the file is removed when the run is dropped.
+struct Run {
+    path: PathBuf,
+    reader: BufReader<File>,
+    remaining: usize,
+    // start of the next element in the file
+    offset: u64,
+    // set when a failed read couldn't seek back to `offset`, leaving the reader in the middle of an element
+    poisoned: bool,
+}
+
+impl Run {
+    // Reads the next element. A failed read moves the reader back to the start of the element, so that reading again
+    // decodes it whole rather than from wherever the failure left off.
+    fn read_next<T: DeserializeOwned>(&mut self) -> io::Result<Option<T>> {
+        if self.poisoned {
+            return Err(io::Error::other("run left unreadable by an earlier failed read"));
+        }
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        let mut counted = Counted { inner: &mut self.reader, read: 0 };
+        match bincode::deserialize_from(&mut counted) {
+            Ok(value) => {
+                self.offset += counted.read;
+                self.remaining -= 1;
+                Ok(Some(value))
+            }
+            Err(err) => {
+                self.poisoned = self.reader.seek(SeekFrom::Start(self.offset)).is_err();
+                Err(io::Error::other(err))
+            }
+        }
+    }
+}
+
+// Counts the bytes read through it.
+struct Counted<'a, R> {
+    inner: &'a mut R,
+    read: u64,
+}
+
+impl<R: Read> Read for Counted<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.read += read as u64;
+        Ok(read)
+    }
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+// A run ordered by the element it will yield next.
+struct RunHead<T> {
+    head: T,
+    run: Run,
+}
+
+impl<T: PartialOrd> PartialEq for RunHead<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.head == other.head
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for RunHead<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.head.partial_cmp(&other.head)
+    }
+}
+
+/// Heap for datasets larger than memory, spilling sorted runs to disk.
+///
+/// Elements are buffered in an in-memory heap until its shallow size (`len * size_of::<T>()`) exceeds the byte budget;
+/// the buffer is then drained in order into a run file in the spill directory.
+/// `pop` merges the in-memory heap with the heads of all runs, which are themselves kept in a heap.
+/// Run files are deleted as soon as they are exhausted, and all remaining ones when the heap is dropped.
+///
+/// Runs are never merged, and each keeps its file open until it is exhausted, so the heap holds one file descriptor
+/// per run: about `len * size_of::<T>() / budget` of them. Pick a budget that keeps this under the process's limit on
+/// open files (often 1024 by default on Linux), or pop often enough for runs to drain.
+///
+/// `push` and `pop` panic on I/O errors; use [`try_push`](Self::try_push) and [`try_pop`](Self::try_pop) to handle them.
+///
+/// # Example
+/// ```
+/// use fibheap::ExternalFibonacciHeap;
+///
+/// let mut heap = ExternalFibonacciHeap::new(64, std::env::temp_dir());
+/// for value in (0..100u64).rev() {
+///     heap.push(value);
+/// }
+/// assert!(heap.run_count() > 0);
+/// assert_eq!(std::iter::from_fn(|| heap.pop()).collect::<Vec<_>>(), (0..100).collect::<Vec<_>>());
+/// ```
+pub struct ExternalFibonacciHeap<T> {
+    memory: FibonacciHeap<T>,
+    runs: FibonacciHeap<RunHead<T>>,
+    budget: usize,
+    dir: PathBuf,
+    id: u64,
+    next_run: u64,
+    len: usize,
+}
+
+impl<T: Serialize + DeserializeOwned + PartialOrd> ExternalFibonacciHeap<T> {
+    /// Creates a heap keeping about `budget` bytes of elements in memory and spilling runs into `spill_dir`.
+    ///
+    /// At least one element is always kept in memory, whatever the budget.
+    pub fn new(budget: usize, spill_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            memory: FibonacciHeap::new(),
+            runs: FibonacciHeap::new(),
+            budget,
+            dir: spill_dir.into(),
+            id: NEXT_HEAP_ID.fetch_add(1, atomic::Ordering::Relaxed),
+            next_run: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of runs currently on disk.
+    pub fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    pub fn spill_dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        match (self.memory.peek(), self.runs.peek()) {
+            (Some(memory), Some(run)) if run.head < *memory => Some(&run.head),
+            (None, Some(run)) => Some(&run.head),
+            (memory, _) => memory,
+        }
+    }
+
+    #[deprecated(note = "renamed to `peek` for parity with `BinaryHeap`")]
+    pub fn top(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    /// # Panics
+    /// Panics if spilling to disk fails.
+    pub fn push(&mut self, value: T) {
+        self.try_push(value).expect("failed to spill to disk")
+    }
+
+    /// # Panics
+    /// Panics if reading a run back from disk fails.
+    pub fn pop(&mut self) -> Option<T> {
+        self.try_pop().expect("failed to read a spilled run")
+    }
+
+    /// Pushes `value`, spilling the in-memory elements to a new run if they exceed the budget.
+    ///
+    /// On error the element stays queued in memory, and so do the elements that failed to spill.
+    pub fn try_push(&mut self, value: T) -> io::Result<()> {
+        self.memory.push(value);
+        self.len += 1;
+        if self.memory.len() > 1 && self.memory.len() * mem::size_of::<T>() > self.budget {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Removes and returns the smallest element, reading the next element of its run if it came from disk.
+    ///
+    /// On error nothing is removed: the run stays queued with the element it would have yielded, and the next pop
+    /// reads the run's next element again from its start.
+    pub fn try_pop(&mut self) -> io::Result<Option<T>> {
+        let from_run = match (self.memory.peek(), self.runs.peek()) {
+            (Some(memory), Some(run)) => run.head < *memory,
+            (None, Some(_)) => true,
+            _ => false,
+        };
+        if !from_run {
+            let value = self.memory.pop();
+            self.len -= value.is_some() as usize;
+            return Ok(value);
+        }
+        let RunHead { head, mut run } = self.runs.pop().unwrap();
+        match run.read_next() {
+            Ok(next) => {
+                self.len -= 1;
+                if let Some(next) = next {
+                    self.runs.push(RunHead { head: next, run });
+                }
+                Ok(Some(head))
+            }
+            Err(err) => {
+                // The run stays queued behind the same head, so nothing is lost and the next pop reads again.
+                self.runs.push(RunHead { head, run });
+                Err(err)
+            }
+        }
+    }
+
+    fn spill(&mut self) -> io::Result<()> {
+        let path = self.dir.join(format!("fibheap-{}-{}-{}.run", process::id(), self.id, self.next_run));
+        self.next_run += 1;
+        let sorted = mem::take(&mut self.memory).into_sorted_vec();
+        // On failure the elements go back into memory, so that a failed spill loses nothing.
+        let written = File::create(&path).and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            sorted.iter().try_for_each(|value| bincode::serialize_into(&mut writer, value)).map_err(io::Error::other)?;
+            writer.flush()
+        });
+        let remaining = sorted.len();
+        if let Err(err) = written {
+            let _ = fs::remove_file(&path);
+            self.memory = FibonacciHeap::from_vec(sorted);
+            return Err(err);
+        }
+
+        // The elements are only dropped once the run is open and its head read back.
+        let mut run = match File::open(&path) {
+            Ok(file) => Run { reader: BufReader::new(file), path, remaining, offset: 0, poisoned: false },
+            Err(err) => {
+                let _ = fs::remove_file(&path);
+                self.memory = FibonacciHeap::from_vec(sorted);
+                return Err(err);
+            }
+        };
+        match run.read_next() {
+            Ok(head) => {
+                if let Some(head) = head {
+                    self.runs.push(RunHead { head, run });
+                }
+                Ok(())
+            }
+            Err(err) => {
+                // dropping the run removes its file
+                drop(run);
+                self.memory = FibonacciHeap::from_vec(sorted);
+                Err(err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+
+    fn spill_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fibheap-test-{}-{name}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn sorts_more_than_budget() {
+        let dir = spill_dir("sorts");
+        let mut rng = Rng(0x6a09e667f3bcc908);
+        let mut values: Vec<u64> = (0..20_000).map(|_| rng.below(5000)).collect();
+        let mut heap = ExternalFibonacciHeap::new(1024, &dir);
+        for &value in &values {
+            heap.push(value);
+        }
+        assert_eq!(heap.len(), values.len());
+        assert!(heap.run_count() > 100);
+
+        values.sort_unstable();
+        for expected in values {
+            assert_eq!(heap.peek(), Some(&expected));
+            assert_eq!(heap.pop(), Some(expected));
+        }
+        assert_eq!(heap.pop(), None);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn failed_read_loses_nothing() {
+        // cut on an element boundary, and in the middle of one, which the failed read has partly consumed
+        for cut in [10_000, 10_004] {
+            let dir = spill_dir(&format!("failed-read-{cut}"));
+            // one run of 4097 values, more than its reader buffers at once
+            let mut heap = ExternalFibonacciHeap::new(4096 * 8, &dir);
+            for value in 0..4097u64 {
+                heap.push(value);
+            }
+            heap.push(10_000);
+            assert_eq!(heap.run_count(), 1);
+            let run = fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+            let bytes = fs::read(&run).unwrap();
+            // cut the file short past what is buffered, so that a later refill fails
+            fs::OpenOptions::new().write(true).open(&run).unwrap().set_len(cut).unwrap();
+
+            let mut popped = 0;
+            let err = loop {
+                match heap.try_pop() {
+                    Ok(value) => {
+                        assert_eq!(value, Some(popped), "cut {cut}");
+                        popped += 1;
+                    }
+                    Err(err) => break err,
+                }
+            };
+            assert_eq!(err.kind(), io::ErrorKind::Other);
+            assert_eq!(heap.len(), 4098 - popped as usize);
+            assert_eq!(heap.peek(), Some(&popped));
+            // failing again changes nothing
+            assert!(heap.try_pop().is_err());
+            assert_eq!(heap.len(), 4098 - popped as usize);
+
+            // once the file is whole again, popping resumes where it failed
+            fs::write(&run, &bytes).unwrap();
+            let rest: Vec<u64> = std::iter::from_fn(|| heap.pop()).collect();
+            assert_eq!(rest, (popped..4097).chain([10_000]).collect::<Vec<_>>(), "cut {cut}");
+            drop(heap);
+            fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn interleaved_and_cleaned_up_on_drop() {
+        let dir = spill_dir("interleaved");
+        let mut rng = Rng(0xbb67ae8584caa73b);
+        let mut heap = ExternalFibonacciHeap::new(8 * 16, &dir);
+        let mut reference = FibonacciHeap::new();
+        for _ in 0..5000 {
+            if rng.below(3) > 0 {
+                let value = format!("{:05}", rng.below(10_000));
+                heap.push(value.clone());
+                reference.push(value);
+            } else {
+                assert_eq!(heap.pop(), reference.pop());
+            }
+            assert_eq!(heap.len(), reference.len());
+        }
+        assert!(fs::read_dir(&dir).unwrap().count() > 0);
+        drop(heap);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir(&dir).unwrap();
+    }
+}
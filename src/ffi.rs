@@ -0,0 +1,163 @@
+//! C interface, enabled by the `ffi` feature.
+//!
+//! Build the crate as a static or dynamic library (e.g. `cargo rustc --release --features ffi --crate-type staticlib`)
+//! and include `include/fibheap.h`, which is generated by `cbindgen --config cbindgen.toml --output include/fibheap.h`.
+//!
+//! Every function accepts a null heap and reports it (`FIBHEAP_STATUS_NULL`, `false` or `0`); no function unwinds into C,
+//! panics are caught and reported as `FIBHEAP_STATUS_PANIC` (or `false`).
+//! Using a heap after `fibheap_free`, or freeing it twice, is undefined behavior.
+
+use core::cmp::Ordering;
+use core::ffi::c_void;
+use core::ptr;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::FibonacciHeap;
+
+/// Status returned by the fallible functions (`fibheap_status` in C).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok = 0,
+    /// A heap argument was null.
+    Null = 1,
+    /// The key was NaN, which has no place in the order.
+    Nan = 2,
+    /// Both arguments of `fibheap_merge` were the same heap.
+    Aliased = 3,
+    /// A panic was caught at the boundary; the heap may have lost elements but remains safe to use and free.
+    Panic = 4,
+}
+
+struct Entry {
+    key: f64,
+    data: *mut c_void,
+}
+
+// Only keys are compared; data pointers are opaque.
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.key.partial_cmp(&other.key)
+    }
+}
+
+/// Opaque heap of `(key, data)` pairs, smallest key first (`fibheap_t` in C). Equal keys pop in unspecified order.
+pub struct Heap {
+    heap: FibonacciHeap<Entry>,
+}
+
+fn guard<R>(on_panic: R, f: impl FnOnce() -> R) -> R {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(on_panic)
+}
+
+// Writes `entry` to the optional out-parameters.
+unsafe fn write_out(entry: &Entry, out_key: *mut f64, out_data: *mut *mut c_void) {
+    if !out_key.is_null() {
+        *out_key = entry.key;
+    }
+    if !out_data.is_null() {
+        *out_data = entry.data;
+    }
+}
+
+/// Allocates an empty heap. Returns null if a panic occurred.
+#[no_mangle]
+pub extern "C" fn fibheap_new() -> *mut Heap {
+    guard(ptr::null_mut(), || Box::into_raw(Box::new(Heap { heap: FibonacciHeap::new() })))
+}
+
+/// Frees `heap`; null is ignored. The data pointers it still holds are not touched.
+///
+/// # Safety
+/// `heap` must be null or a pointer returned by `fibheap_new` that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn fibheap_free(heap: *mut Heap) {
+    if !heap.is_null() {
+        guard((), || drop(Box::from_raw(heap)));
+    }
+}
+
+/// Inserts `data` with priority `key`.
+///
+/// # Safety
+/// `heap` must be null or a live heap.
+#[no_mangle]
+pub unsafe extern "C" fn fibheap_push(heap: *mut Heap, key: f64, data: *mut c_void) -> Status {
+    let Some(heap) = heap.as_mut() else {
+        return Status::Null;
+    };
+    if key.is_nan() {
+        return Status::Nan;
+    }
+    guard(Status::Panic, || {
+        heap.heap.push(Entry { key, data });
+        Status::Ok
+    })
+}
+
+/// Removes the entry with the smallest key and stores it in `out_key` and `out_data` (either may be null).
+/// Returns `false`, leaving the outputs untouched, if the heap is empty or null.
+///
+/// # Safety
+/// `heap` must be null or a live heap; the outputs must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn fibheap_pop(heap: *mut Heap, out_key: *mut f64, out_data: *mut *mut c_void) -> bool {
+    let Some(heap) = heap.as_mut() else {
+        return false;
+    };
+    match guard(None, || heap.heap.pop()) {
+        Some(entry) => {
+            write_out(&entry, out_key, out_data);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Like `fibheap_pop`, without removing the entry.
+///
+/// # Safety
+/// As for `fibheap_pop`.
+#[no_mangle]
+pub unsafe extern "C" fn fibheap_peek(heap: *const Heap, out_key: *mut f64, out_data: *mut *mut c_void) -> bool {
+    match heap.as_ref().and_then(|heap| heap.heap.peek()) {
+        Some(entry) => {
+            write_out(entry, out_key, out_data);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Number of entries; 0 for null.
+///
+/// # Safety
+/// `heap` must be null or a live heap.
+#[no_mangle]
+pub unsafe extern "C" fn fibheap_len(heap: *const Heap) -> usize {
+    heap.as_ref().map_or(0, |heap| heap.heap.len())
+}
+
+/// Moves every entry of `src` into `dst`. `src` is left empty, and still has to be freed.
+///
+/// # Safety
+/// Both arguments must be null or live heaps.
+#[no_mangle]
+pub unsafe extern "C" fn fibheap_merge(dst: *mut Heap, src: *mut Heap) -> Status {
+    if dst == src {
+        return if dst.is_null() { Status::Null } else { Status::Aliased };
+    }
+    let (Some(dst), Some(src)) = (dst.as_mut(), src.as_mut()) else {
+        return Status::Null;
+    };
+    guard(Status::Panic, || {
+        dst.heap.append(core::mem::take(&mut src.heap));
+        Status::Ok
+    })
+}
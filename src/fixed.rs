@@ -0,0 +1,456 @@
+use core::fmt;
+use core::mem::MaybeUninit;
+
+// Link value meaning "no node".
+const NIL: usize = usize::MAX;
+// Linking only ever joins trees of equal degree, so a tree of degree d holds at least 2^d nodes
+// and degrees stay below log2(N) < usize::BITS.
+const MAX_DEGREE: usize = usize::BITS as usize;
+
+struct Slot<T> {
+    value: T,
+    // first child; children are chained through `sibling`
+    child: usize,
+    // next root, or next child of the parent
+    sibling: usize,
+    degree: usize,
+}
+
+/// Min-heap of at most `N` elements that never allocates, for `no_std` targets without `alloc`.
+///
+/// Nodes live in an inline arena of `N` slots and refer to each other by index;
+/// consolidation uses a fixed-size degree table on the stack.
+/// `push` hands the value back when the heap is full, and [`force_push`](Self::force_push) makes room for smaller
+/// values by evicting the largest, so that the heap keeps the `N` smallest values pushed.
+///
+/// # Example
+/// ```
+/// use fibheap::FixedFibonacciHeap;
+///
+/// let mut heap = FixedFibonacciHeap::<u32, 2>::new();
+/// assert_eq!(heap.push(3), Ok(()));
+/// assert_eq!(heap.push(1), Ok(()));
+/// assert_eq!(heap.push(2), Err(2));
+/// assert_eq!(heap.pop(), Some(1));
+/// assert_eq!(heap.push(2), Ok(()));
+/// assert_eq!(heap.pop(), Some(2));
+/// ```
+pub struct FixedFibonacciHeap<T, const N: usize> {
+    // A slot is initialized exactly when it is reachable from `roots`.
+    slots: [MaybeUninit<Slot<T>>; N],
+    // stack of free slot indices, the top being `free[free_len - 1]`
+    free: [usize; N],
+    free_len: usize,
+    roots: usize,
+    top: usize,
+    len: usize,
+}
+
+impl<T: PartialOrd, const N: usize> FixedFibonacciHeap<T, N> {
+    pub fn new() -> Self {
+        Self {
+            slots: [const { MaybeUninit::uninit() }; N],
+            free: core::array::from_fn(|ix| N - 1 - ix),
+            free_len: N,
+            roots: NIL,
+            top: NIL,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        if self.top == NIL {
+            return None;
+        }
+        Some(&self.slot(self.top).value)
+    }
+
+    #[deprecated(note = "renamed to `peek` for parity with `BinaryHeap`")]
+    pub fn top(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    /// Pushes `value`, or returns it if the heap is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.free_len == 0 {
+            return Err(value);
+        }
+        self.free_len -= 1;
+        let ix = self.free[self.free_len];
+        self.slots[ix].write(Slot { value, child: NIL, sibling: self.roots, degree: 0 });
+        self.roots = ix;
+        self.len += 1;
+        if self.top == NIL || self.slot(ix).value < self.slot(self.top).value {
+            self.top = ix;
+        }
+        Ok(())
+    }
+
+    /// Same as [`push`](Self::push), under the name `Vec` uses for pushes that never allocate nor panic.
+    pub fn push_within_capacity(&mut self, value: T) -> Result<(), T> {
+        self.push(value)
+    }
+
+    /// Pushes `value`; if the heap is full, evicts and returns the largest element in its place when `value` is
+    /// smaller, or returns `value` itself otherwise. The heap thus keeps the `N` smallest values ever pushed, as
+    /// top-k selection wants.
+    ///
+    /// While there is room this costs what `push` does. A full heap doesn't know its largest element, so it scans
+    /// the `N` slots for it: a largest element always sits at a leaf, so only leaves are compared, at most `N - 1`
+    /// times, plus once with `value`. Evicting it then walks the sibling lists to find the one that holds it, as
+    /// nodes have no parent links. A full push is thus O(N), without any memory beyond a few indices. Once a
+    /// panicking comparison has made `pop` leak the nodes, the lost slots are never reclaimed and `value` is returned.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FixedFibonacciHeap;
+    ///
+    /// let mut heap = FixedFibonacciHeap::<u32, 2>::new();
+    /// assert_eq!(heap.force_push(3), None);
+    /// assert_eq!(heap.force_push(5), None);
+    /// assert_eq!(heap.force_push(1), Some(5));
+    /// assert_eq!(heap.force_push(4), Some(4));
+    /// assert_eq!(heap.pop(), Some(1));
+    /// assert_eq!(heap.pop(), Some(3));
+    /// ```
+    pub fn force_push(&mut self, value: T) -> Option<T> {
+        let value = match self.push(value) {
+            Ok(()) => return None,
+            Err(value) => value,
+        };
+        // Every slot holds an element when they are all reachable; after a panicking comparison leaked nodes, there is
+        // no telling which slots are initialized, and no room is made.
+        if self.len < N {
+            return Some(value);
+        }
+        let largest = (0..N)
+            .filter(|&ix| self.slot(ix).child == NIL)
+            .reduce(|largest, ix| if self.slot(largest).value < self.slot(ix).value { ix } else { largest });
+        let Some(largest) = largest.filter(|&largest| value < self.slot(largest).value) else {
+            return Some(value);
+        };
+        self.unlink_leaf(largest);
+        // SAFETY: `largest` was reachable and has just been unlinked; its slot is freed without being read again.
+        let Slot { value: evicted, .. } = unsafe { self.slots[largest].assume_init_read() };
+        self.free[self.free_len] = largest;
+        self.free_len += 1;
+        self.len -= 1;
+        if self.top == largest {
+            // every element equals the evicted one, so `value` becomes the top without a comparison
+            self.top = NIL;
+        }
+        if self.push(value).is_err() {
+            unreachable!("a slot was just freed");
+        }
+        Some(evicted)
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.top == NIL {
+            return None;
+        }
+        let top = self.top;
+        // SAFETY: `top` is reachable from `roots`, hence initialized; it is unlinked below and its slot freed
+        // without being read again.
+        let Slot { value, child, sibling: top_sibling, .. } = unsafe { self.slots[top].assume_init_read() };
+        let roots = self.roots;
+        // Until consolidation finishes the heap looks empty, so a panicking comparison leaks the nodes
+        // instead of leaving links to the slot just vacated.
+        self.roots = NIL;
+        self.top = NIL;
+        self.len -= 1;
+        let len = self.len;
+        self.len = 0;
+
+        let mut table = [NIL; MAX_DEGREE];
+        for list in [roots, child] {
+            let mut ix = list;
+            while ix != NIL {
+                if ix == top {
+                    ix = top_sibling;
+                    continue;
+                }
+                let next = self.slot(ix).sibling;
+                self.link_into(&mut table, ix);
+                ix = next;
+            }
+        }
+        for ix in table.into_iter().filter(|&ix| ix != NIL) {
+            self.slot_mut(ix).sibling = self.roots;
+            self.roots = ix;
+            if self.top == NIL || self.slot(ix).value < self.slot(self.top).value {
+                self.top = ix;
+            }
+        }
+        self.len = len;
+        self.free[self.free_len] = top;
+        self.free_len += 1;
+        Some(value)
+    }
+
+    // Merges the tree rooted at `ix` into the degree table, linking equal-degree trees under the smaller root.
+    fn link_into(&mut self, table: &mut [usize; MAX_DEGREE], mut ix: usize) {
+        loop {
+            let degree = self.slot(ix).degree;
+            let other = table[degree];
+            if other == NIL {
+                table[degree] = ix;
+                return;
+            }
+            table[degree] = NIL;
+            let (parent, child) = if self.slot(other).value < self.slot(ix).value { (other, ix) } else { (ix, other) };
+            let first = self.slot(parent).child;
+            self.slot_mut(child).sibling = first;
+            let parent_slot = self.slot_mut(parent);
+            parent_slot.child = child;
+            parent_slot.degree += 1;
+            ix = parent;
+        }
+    }
+}
+
+impl<T, const N: usize> FixedFibonacciHeap<T, N> {
+    // Takes the leaf `ix` out of the root list or the children of its parent, which it finds by walking every
+    // sibling list, each slot being in exactly one: O(N). Only called on a full heap, whose slots are all initialized.
+    fn unlink_leaf(&mut self, ix: usize) {
+        if let Some(head) = self.remove_from_list(self.roots, ix) {
+            self.roots = head;
+            return;
+        }
+        for parent in 0..N {
+            if let Some(head) = self.remove_from_list(self.slot(parent).child, ix) {
+                let parent = self.slot_mut(parent);
+                parent.child = head;
+                parent.degree -= 1;
+                return;
+            }
+        }
+        unreachable!("slot {ix} is in no sibling list");
+    }
+
+    // Removes `ix` from the sibling list starting at `head`, returning the list's new head, or `None` if it isn't there.
+    fn remove_from_list(&mut self, head: usize, ix: usize) -> Option<usize> {
+        if head == NIL {
+            return None;
+        }
+        let next = self.slot(ix).sibling;
+        if head == ix {
+            return Some(next);
+        }
+        let mut prev = head;
+        while self.slot(prev).sibling != NIL {
+            if self.slot(prev).sibling == ix {
+                self.slot_mut(prev).sibling = next;
+                return Some(head);
+            }
+            prev = self.slot(prev).sibling;
+        }
+        None
+    }
+
+    fn slot(&self, ix: usize) -> &Slot<T> {
+        // SAFETY: only called with indices of reachable, hence initialized, slots.
+        unsafe { self.slots[ix].assume_init_ref() }
+    }
+
+    fn slot_mut(&mut self, ix: usize) -> &mut Slot<T> {
+        // SAFETY: as in `slot`.
+        unsafe { self.slots[ix].assume_init_mut() }
+    }
+}
+
+impl<T: PartialOrd, const N: usize> Default for FixedFibonacciHeap<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for FixedFibonacciHeap<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let top = (self.top != NIL).then(|| &self.slot(self.top).value);
+        f.debug_struct("FixedFibonacciHeap").field("len", &self.len).field("top", &top).finish()
+    }
+}
+
+impl<T, const N: usize> Drop for FixedFibonacciHeap<T, N> {
+    fn drop(&mut self) {
+        // Walk every tree without a stack: drop the head of the work list and splice its children in front.
+        let mut list = self.roots;
+        self.roots = NIL;
+        while list != NIL {
+            let ix = list;
+            let (child, sibling) = (self.slot(ix).child, self.slot(ix).sibling);
+            list = sibling;
+            if child != NIL {
+                let mut last = child;
+                while self.slot(last).sibling != NIL {
+                    last = self.slot(last).sibling;
+                }
+                self.slot_mut(last).sibling = list;
+                list = child;
+            }
+            // SAFETY: `ix` was reachable and has just been unlinked, so it is dropped exactly once.
+            unsafe { self.slots[ix].assume_init_drop() };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{DropCounter, Rng};
+    use crate::FibonacciHeap;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn fill_overflow_drain() {
+        let mut heap = FixedFibonacciHeap::<u32, 8>::new();
+        assert_eq!(heap.pop(), None);
+        for value in [5, 3, 7, 1, 8, 2, 6, 4] {
+            assert_eq!(heap.push(value), Ok(()));
+        }
+        assert!(heap.is_full());
+        assert_eq!(heap.push(0), Err(0));
+        assert_eq!(heap.peek(), Some(&1));
+        for expected in 1..=8 {
+            assert_eq!(heap.pop(), Some(expected));
+        }
+        assert!(heap.is_empty());
+        assert_eq!(heap.pop(), None);
+
+        let mut empty = FixedFibonacciHeap::<u32, 0>::new();
+        assert_eq!(empty.push(1), Err(1));
+        assert_eq!(empty.pop(), None);
+    }
+
+    #[test]
+    fn matches_heap() {
+        let mut rng = Rng(0xcbbb9d5dc1059ed8);
+        let mut fixed = FixedFibonacciHeap::<u64, 64>::new();
+        let mut reference = FibonacciHeap::new();
+        let rounds = if cfg!(miri) { 2000 } else { 20_000 };
+        for _ in 0..rounds {
+            if rng.below(2) == 0 {
+                let value = rng.below(100);
+                match fixed.push(value) {
+                    Ok(()) => reference.push(value),
+                    Err(back) => {
+                        assert_eq!(back, value);
+                        assert_eq!(reference.len(), 64);
+                    }
+                }
+            } else {
+                assert_eq!(fixed.pop(), reference.pop());
+            }
+            assert_eq!(fixed.len(), reference.len());
+            assert_eq!(fixed.peek(), reference.peek());
+        }
+    }
+
+    #[test]
+    fn force_push_at_small_capacities() {
+        let mut empty = FixedFibonacciHeap::<u32, 0>::new();
+        assert_eq!(empty.push_within_capacity(1), Err(1));
+        assert_eq!(empty.force_push(1), Some(1));
+        assert!(empty.is_empty());
+
+        let mut one = FixedFibonacciHeap::<u32, 1>::new();
+        assert_eq!(one.force_push(5), None);
+        assert_eq!(one.push_within_capacity(2), Err(2));
+        assert_eq!(one.force_push(7), Some(7));
+        assert_eq!(one.force_push(5), Some(5));
+        assert_eq!(one.force_push(2), Some(5));
+        assert_eq!(one.peek(), Some(&2));
+        assert_eq!(one.pop(), Some(2));
+        assert_eq!(one.force_push(9), None);
+        assert_eq!(one.pop(), Some(9));
+        assert_eq!(one.pop(), None);
+    }
+
+    #[test]
+    fn force_push_keeps_the_smallest() {
+        let mut heap = FixedFibonacciHeap::<u32, 4>::new();
+        for value in [4, 4, 4, 4] {
+            assert_eq!(heap.force_push(value), None);
+        }
+        // every element is the largest, the top among them
+        assert_eq!(heap.force_push(1), Some(4));
+        assert_eq!(heap.peek(), Some(&1));
+        assert_eq!(heap.force_push(4), Some(4));
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.force_push(0), None);
+        assert_eq!(heap.force_push(2), Some(4));
+        assert_eq!((0..4).map(|_| heap.pop().unwrap()).collect::<Vec<_>>(), [0, 2, 4, 4]);
+
+        let mut rng = Rng(0x1f83d9abfb41bd6b);
+        let mut heap = FixedFibonacciHeap::<u64, 32>::new();
+        let mut pushed = vec![];
+        let rounds = if cfg!(miri) { 300 } else { 5000 };
+        for round in 0..rounds {
+            let value = rng.below(1000);
+            let out = heap.force_push(value);
+            pushed.push(value);
+            pushed.sort_unstable();
+            if pushed.len() > 32 {
+                // the evicted element is the largest of the 33, or `value` itself when it is no smaller
+                assert_eq!(out, pushed.pop());
+            } else {
+                assert_eq!(out, None);
+            }
+            // pops in between keep consolidated trees in the mix
+            if round % 7 == 0 {
+                assert_eq!(heap.pop(), Some(pushed.remove(0)));
+            }
+            assert_eq!(heap.len(), pushed.len());
+            assert_eq!(heap.peek(), pushed.first());
+        }
+        for expected in pushed {
+            assert_eq!(heap.pop(), Some(expected));
+        }
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn drops_only_live_values() {
+        let drops = Rc::new(Cell::new(0));
+        let mut heap = FixedFibonacciHeap::<DropCounter, 16>::new();
+        for value in 0..16 {
+            assert!(heap.push(DropCounter::new(value, &drops)).is_ok());
+        }
+        let rejected = heap.push(DropCounter::new(99, &drops));
+        drop(rejected);
+        assert_eq!(drops.get(), 1);
+        for _ in 0..5 {
+            drop(heap.pop());
+        }
+        assert_eq!(drops.get(), 6);
+        for value in 0..5 {
+            assert!(heap.force_push(DropCounter::new(value, &drops)).is_none());
+        }
+        let evicted = heap.force_push(DropCounter::new(-1, &drops)).unwrap();
+        assert_eq!((evicted.value, drops.get()), (15, 6));
+        drop(evicted);
+        let rejected = heap.force_push(DropCounter::new(99, &drops)).unwrap();
+        assert_eq!((rejected.value, drops.get()), (99, 7));
+        drop(rejected);
+        drop(heap);
+        assert_eq!(drops.get(), 8 + 16);
+    }
+}
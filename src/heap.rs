@@ -0,0 +1,236 @@
+#[cfg(feature = "std")]
+use core::cmp::Reverse;
+#[cfg(feature = "std")]
+use std::collections::BinaryHeap;
+
+use crate::{CompactFibonacciHeap, FibonacciHeap, PairingHeap};
+
+/// Minimal min-priority-queue interface, so that algorithms can be written once and run on either backend.
+///
+/// Implemented for [`FibonacciHeap`], [`CompactFibonacciHeap`], [`PairingHeap`] and, with the `std` feature, for `BinaryHeap<Reverse<T>>`:
+/// `BinaryHeap` is a max-heap, so it is used through `Reverse` and both implementations pop the smallest element first.
+///
+/// # Example
+/// ```
+/// use std::cmp::Reverse;
+/// use std::collections::BinaryHeap;
+/// use fibheap::{FibonacciHeap, Heap};
+///
+/// fn smallest_three<H: Heap<u32> + Default>(values: &[u32]) -> Vec<u32> {
+///     let mut heap = H::default();
+///     values.iter().for_each(|&value| heap.push(value));
+///     std::iter::from_fn(|| heap.pop()).take(3).collect()
+/// }
+///
+/// let values = [7, 3, 9, 1, 4];
+/// assert_eq!(smallest_three::<FibonacciHeap<u32>>(&values), [1, 3, 4]);
+/// # #[cfg(feature = "std")]
+/// assert_eq!(smallest_three::<BinaryHeap<Reverse<u32>>>(&values), [1, 3, 4]);
+/// ```
+pub trait Heap<T> {
+    fn push(&mut self, value: T);
+
+    /// Removes and returns the smallest element.
+    fn pop(&mut self) -> Option<T>;
+
+    /// The smallest element.
+    fn peek(&self) -> Option<&T>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Moves every element of `other` into `self`.
+    fn append(&mut self, other: Self)
+    where
+        Self: Sized;
+}
+
+impl<T: PartialOrd> Heap<T> for FibonacciHeap<T> {
+    fn push(&mut self, value: T) {
+        FibonacciHeap::push(self, value)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        FibonacciHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    fn len(&self) -> usize {
+        FibonacciHeap::len(self)
+    }
+
+    fn append(&mut self, other: Self) {
+        FibonacciHeap::append(self, other)
+    }
+}
+
+impl<T: PartialOrd> Heap<T> for PairingHeap<T> {
+    fn push(&mut self, value: T) {
+        PairingHeap::push(self, value)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        PairingHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        PairingHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        PairingHeap::len(self)
+    }
+
+    fn append(&mut self, other: Self) {
+        PairingHeap::append(self, other)
+    }
+}
+
+impl<T: PartialOrd> Heap<T> for CompactFibonacciHeap<T> {
+    fn push(&mut self, value: T) {
+        CompactFibonacciHeap::push(self, value)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        CompactFibonacciHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        CompactFibonacciHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        CompactFibonacciHeap::len(self)
+    }
+
+    fn append(&mut self, other: Self) {
+        CompactFibonacciHeap::append(self, other)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Ord> Heap<T> for BinaryHeap<Reverse<T>> {
+    fn push(&mut self, value: T) {
+        BinaryHeap::push(self, Reverse(value))
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        BinaryHeap::pop(self).map(|Reverse(value)| value)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        BinaryHeap::peek(self).map(|Reverse(value)| value)
+    }
+
+    fn len(&self) -> usize {
+        BinaryHeap::len(self)
+    }
+
+    fn append(&mut self, mut other: Self) {
+        BinaryHeap::append(self, &mut other)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+
+    // Dijkstra over a random graph, generic over the queue.
+    fn shortest_paths<H: Heap<(u64, usize)> + Default>(edges: &[Vec<(usize, u64)>]) -> Vec<u64> {
+        let mut dist = vec![u64::MAX; edges.len()];
+        let mut queue = H::default();
+        dist[0] = 0;
+        queue.push((0, 0));
+        while let Some((d, node)) = queue.pop() {
+            if d > dist[node] {
+                continue;
+            }
+            for &(next, weight) in &edges[node] {
+                if d + weight < dist[next] {
+                    dist[next] = d + weight;
+                    queue.push((d + weight, next));
+                }
+            }
+        }
+        assert!(queue.is_empty());
+        dist
+    }
+
+    #[test]
+    fn same_algorithm_on_every_backend() {
+        let mut rng = Rng(0x8f1bbcdcca62c1d6);
+        let edges: Vec<Vec<(usize, u64)>> =
+            (0..500).map(|_| (0..8).map(|_| (rng.below(500) as usize, rng.below(100))).collect()).collect();
+        let fib = shortest_paths::<FibonacciHeap<_>>(&edges);
+        let binary = shortest_paths::<BinaryHeap<Reverse<_>>>(&edges);
+        assert_eq!(fib, binary);
+        assert_eq!(shortest_paths::<CompactFibonacciHeap<_>>(&edges), fib);
+        assert_eq!(shortest_paths::<PairingHeap<_>>(&edges), fib);
+    }
+
+    fn merge_and_drain<H: Heap<u32> + Default>(a: &[u32], b: &[u32]) -> (Option<u32>, usize, Vec<u32>) {
+        let (mut x, mut y) = (H::default(), H::default());
+        a.iter().for_each(|&v| x.push(v));
+        b.iter().for_each(|&v| y.push(v));
+        x.append(y);
+        let peek = x.peek().copied();
+        let len = x.len();
+        (peek, len, core::iter::from_fn(|| x.pop()).collect())
+    }
+
+    #[test]
+    fn append_and_peek_agree() {
+        let (a, b) = ([5, 1, 9], [4, 0, 4]);
+        let fib = merge_and_drain::<FibonacciHeap<u32>>(&a, &b);
+        assert_eq!(fib, (Some(0), 6, vec![0, 1, 4, 4, 5, 9]));
+        assert_eq!(merge_and_drain::<BinaryHeap<Reverse<u32>>>(&a, &b), fib);
+        assert_eq!(merge_and_drain::<CompactFibonacciHeap<u32>>(&a, &b), fib);
+        assert_eq!(merge_and_drain::<PairingHeap<u32>>(&a, &b), fib);
+        assert_eq!(merge_and_drain::<FibonacciHeap<u32>>(&[], &[]), (None, 0, vec![]));
+    }
+
+    // Random pushes, pops and appends checked against a sorted vector.
+    fn matches_sorted_model<H: Heap<u64> + Default>() {
+        let mut rng = Rng(0x3956c25bf348b538);
+        let mut heap = H::default();
+        // kept in descending order, so that the minimum is at the end
+        let mut model: Vec<u64> = vec![];
+        let insert = |model: &mut Vec<u64>, value| model.insert(model.partition_point(|&v| v > value), value);
+        for _ in 0..10_000 {
+            match rng.below(6) {
+                0..=2 => {
+                    let value = rng.below(500);
+                    heap.push(value);
+                    insert(&mut model, value);
+                }
+                3 => {
+                    let mut other = H::default();
+                    for _ in 0..rng.below(10) {
+                        let value = rng.below(500);
+                        other.push(value);
+                        insert(&mut model, value);
+                    }
+                    heap.append(other);
+                }
+                _ => assert_eq!(heap.pop(), model.pop()),
+            }
+            assert_eq!(heap.len(), model.len());
+            assert_eq!(heap.peek(), model.last());
+        }
+    }
+
+    #[test]
+    fn random_operations_on_every_backend() {
+        matches_sorted_model::<FibonacciHeap<u64>>();
+        matches_sorted_model::<CompactFibonacciHeap<u64>>();
+        matches_sorted_model::<PairingHeap<u64>>();
+        matches_sorted_model::<BinaryHeap<Reverse<u64>>>();
+    }
+}
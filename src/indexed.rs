@@ -0,0 +1,441 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+// Link value meaning "no node".
+const NIL: usize = usize::MAX;
+// With cascading cuts a tree of degree d still holds at least F(d+2) >= phi^d nodes, so degrees stay below
+// log_phi(usize::MAX) < 2 * usize::BITS.
+const MAX_DEGREE: usize = 2 * usize::BITS as usize;
+
+/// Priorities of the indices in an [`IndexedFibonacciHeap`], stored by the caller.
+///
+/// Implemented for vectors and slices, indexed directly, and for closures from index to priority.
+pub trait Priorities {
+    type Priority: PartialOrd;
+
+    fn priority(&self, index: usize) -> Self::Priority;
+}
+
+impl<P: PartialOrd + Clone> Priorities for [P] {
+    type Priority = P;
+
+    fn priority(&self, index: usize) -> P {
+        self[index].clone()
+    }
+}
+
+impl<P: PartialOrd + Clone> Priorities for Vec<P> {
+    type Priority = P;
+
+    fn priority(&self, index: usize) -> P {
+        self[index].clone()
+    }
+}
+
+impl<P: PartialOrd, F: Fn(usize) -> P> Priorities for F {
+    type Priority = P;
+
+    fn priority(&self, index: usize) -> P {
+        self(index)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Links {
+    parent: usize,
+    child: usize,
+    // siblings, in a circular list; roots are the siblings of `top`
+    left: usize,
+    right: usize,
+    degree: usize,
+    // lost a child since it last became a child itself
+    marked: bool,
+    contained: bool,
+}
+
+const UNLINKED: Links = Links { parent: NIL, child: NIL, left: NIL, right: NIL, degree: 0, marked: false, contained: false };
+
+/// Min-heap of indices `0..n` ordered by priorities the caller stores, with O(1) amortized
+/// [`decrease_key`](Self::decrease_key): the classic structure for Dijkstra and Prim over a `dist` array.
+///
+/// The heap owns its [`Priorities`], typically the `dist` vector itself, and lends it out through
+/// [`priorities_mut`](Self::priorities_mut). After lowering the priority of an index in the heap, call
+/// `decrease_key` on it before any other operation; raising one is not supported, and debug builds check for it.
+/// A closure over priorities kept elsewhere (in `Cell`s, say) works too.
+///
+/// The node of index `i` lives at position `i` of an internal table, which grows to the largest index pushed,
+/// so nothing is allocated per push and no key pairs are stored. Decreased nodes are cut from their parent with
+/// the usual cascading cuts, so `pop` stays O(log n) amortized.
+///
+/// # Example
+/// ```
+/// use fibheap::IndexedFibonacciHeap;
+///
+/// let mut heap = IndexedFibonacciHeap::new(vec![5, 3, 8]);
+/// for index in 0..3 {
+///     heap.push(index);
+/// }
+/// heap.priorities_mut()[2] = 1;
+/// heap.decrease_key(2);
+/// assert_eq!(heap.pop(), Some(2));
+/// assert_eq!(heap.pop(), Some(1));
+/// assert!(heap.contains(0));
+/// ```
+pub struct IndexedFibonacciHeap<S: Priorities + ?Sized> {
+    links: Vec<Links>,
+    top: usize,
+    len: usize,
+    // the priority of each index when last seen, to catch increases
+    #[cfg(debug_assertions)]
+    seen: Vec<Option<S::Priority>>,
+    priorities: S,
+}
+
+impl<S: Priorities> IndexedFibonacciHeap<S> {
+    pub fn new(priorities: S) -> Self {
+        Self {
+            links: Vec::new(),
+            top: NIL,
+            len: 0,
+            #[cfg(debug_assertions)]
+            seen: Vec::new(),
+            priorities,
+        }
+    }
+
+    pub fn into_priorities(self) -> S {
+        self.priorities
+    }
+}
+
+impl<S: Priorities + ?Sized> IndexedFibonacciHeap<S> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.links.get(index).is_some_and(|links| links.contained)
+    }
+
+    /// The index with the smallest priority.
+    pub fn peek(&self) -> Option<usize> {
+        (self.top != NIL).then_some(self.top)
+    }
+
+    pub fn priorities(&self) -> &S {
+        &self.priorities
+    }
+
+    /// The priorities, to be lowered before calling [`decrease_key`](Self::decrease_key) on the indices changed.
+    pub fn priorities_mut(&mut self) -> &mut S {
+        &mut self.priorities
+    }
+
+    /// Adds `index`, whose priority is read now.
+    ///
+    /// # Panics
+    /// Panics if `index` is already in the heap.
+    pub fn push(&mut self, index: usize) {
+        if index >= self.links.len() {
+            self.links.resize(index + 1, UNLINKED);
+            #[cfg(debug_assertions)]
+            self.seen.resize_with(index + 1, || None);
+        }
+        assert!(!self.links[index].contained, "index {index} is already in the heap");
+        self.links[index] = Links { contained: true, ..UNLINKED };
+        self.remember(index);
+        self.add_root(index);
+        self.len += 1;
+    }
+
+    /// Removes and returns the index with the smallest priority.
+    pub fn pop(&mut self) -> Option<usize> {
+        let top = self.peek()?;
+        let mut child = self.links[top].child;
+        while child != NIL {
+            let next = self.links[child].right;
+            let last = next == self.links[top].child;
+            self.links[child].parent = NIL;
+            self.links[child].marked = false;
+            self.splice_after(top, child);
+            child = if last { NIL } else { next };
+        }
+        let next_root = self.links[top].right;
+        self.unlink(top);
+        self.links[top] = UNLINKED;
+        #[cfg(debug_assertions)]
+        {
+            self.seen[top] = None;
+        }
+        self.len -= 1;
+        self.top = if next_root == top { NIL } else { next_root };
+        self.consolidate();
+        Some(top)
+    }
+
+    /// Restores heap order after the priority of `index` was lowered, in O(1) amortized.
+    ///
+    /// # Panics
+    /// Panics if `index` isn't in the heap, and in debug builds if its priority went up since it was last seen.
+    pub fn decrease_key(&mut self, index: usize) {
+        assert!(self.contains(index), "index {index} is not in the heap");
+        self.remember(index);
+        let parent = self.links[index].parent;
+        if parent != NIL && self.less(index, parent) {
+            self.cut(index);
+            self.cascading_cut(parent);
+        }
+        if self.less(index, self.top) {
+            self.top = index;
+        }
+    }
+
+    fn less(&self, a: usize, b: usize) -> bool {
+        self.priorities.priority(a) < self.priorities.priority(b)
+    }
+
+    #[cfg(debug_assertions)]
+    fn remember(&mut self, index: usize) {
+        let priority = self.priorities.priority(index);
+        if let Some(seen) = &self.seen[index] {
+            debug_assert!(priority.partial_cmp(seen) != Some(core::cmp::Ordering::Greater), "priority of index {index} went up while it was in the heap");
+        }
+        self.seen[index] = Some(priority);
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn remember(&mut self, _index: usize) {}
+
+    // Makes `node` a root and the top if it is smaller.
+    fn add_root(&mut self, node: usize) {
+        if self.top == NIL {
+            self.links[node].left = node;
+            self.links[node].right = node;
+            self.top = node;
+        } else {
+            self.splice_after(self.top, node);
+            if self.less(node, self.top) {
+                self.top = node;
+            }
+        }
+    }
+
+    // Inserts `node` into the sibling list of `at`, right after it.
+    fn splice_after(&mut self, at: usize, node: usize) {
+        let right = self.links[at].right;
+        self.links[node].left = at;
+        self.links[node].right = right;
+        self.links[at].right = node;
+        self.links[right].left = node;
+    }
+
+    // Takes `node` out of its sibling list, leaving it a list of its own.
+    fn unlink(&mut self, node: usize) {
+        let Links { left, right, .. } = self.links[node];
+        self.links[left].right = right;
+        self.links[right].left = left;
+        self.links[node].left = node;
+        self.links[node].right = node;
+    }
+
+    // Moves `node` from its parent's children to the roots.
+    fn cut(&mut self, node: usize) {
+        let parent = self.links[node].parent;
+        let right = self.links[node].right;
+        if self.links[parent].child == node {
+            self.links[parent].child = if right == node { NIL } else { right };
+        }
+        self.links[parent].degree -= 1;
+        self.unlink(node);
+        self.links[node].parent = NIL;
+        self.links[node].marked = false;
+        self.splice_after(self.top, node);
+    }
+
+    // Cuts the ancestors of a node that lost a child, for as long as they had already lost one.
+    fn cascading_cut(&mut self, mut node: usize) {
+        while self.links[node].parent != NIL {
+            if !self.links[node].marked {
+                self.links[node].marked = true;
+                return;
+            }
+            let parent = self.links[node].parent;
+            self.cut(node);
+            node = parent;
+        }
+    }
+
+    // Links the roots until no two have the same degree, and finds the new top.
+    fn consolidate(&mut self) {
+        if self.top == NIL {
+            return;
+        }
+        let mut table = [NIL; MAX_DEGREE];
+        let mut rest = self.top;
+        while rest != NIL {
+            let root = rest;
+            let next = self.links[root].right;
+            rest = if next == root { NIL } else { next };
+            self.unlink(root);
+            let mut node = root;
+            loop {
+                let degree = self.links[node].degree;
+                let other = table[degree];
+                if other == NIL {
+                    table[degree] = node;
+                    break;
+                }
+                table[degree] = NIL;
+                let (parent, child) = if self.less(other, node) { (other, node) } else { (node, other) };
+                self.link(parent, child);
+                node = parent;
+            }
+        }
+        self.top = NIL;
+        for node in table.into_iter().filter(|&node| node != NIL) {
+            self.add_root(node);
+        }
+    }
+
+    // Makes the root `child` a child of the root `parent`.
+    fn link(&mut self, parent: usize, child: usize) {
+        self.links[child].parent = parent;
+        self.links[child].marked = false;
+        match self.links[parent].child {
+            NIL => self.links[parent].child = child,
+            first => self.splice_after(first, child),
+        }
+        self.links[parent].degree += 1;
+    }
+}
+
+/// Shows the indices in the heap, so the priorities needn't be `Debug`.
+impl<S: Priorities + ?Sized> fmt::Debug for IndexedFibonacciHeap<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let contained = self.links.iter().enumerate().filter(|(_, links)| links.contained).map(|(index, _)| index);
+        f.debug_set().entries(contained).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+    use alloc::vec;
+
+    #[test]
+    fn matches_sorting_after_random_decreases() {
+        let mut rng = Rng(0x2545f4914f6cdd1d);
+        let mut heap = IndexedFibonacciHeap::new(vec![0u64; 2000]);
+        for index in 0..2000 {
+            heap.priorities_mut()[index] = 1_000_000 + rng.below(1_000_000);
+            heap.push(index);
+        }
+        // a few pops build deep trees for the decreases to cut into
+        let mut popped = vec![];
+        for _ in 0..10 {
+            popped.push(heap.pop().unwrap());
+        }
+        for _ in 0..5000 {
+            let index = rng.below(2000) as usize;
+            if heap.contains(index) {
+                let lowered = heap.priorities()[index] - rng.below(2000).min(heap.priorities()[index]);
+                heap.priorities_mut()[index] = lowered;
+                heap.decrease_key(index);
+            }
+        }
+        while let Some(index) = heap.pop() {
+            popped.push(index);
+        }
+        let dist = heap.into_priorities();
+        let mut expected = popped.clone();
+        expected[10..].sort_by_key(|&index| dist[index]);
+        assert_eq!(popped.iter().map(|&index| dist[index]).collect::<Vec<_>>(), expected.iter().map(|&index| dist[index]).collect::<Vec<_>>());
+        popped.sort_unstable();
+        assert_eq!(popped, (0..2000).collect::<Vec<_>>());
+    }
+
+    // Dijkstra with the distances as the priorities, so nothing but indices is ever queued.
+    fn shortest_paths(edges: &[Vec<(usize, u64)>]) -> Vec<u64> {
+        let mut heap = IndexedFibonacciHeap::new(vec![u64::MAX; edges.len()]);
+        heap.priorities_mut()[0] = 0;
+        heap.push(0);
+        let mut done = vec![false; edges.len()];
+        while let Some(node) = heap.pop() {
+            done[node] = true;
+            let d = heap.priorities()[node];
+            for &(next, weight) in &edges[node] {
+                if !done[next] && d + weight < heap.priorities()[next] {
+                    heap.priorities_mut()[next] = d + weight;
+                    if heap.contains(next) {
+                        heap.decrease_key(next);
+                    } else {
+                        heap.push(next);
+                    }
+                }
+            }
+        }
+        heap.into_priorities()
+    }
+
+    #[test]
+    fn dijkstra_matches_lazy_deletion() {
+        let mut rng = Rng(0x8f1bbcdcca62c1d6);
+        let edges: Vec<Vec<(usize, u64)>> =
+            (0..500).map(|_| (0..8).map(|_| (rng.below(500) as usize, rng.below(100))).collect()).collect();
+        let mut dist = vec![u64::MAX; edges.len()];
+        let mut queue = crate::FibonacciHeap::new();
+        dist[0] = 0;
+        queue.push((0, 0));
+        while let Some((d, node)) = queue.pop() {
+            if d > dist[node] {
+                continue;
+            }
+            for &(next, weight) in &edges[node] {
+                if d + weight < dist[next] {
+                    dist[next] = d + weight;
+                    queue.push((d + weight, next));
+                }
+            }
+        }
+        assert_eq!(shortest_paths(&edges), dist);
+    }
+
+    #[test]
+    fn reinsert_and_sparse_indices() {
+        let mut heap = IndexedFibonacciHeap::new(|index: usize| (index % 7, index));
+        heap.push(1000);
+        heap.push(3);
+        assert_eq!(heap.len(), 2);
+        assert!(!heap.contains(500));
+        assert_eq!(heap.pop(), Some(3));
+        heap.push(3);
+        assert_eq!(heap.pop(), Some(3));
+        assert_eq!(heap.pop(), Some(1000));
+        assert_eq!(heap.pop(), None);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "already in the heap")]
+    fn push_twice() {
+        let mut heap = IndexedFibonacciHeap::new(vec![1, 2]);
+        heap.push(0);
+        heap.push(0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "went up")]
+    fn increase_is_caught() {
+        let mut heap = IndexedFibonacciHeap::new(vec![1, 2]);
+        heap.push(0);
+        heap.push(1);
+        heap.priorities_mut()[0] = 5;
+        heap.decrease_key(0);
+    }
+}
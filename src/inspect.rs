@@ -0,0 +1,114 @@
+use core::fmt;
+
+use crate::{FibonacciHeap, Node};
+
+/// Read-only view of a node of a heap's forest, returned by [`FibonacciHeap::roots`] and [`NodeRef::children`].
+///
+/// A `NodeRef` borrows the heap, so the heap can't change while it's in use, and it offers no way to change
+/// the heap either. It is a plain reference: copying it and walking the trees don't allocate.
+///
+/// # Example
+/// ```
+/// use fibheap::{FibonacciHeap, NodeRef};
+///
+/// // the depth of the deepest tree, without going through strings
+/// fn height<T>(node: NodeRef<'_, T>) -> usize {
+///     1 + node.children().map(height).max().unwrap_or(0)
+/// }
+///
+/// let mut heap = FibonacciHeap::from_vec((0..8).collect());
+/// heap.pop();
+/// heap.consolidate();
+/// let degrees: Vec<_> = heap.roots().map(|root| root.degree()).collect();
+/// assert_eq!(degrees, [0, 1, 2]);
+/// assert_eq!(heap.roots().map(height).max(), Some(3));
+/// assert_eq!(heap.roots().find(|root| root.is_top()).map(|root| *root.value()), Some(1));
+/// ```
+pub struct NodeRef<'a, T> {
+    node: &'a Node<T>,
+    top: bool,
+}
+
+impl<T> FibonacciHeap<T> {
+    /// Iterates over the roots of the forest, in root-list order; see [`NodeRef`].
+    ///
+    /// The shape of the forest depends on the operations done so far and isn't part of the heap's contract,
+    /// except that every element sits below its parent and the minimum is a root.
+    pub fn roots(&self) -> impl ExactSizeIterator<Item = NodeRef<'_, T>> + DoubleEndedIterator + Clone {
+        let top_index = self.top_index;
+        self.roots.iter().enumerate().map(move |(ix, node)| NodeRef { node, top: ix == top_index })
+    }
+}
+
+impl<'a, T> NodeRef<'a, T> {
+    /// The element stored in this node.
+    pub fn value(&self) -> &'a T {
+        self.node.value()
+    }
+
+    /// Number of children of this node.
+    pub fn degree(&self) -> usize {
+        self.node.degree()
+    }
+
+    /// Iterates over the children of this node.
+    pub fn children(&self) -> impl ExactSizeIterator<Item = NodeRef<'a, T>> + DoubleEndedIterator + Clone {
+        self.node.children.iter().map(|node| NodeRef { node, top: false })
+    }
+
+    /// Whether this is the root tracked as the minimum, i.e. the one `peek` returns.
+    pub fn is_top(&self) -> bool {
+        self.top
+    }
+}
+
+impl<T> Clone for NodeRef<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for NodeRef<'_, T> {}
+
+impl<T: fmt::Debug> fmt::Debug for NodeRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NodeRef").field("value", self.value()).field("degree", &self.degree()).field("is_top", &self.top).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn covers_every_element() {
+        let mut heap = FibonacciHeap::new();
+        for i in 0..1000u32 {
+            heap.push(i * 7919 % 1009);
+            if i % 3 == 0 {
+                heap.pop();
+            }
+        }
+        let mut values = vec![];
+        let mut stack: Vec<_> = heap.roots().collect();
+        while let Some(node) = stack.pop() {
+            assert_eq!(node.children().len(), node.degree());
+            assert!(node.children().all(|child| child.value() >= node.value()));
+            values.push(*node.value());
+            stack.extend(node.children());
+        }
+        assert_eq!(values.len(), heap.len());
+        values.sort_unstable();
+        let mut contents = heap.clone().into_vec();
+        contents.sort_unstable();
+        assert_eq!(values, contents);
+
+        assert_eq!(heap.roots().len(), heap.root_count());
+        let tops: Vec<_> = heap.roots().filter(NodeRef::is_top).collect();
+        assert_eq!(tops.len(), 1);
+        assert_eq!(Some(tops[0].value()), heap.peek());
+        assert_eq!(FibonacciHeap::<u32>::new().roots().count(), 0);
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(feature = "instrument")]
+use crate::FibonacciHeap;
+
+/// Operations counted by a [`FibonacciHeap`] since it was created or [`reset_counters`](FibonacciHeap::reset_counters)
+/// was last called, as reported by [`FibonacciHeap::counters`].
+///
+/// Only available with the `instrument` feature; without it the heap carries no counters and counts nothing.
+#[cfg(feature = "instrument")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OpCounters {
+    /// Comparisons between elements, including those of the closure given to `remove`-style operations.
+    /// `validate` and `iter_sorted` are not counted.
+    pub comparisons: u64,
+    /// Roots made the child of another root while consolidating.
+    pub links: u64,
+    /// Subtrees moved to the root list from below another node: when a small heap is flattened, and when an element
+    /// is removed from inside a tree. The children of a popped root are promoted, not cut.
+    pub cuts: u64,
+    /// Scans of the whole root list for its minimum.
+    pub root_scans: u64,
+    /// Children vectors allocated or grown by a link, rather than taken from the spare ones.
+    /// Nodes themselves are stored inline in these vectors and in the root list.
+    pub allocations: u64,
+}
+
+// What a heap counts: `OpCounters` with the `instrument` feature, nothing at all without it.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Counters {
+    #[cfg(feature = "instrument")]
+    counts: OpCounters,
+}
+
+#[cfg(feature = "instrument")]
+impl Counters {
+    pub(crate) fn comparison(&mut self) {
+        self.counts.comparisons += 1;
+    }
+
+    pub(crate) fn link(&mut self) {
+        self.counts.links += 1;
+    }
+
+    pub(crate) fn cuts(&mut self, cuts: usize) {
+        self.counts.cuts += cuts as u64;
+    }
+
+    pub(crate) fn root_scan(&mut self) {
+        self.counts.root_scans += 1;
+    }
+
+    pub(crate) fn allocation(&mut self) {
+        self.counts.allocations += 1;
+    }
+
+    pub(crate) fn merge(&mut self, other: Self) {
+        let OpCounters { comparisons, links, cuts, root_scans, allocations } = other.counts;
+        self.counts.comparisons += comparisons;
+        self.counts.links += links;
+        self.counts.cuts += cuts;
+        self.counts.root_scans += root_scans;
+        self.counts.allocations += allocations;
+    }
+}
+
+#[cfg(not(feature = "instrument"))]
+impl Counters {
+    #[inline(always)]
+    pub(crate) fn comparison(&mut self) {}
+
+    #[inline(always)]
+    pub(crate) fn link(&mut self) {}
+
+    #[inline(always)]
+    pub(crate) fn cuts(&mut self, _cuts: usize) {}
+
+    #[inline(always)]
+    pub(crate) fn root_scan(&mut self) {}
+
+    #[inline(always)]
+    pub(crate) fn allocation(&mut self) {}
+
+    #[inline(always)]
+    pub(crate) fn merge(&mut self, _other: Self) {}
+}
+
+#[cfg(feature = "instrument")]
+impl<T> FibonacciHeap<T> {
+    /// Operations counted so far; see [`OpCounters`]. Appending another heap adds its counts to these.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let mut heap = FibonacciHeap::from_vec((0..100).rev().collect());
+    /// heap.reset_counters();
+    /// heap.pop();
+    /// let counters = heap.counters();
+    /// // 99 roots are linked into trees of distinct degrees
+    /// assert_eq!(counters.links, 99 - 99usize.count_ones() as u64);
+    /// assert_eq!(counters.root_scans, 1);
+    /// ```
+    pub fn counters(&self) -> OpCounters {
+        self.counters.counts
+    }
+
+    pub fn reset_counters(&mut self) {
+        self.counters = Counters::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(feature = "instrument"))]
+    fn counts_nothing_without_the_feature() {
+        assert_eq!(core::mem::size_of::<Counters>(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "instrument")]
+    fn counts_cuts_and_allocations() {
+        let mut heap = FibonacciHeap::from_vec((0..64).rev().collect());
+        heap.pop();
+        let counters = heap.counters();
+        assert_eq!(counters.links, 63 - 63usize.count_ones() as u64);
+        assert!(counters.allocations > 0 && counters.allocations <= counters.links);
+        assert_eq!(counters.cuts, 0);
+
+        // popping below the flat limit turns every node into a root again
+        heap.reset_counters();
+        while heap.len() > 8 {
+            heap.pop();
+        }
+        assert!(heap.counters().cuts > 0);
+        assert_eq!(heap.root_count(), heap.len());
+    }
+}
@@ -0,0 +1,169 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+
+use crate::{FibonacciHeap, Node};
+
+/// Iterator over references to the elements of a heap, in unspecified order.
+///
+/// Returned by [`FibonacciHeap::iter`] and [`PairingHeap::iter`](crate::PairingHeap::iter).
+pub struct Iter<'a, T> {
+    stack: Vec<&'a Node<T>>,
+    remaining: usize,
+}
+
+impl<'a, T> Iter<'a, T> {
+    // `len` is the number of nodes in the trees of `roots`.
+    pub(crate) fn new(roots: &'a [Node<T>], len: usize) -> Self {
+        Self { stack: roots.iter().collect(), remaining: len }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.stack.pop()?;
+        self.stack.extend(&node.children);
+        self.remaining -= 1;
+        Some(node.value())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self { stack: self.stack.clone(), remaining: self.remaining }
+    }
+}
+
+/// Shows how many elements are left, so `T` needn't be `Debug`.
+impl<T> fmt::Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Iter").field("remaining", &self.remaining).finish_non_exhaustive()
+    }
+}
+
+/// Iterator over references to the elements of a heap in ascending order, without modifying the heap.
+///
+/// Returned by [`FibonacciHeap::iter_sorted`] and [`PairingHeap::iter_sorted`](crate::PairingHeap::iter_sorted).
+pub struct IterSorted<'a, T> {
+    // Frontier of the walk: every node not yet yielded whose parent has been.
+    frontier: FibonacciHeap<ByValue<'a, T>>,
+}
+
+impl<'a, T: PartialOrd> IterSorted<'a, T> {
+    pub(crate) fn new(roots: &'a [Node<T>]) -> Self {
+        Self { frontier: FibonacciHeap::from_vec(roots.iter().map(ByValue).collect()) }
+    }
+}
+
+impl<'a, T: PartialOrd> Iterator for IterSorted<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let ByValue(node) = self.frontier.pop()?;
+        for child in &node.children {
+            self.frontier.push(ByValue(child));
+        }
+        Some(node.value())
+    }
+}
+
+/// Copies the frontier, a few nodes per tree; the copy continues from the same element.
+impl<T> Clone for IterSorted<'_, T> {
+    fn clone(&self) -> Self {
+        Self { frontier: self.frontier.clone() }
+    }
+}
+
+/// Shows how many nodes the walk may yield next, so `T` needn't be `Debug`.
+impl<T> fmt::Debug for IterSorted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IterSorted").field("frontier", &self.frontier.len()).finish_non_exhaustive()
+    }
+}
+
+// Node reference ordered by the node's value.
+struct ByValue<'a, T>(&'a Node<T>);
+
+impl<T> Clone for ByValue<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ByValue<'_, T> {}
+
+impl<T: PartialOrd> PartialEq for ByValue<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.value() == other.0.value()
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for ByValue<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.0.value().partial_cmp(other.0.value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::Rng;
+    use crate::{assert_heap_eq, FibonacciHeap};
+
+    #[test]
+    fn iter_and_iter_sorted() {
+        let heap = FibonacciHeap::<u8>::new();
+        assert_eq!(heap.iter().next(), None);
+        assert_eq!(heap.iter_sorted().next(), None);
+
+        let mut rng = Rng(0x9b05688c2b3e6c1f);
+        let mut heap = FibonacciHeap::new();
+        let mut expected = vec![];
+        for _ in 0..3000 {
+            let value = rng.below(200);
+            heap.push(value);
+            expected.push(value);
+            if rng.below(4) == 0 {
+                let popped = heap.pop().unwrap();
+                let ix = expected.iter().position(|&v| v == popped).unwrap();
+                expected.swap_remove(ix);
+            }
+        }
+        expected.sort_unstable();
+
+        assert_eq!(heap.iter().len(), heap.len());
+        let mut unordered: Vec<u64> = heap.iter().copied().collect();
+        unordered.sort_unstable();
+        assert_eq!(unordered, expected);
+        assert!(heap.iter_sorted().copied().eq(expected.iter().copied()));
+        assert_heap_eq!(heap, expected);
+    }
+
+    #[test]
+    fn clones_continue_independently() {
+        let mut heap = FibonacciHeap::new();
+        for i in 0..500u32 {
+            heap.push(i * 7 % 500);
+        }
+        heap.pop();
+        let mut sorted = heap.iter_sorted();
+        let mut unordered = heap.iter();
+        for _ in 0..100 {
+            sorted.next();
+            unordered.next();
+        }
+        let (sorted_copy, unordered_copy) = (sorted.clone(), unordered.clone());
+        assert_eq!(unordered_copy.len(), 399);
+        assert!(sorted_copy.copied().eq(101..500));
+        assert!(sorted.copied().eq(101..500));
+        assert!(unordered_copy.eq(unordered));
+        assert_eq!(format!("{:?}", heap.iter()), "Iter { remaining: 499, .. }");
+    }
+}
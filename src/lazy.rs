@@ -0,0 +1,300 @@
+use core::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::{FibonacciHeap, HeapMemoryUsage};
+
+/// Min-heap of keys by priority that replaces decrease-key with stale entries, as graph searches without handles do.
+///
+/// [`push_improving`](Self::push_improving) queues a new `(priority, key)` entry only when it beats the best priority
+/// recorded for the key, leaving the entry it replaces in the heap; [`pop`](Self::pop) discards such stale entries as
+/// they come up and only returns live ones, each key at its best priority. A popped key keeps its record, so pushing
+/// it again only succeeds with a better priority, which is what Dijkstra and A* want of settled nodes.
+///
+/// Compared to [`IndexedFibonacciHeap`](crate::IndexedFibonacciHeap), keys can be anything hashable and no index
+/// space is needed, at the cost of a hash map lookup per operation and of the stale entries, which are stored and
+/// popped like live ones: [`garbage_ratio`](Self::garbage_ratio) tells how much of the heap they make up.
+///
+/// # Example
+/// ```
+/// use fibheap::LazyKeyedHeap;
+///
+/// let mut heap = LazyKeyedHeap::new();
+/// assert!(heap.push_improving("b", 5));
+/// assert!(heap.push_improving("a", 7));
+/// assert!(heap.push_improving("a", 3));
+/// assert!(!heap.push_improving("b", 6));
+/// assert_eq!((heap.len(), heap.queued_len()), (2, 3));
+/// assert_eq!(heap.pop(), Some(("a", 3)));
+/// assert_eq!(heap.pop(), Some(("b", 5)));
+/// // the stale entry of "a" is skipped
+/// assert_eq!(heap.pop(), None);
+/// ```
+#[derive(Debug)]
+pub struct LazyKeyedHeap<K, P> {
+    heap: FibonacciHeap<Entry<K, P>>,
+    best: HashMap<K, Best<P>>,
+    live: usize,
+}
+
+// Ordered by priority alone, so that keys needn't be comparable.
+#[derive(Debug)]
+struct Entry<K, P>(P, K);
+
+impl<K, P: PartialOrd> PartialEq for Entry<K, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<K, P: PartialOrd> PartialOrd for Entry<K, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+#[derive(Debug)]
+struct Best<P> {
+    priority: P,
+    // whether the entry with this priority is still in the heap
+    queued: bool,
+}
+
+impl<K: Hash + Eq + Clone, P: PartialOrd + Clone> LazyKeyedHeap<K, P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty heap with room for `capacity` keys in both the map of best priorities and the heap, so that
+    /// pushing that many distinct keys doesn't reallocate either.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { heap: FibonacciHeap::with_capacity(capacity), best: HashMap::with_capacity(capacity), live: 0 }
+    }
+
+    /// Makes room for `additional` more keys in the map and as many entries in the heap.
+    pub fn reserve(&mut self, additional: usize) {
+        self.best.reserve(additional);
+        self.heap.reserve(additional);
+    }
+
+    /// Releases the spare capacity of the map and of the heap. The recorded priorities and the queued entries are
+    /// kept as they are.
+    pub fn shrink_to_fit(&mut self) {
+        self.best.shrink_to_fit();
+        self.heap.shrink_to_fit();
+    }
+
+    /// Capacities of the map of best priorities and of the heap, in keys and entries.
+    pub fn capacity(&self) -> (usize, usize) {
+        (self.best.capacity(), self.heap.capacity())
+    }
+
+    /// Breaks down the memory held by the heap and the map, see [`HeapMemoryUsage`]. The map's entries count as
+    /// overhead and its free slots as unused capacity, leaving out the hash table's control bytes.
+    pub fn memory_usage(&self) -> HeapMemoryUsage {
+        let entry = core::mem::size_of::<(K, Best<P>)>();
+        let mut usage = self.heap.memory_usage();
+        usage.node_overhead += self.best.len() * entry;
+        usage.unused_capacity += (self.best.capacity() - self.best.len()) * entry;
+        usage.total = usage.payload + usage.node_overhead + usage.unused_capacity;
+        usage
+    }
+
+    /// Number of keys with a live entry in the heap.
+    pub fn len(&self) -> usize {
+        self.live
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.live == 0
+    }
+
+    /// Number of entries in the heap, stale ones included.
+    pub fn queued_len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Share of the entries in the heap that are stale, from 0 (none, or an empty heap) to 1.
+    pub fn garbage_ratio(&self) -> f64 {
+        if self.heap.is_empty() {
+            return 0.0;
+        }
+        (self.heap.len() - self.live) as f64 / self.heap.len() as f64
+    }
+
+    /// The best priority recorded for `key`, whether it is still queued or was popped.
+    pub fn best(&self, key: &K) -> Option<&P> {
+        self.best.get(key).map(|best| &best.priority)
+    }
+
+    /// Queues `key` at `priority` if that is better than the best priority recorded for it, and tells whether it was.
+    pub fn push_improving(&mut self, key: K, priority: P) -> bool {
+        match self.best.get_mut(&key) {
+            Some(best) if priority.partial_cmp(&best.priority) != Some(Ordering::Less) => return false,
+            Some(best) => {
+                best.priority = priority.clone();
+                if !best.queued {
+                    best.queued = true;
+                    self.live += 1;
+                }
+            }
+            None => {
+                self.best.insert(key.clone(), Best { priority: priority.clone(), queued: true });
+                self.live += 1;
+            }
+        }
+        self.heap.push(Entry(priority, key));
+        true
+    }
+
+    /// Removes and returns the live entry with the smallest priority, discarding the stale entries before it.
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        while let Some(Entry(priority, key)) = self.heap.pop() {
+            let best = self.best.get_mut(&key).unwrap();
+            if best.queued && best.priority == priority {
+                best.queued = false;
+                self.live -= 1;
+                return Some((key, priority));
+            }
+        }
+        None
+    }
+
+    /// Removes every entry and forgets the best priorities recorded.
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.best.clear();
+        self.live = 0;
+    }
+}
+
+/// Pushes each pair with [`push_improving`](LazyKeyedHeap::push_improving), after reserving for the iterator's
+/// lower size bound.
+impl<K: Hash + Eq + Clone, P: PartialOrd + Clone> Extend<(K, P)> for LazyKeyedHeap<K, P> {
+    fn extend<I: IntoIterator<Item = (K, P)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for (key, priority) in iter {
+            self.push_improving(key, priority);
+        }
+    }
+}
+
+impl<K, P> Default for LazyKeyedHeap<K, P> {
+    fn default() -> Self {
+        Self { heap: FibonacciHeap::new(), best: HashMap::new(), live: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+    use crate::IndexedFibonacciHeap;
+
+    #[test]
+    fn stale_entries_are_skipped() {
+        let mut heap = LazyKeyedHeap::new();
+        assert_eq!(heap.garbage_ratio(), 0.0);
+        for priority in (0..10).rev() {
+            assert!(heap.push_improving('a', priority));
+        }
+        assert!(!heap.push_improving('a', 0));
+        assert!(heap.push_improving('b', 4));
+        assert_eq!((heap.len(), heap.queued_len()), (2, 11));
+        assert!((heap.garbage_ratio() - 9.0 / 11.0).abs() < 1e-9);
+        assert_eq!(heap.pop(), Some(('a', 0)));
+        assert_eq!(heap.pop(), Some(('b', 4)));
+        assert_eq!(heap.queued_len(), 5);
+        assert_eq!(heap.garbage_ratio(), 1.0);
+        // settled keys only come back with a better priority
+        assert!(!heap.push_improving('b', 4));
+        assert!(heap.push_improving('b', 2));
+        assert_eq!(heap.best(&'b'), Some(&2));
+        assert_eq!(heap.pop(), Some(('b', 2)));
+        assert_eq!(heap.pop(), None);
+        assert!(heap.is_empty() && heap.queued_len() == 0);
+        heap.clear();
+        assert_eq!(heap.best(&'a'), None);
+    }
+
+    #[test]
+    fn capacity() {
+        let mut heap = LazyKeyedHeap::with_capacity(1000);
+        let (map, entries) = heap.capacity();
+        assert!(map >= 1000 && entries >= 1000);
+        heap.extend((0..1000u32).map(|key| (key, u64::from(key))));
+        assert_eq!(heap.capacity(), (map, entries));
+        heap.reserve(500);
+        let (map, entries) = heap.capacity();
+        assert!(map >= 1500 && entries >= 1500);
+        for key in 1000..1500 {
+            heap.push_improving(key, 0);
+        }
+        assert_eq!(heap.capacity(), (map, entries));
+
+        for _ in 0..1400 {
+            heap.pop();
+        }
+        let before = heap.memory_usage().total;
+        heap.shrink_to_fit();
+        assert!(heap.memory_usage().total < before);
+        assert!(heap.capacity().1 < entries);
+        // settled keys keep their records
+        assert!(!heap.push_improving(7, 7));
+        assert_eq!(heap.best(&1499), Some(&0));
+        assert_eq!(heap.len(), 100);
+        let rest: Vec<u32> = core::iter::from_fn(|| heap.pop()).map(|(key, _)| key).collect();
+        assert_eq!(rest, (900..1000).collect::<Vec<_>>());
+        heap.clear();
+        heap.shrink_to_fit();
+        assert_eq!(heap.capacity(), (0, 0));
+    }
+
+    #[test]
+    fn dijkstra_against_decrease_key() {
+        let mut rng = Rng(0x9b05688c2b3e6c1f);
+        let n = 2000;
+        let edges: Vec<Vec<(usize, u64)>> =
+            (0..n).map(|_| (0..6).map(|_| (rng.below(n as u64) as usize, 1 + rng.below(100))).collect()).collect();
+
+        let mut lazy = LazyKeyedHeap::new();
+        let mut lazy_dist = vec![u64::MAX; n];
+        let mut pushes = 0;
+        lazy.push_improving(0, 0);
+        while let Some((node, d)) = lazy.pop() {
+            lazy_dist[node] = d;
+            for &(next, weight) in &edges[node] {
+                pushes += usize::from(lazy.push_improving(next, d + weight));
+            }
+        }
+        assert_eq!(lazy.queued_len(), 0);
+
+        let mut indexed = IndexedFibonacciHeap::new(vec![u64::MAX; n]);
+        let mut pops = 0;
+        indexed.priorities_mut()[0] = 0;
+        indexed.push(0);
+        let mut done = vec![false; n];
+        while let Some(node) = indexed.pop() {
+            pops += 1;
+            done[node] = true;
+            let d = indexed.priorities()[node];
+            for &(next, weight) in &edges[node] {
+                if !done[next] && d + weight < indexed.priorities()[next] {
+                    indexed.priorities_mut()[next] = d + weight;
+                    if indexed.contains(next) {
+                        indexed.decrease_key(next);
+                    } else {
+                        indexed.push(next);
+                    }
+                }
+            }
+        }
+        let indexed_dist = indexed.into_priorities();
+        assert_eq!(lazy_dist, indexed_dist);
+        // every entry pushed is popped: one per reached node with decrease-key, more without
+        let reached = indexed_dist.iter().filter(|&&d| d != u64::MAX).count();
+        assert_eq!(pops, reached);
+        assert!(pushes > reached, "{pushes} {reached}");
+    }
+}
@@ -1,7 +1,159 @@
-use std::collections::HashMap;
-use std::mem;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+#[cfg(feature = "std")]
+use core::hash::{Hash, Hasher};
+use core::mem;
+use core::ops::{Add, AddAssign};
+#[cfg(feature = "std")]
+use std::collections::hash_map::DefaultHasher;
+
+use instrument::Counters;
+
+#[cfg(feature = "allocator_api")]
+mod alloc_heap;
+#[cfg(feature = "async")]
+mod async_heap;
+#[cfg(feature = "sync")]
+mod channel;
+#[cfg(feature = "paranoid")]
+mod checked;
+mod checkpoint;
+mod compact;
+#[cfg(feature = "std")]
+mod counted;
+mod cow;
+mod cursor;
+#[cfg(feature = "tokio")]
+mod delay;
+mod display;
+mod dot;
+#[cfg(feature = "external")]
+mod external;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod fixed;
+mod heap;
+mod indexed;
+mod inspect;
+mod instrument;
+mod iter;
+#[cfg(feature = "std")]
+mod lazy;
+#[cfg(feature = "sync")]
+mod lock;
+mod median;
+#[cfg(feature = "metrics")]
+mod metered;
+mod monotone;
+#[cfg(feature = "sync")]
+mod multiqueue;
+mod observed;
+mod offset;
+mod pairing;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod persistent;
+mod pinned;
+#[cfg(kani)]
+mod proofs;
+#[cfg(feature = "proptest")]
+mod proptest_impl;
+#[cfg(feature = "quickcheck")]
+mod quickcheck_impl;
+mod rank;
+mod record;
+#[cfg(feature = "rkyv")]
+mod rkyv_impl;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "std")]
+mod set;
+#[cfg(feature = "sync")]
+mod shared;
+mod sort;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+#[cfg(test)]
+mod testing;
+#[cfg(feature = "std")]
+mod timer;
+mod tombstone;
+#[cfg(feature = "std")]
+mod topk;
+mod validate;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod window;
+
+#[cfg(feature = "allocator_api")]
+pub use alloc_heap::AllocFibonacciHeap;
+#[cfg(feature = "bumpalo")]
+pub use alloc_heap::BumpFibonacciHeap;
+#[cfg(feature = "async")]
+pub use async_heap::{AsyncFibonacciHeap, Pop};
+#[cfg(feature = "sync")]
+pub use channel::{priority_channel, Receiver, Sender};
+#[cfg(feature = "paranoid")]
+pub use checked::CheckedFibonacciHeap;
+pub use checkpoint::{CheckpointFibonacciHeap, CheckpointId};
+pub use compact::CompactFibonacciHeap;
+#[cfg(feature = "std")]
+pub use counted::CountedFibonacciHeap;
+pub use cow::CowFibonacciHeap;
+pub use cursor::CursorMut;
+#[cfg(feature = "tokio")]
+pub use delay::{DelayKey, DelayedFibonacciQueue};
+pub use display::DisplayTree;
+#[cfg(feature = "external")]
+pub use external::ExternalFibonacciHeap;
+pub use fixed::FixedFibonacciHeap;
+pub use heap::Heap;
+pub use indexed::{IndexedFibonacciHeap, Priorities};
+pub use inspect::NodeRef;
+#[cfg(feature = "instrument")]
+pub use instrument::OpCounters;
+pub use iter::{Iter, IterSorted};
+#[cfg(feature = "std")]
+pub use lazy::LazyKeyedHeap;
+pub use median::MedianTracker;
+#[cfg(feature = "metrics")]
+pub use metered::MeteredFibonacciHeap;
+pub use monotone::{MonotoneFibonacciHeap, MonotoneKey};
+#[cfg(feature = "sync")]
+pub use multiqueue::MultiQueue;
+pub use observed::ObservedFibonacciHeap;
+pub use offset::{OffsetFibonacciHeap, Offsetable};
+pub use pairing::PairingHeap;
+pub use persistent::PersistentFibonacciHeap;
+pub use pinned::PinnedFibonacciHeap;
+#[cfg(feature = "proptest")]
+pub use proptest_impl::heap_strategy;
+pub use record::{replay, replay_until, Op, RecordingHeap};
+#[cfg(feature = "rkyv")]
+pub use rkyv_impl::ArchivedFibonacciHeap;
+#[cfg(feature = "std")]
+pub use set::FibonacciHeapSet;
+#[cfg(feature = "sync")]
+pub use shared::SharedFibonacciHeap;
+#[cfg(feature = "serde")]
+pub use serde_impl::{DecodeError, DeserializeOptions};
+pub use sort::{partial_sort, sort, sort_in_place};
+#[cfg(feature = "std")]
+pub use timer::{TimerKey, TimerQueue};
+pub use tombstone::TombstoneFibonacciHeap;
+#[cfg(feature = "std")]
+pub use topk::{GroupedTopK, Groups, Retain};
+pub use validate::InvariantViolation;
+#[cfg(feature = "wasm")]
+pub use wasm::JsFibHeap;
+pub use window::{window_min, window_min_by_key};
 
-#[derive(Debug)]
 struct Node<T> {
     value: T,
     children: Vec<Self>,
@@ -16,40 +168,345 @@ impl<T> Node<T> {
         &self.value
     }
 
-    fn children(&self) -> &[Self] {
-        &self.children
-    }
-
     fn degree(&self) -> usize {
         self.children.len()
     }
 
-    fn push_child(&mut self, node: Self) {
-        self.children.push(node);
+    // Number of nodes in this tree, counted without recursion.
+    fn subtree_len(&self) -> usize {
+        let mut len = 0;
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            len += 1;
+            stack.extend(&node.children);
+        }
+        len
+    }
+}
+
+impl<T: Clone> Node<T> {
+    // Copies the tree depth-first with an explicit stack, so that deep trees cannot overflow the call stack.
+    // `buffer` supplies the (empty) children vector for a copy of the given degree.
+    fn clone_with<'a>(&'a self, stack: &mut Vec<(&'a Self, Self)>, buffer: &mut impl FnMut(usize) -> Vec<Self>) -> Self {
+        let mut copy = |node: &Self| Self { value: node.value.clone(), children: buffer(node.degree()) };
+        // (source, copy whose children are filled in so far)
+        stack.push((self, copy(self)));
+        loop {
+            let (source, dest) = stack.last_mut().unwrap();
+            if let Some(child) = source.children.get(dest.children.len()) {
+                stack.push((child, copy(child)));
+                continue;
+            }
+            let (_, done) = stack.pop().unwrap();
+            match stack.last_mut() {
+                Some((_, parent)) => parent.children.push(done),
+                None => return done,
+            }
+        }
     }
 }
 
 impl<T: Clone> Clone for Node<T> {
     fn clone(&self) -> Self {
-        Self {
-            value: self.value.clone(),
-            children: self.children.clone()
+        self.clone_with(&mut vec![], &mut Vec::with_capacity)
+    }
+}
+
+// Emptied children vectors, bucketed by capacity: `buckets[k]` holds capacities in `2^k..2^(k+1)`.
+struct SparePool<T> {
+    buckets: Vec<Vec<Vec<Node<T>>>>,
+}
+
+impl<T> SparePool<T> {
+    fn put(&mut self, vec: Vec<Node<T>>) {
+        debug_assert!(vec.is_empty());
+        if vec.capacity() == 0 {
+            return;
+        }
+        let bucket = vec.capacity().ilog2() as usize;
+        if bucket >= self.buckets.len() {
+            self.buckets.resize_with(bucket + 1, Vec::new);
         }
+        self.buckets[bucket].push(vec);
+    }
+
+    // A vector with room for at least `capacity` nodes, taken from the smallest bucket that guarantees it.
+    fn take(&mut self, capacity: usize) -> Option<Vec<Node<T>>> {
+        let first = capacity.next_power_of_two().ilog2() as usize;
+        self.buckets.iter_mut().skip(first).find_map(Vec::pop)
     }
 }
 
-#[derive(Debug)]
+impl<T> Default for SparePool<T> {
+    fn default() -> Self {
+        Self { buckets: vec![] }
+    }
+}
+
+/// A min-heap of elements ordered by `PartialOrd`.
+///
+/// Elements must be comparable with each other: a value that compares as neither smaller, equal nor greater than
+/// another, like `f64::NAN`, is not supported. Debug builds panic on the first comparison that involves one.
+/// Release builds don't check; no element is lost (`len`, `iter` and `into_vec` still cover all of them), but `peek`
+/// and the pop order become unspecified. Floats that may be NaN can be pushed through a wrapper ordered by
+/// [`f64::total_cmp`], which sorts positive NaNs after every number.
+///
+/// Like a `Vec<T>`, a heap is `Send` when `T` is `Send` and `Sync` when `T` is `Sync`, and so are its iterators.
+// Small heaps stay flat: while every element is a root of its own (`roots.len() == len`) and there are at most
+// `FLAT_MAX_LEN` of them, removing the minimum just rescans the roots instead of linking them into trees.
+// A flat heap that has grown past that limit is consolidated by its next removal, and a heap of trees that shrinks
+// to `FLAT_MAX_LEN / 2` elements is flattened again; the gap keeps a heap near the limit from switching back and forth.
 pub struct FibonacciHeap<T> {
    roots: Vec<Node<T>>,
    top_index: usize,
    len: usize, // count of whole nodes (not self.roots.len())
+   // Scratch space kept between pops so that steady-state popping doesn't allocate:
+   // the consolidation table (degree -> root index) and emptied children vectors of popped nodes, reused for linking.
+   degrees: Vec<usize>,
+   spare: SparePool<T>,
+   // Most links a single `pop` may perform; `usize::MAX` when unbounded.
+   max_links: usize,
+   // Link on every push and append, keeping root degrees distinct; `degrees` then indexes every root between operations.
+   eager: bool,
+   // Operation counts with the `instrument` feature, a zero-sized no-op without it.
+   counters: Counters,
 }
 
-impl<T: PartialOrd> FibonacciHeap<T> {
+impl<T> FibonacciHeap<T> {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Creates an empty heap whose root list has room for `capacity` elements,
+    /// so that the first `capacity` pushes don't allocate (without eager consolidation, which allocates trees).
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut heap = Self::new();
+        heap.reserve(capacity);
+        heap
+    }
+
+    /// Makes room in the root list for at least `additional` more pushes without reallocating.
+    pub fn reserve(&mut self, additional: usize) {
+        self.roots.reserve(additional);
+    }
+
+    /// Number of elements the root list can hold without reallocating, which bounds the pushes that don't allocate.
+    pub fn capacity(&self) -> usize {
+        self.roots.capacity()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over references to the elements in unspecified order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(&self.roots, self.len)
+    }
+
+    /// Number of trees in the root list, i.e. how much linking work the next `pop` (or `consolidate`) faces.
+    ///
+    /// Heaps of up to 16 elements aren't linked by `pop`, so every element stays a root of its own.
+    pub fn root_count(&self) -> usize {
+        self.roots.len()
+    }
+
+    /// Returns the smallest element, or `None` if the heap is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let mut heap = FibonacciHeap::new();
+    /// assert_eq!(heap.peek(), None);
+    /// heap.push(2);
+    /// heap.push(1);
+    /// assert_eq!(heap.peek(), Some(&1));
+    /// ```
+    pub fn peek(&self) -> Option<&T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.roots[self.top_index].value())
+        }
+    }
+
+    #[deprecated(note = "renamed to `peek` for parity with `BinaryHeap`")]
+    pub fn top(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    /// Removes every element, keeping the root list's allocation.
+    pub fn clear(&mut self) {
+        drop_forest(&mut self.roots);
+        self.degrees.clear();
+        self.top_index = 0;
+        self.len = 0;
+    }
+
+    /// Releases spare capacity of the root list and the scratch space kept for popping.
+    pub fn shrink_to_fit(&mut self) {
+        self.roots.shrink_to_fit();
+        if self.eager {
+            self.degrees.shrink_to_fit();
+        } else {
+            self.degrees = vec![];
+        }
+        self.spare = SparePool::default();
+    }
+
+    /// Caps the linking work of every `pop` (and `replace_top`) at `max` links; `None`, the default, removes the cap.
+    ///
+    /// A capped `pop` stops consolidating once it has linked `max` pairs of trees and leaves the remaining roots
+    /// for later operations, so a single `pop` after a long run of pushes no longer links them all at once.
+    /// The minimum is still exact: it is found by scanning the whole root list.
+    ///
+    /// This trades the O(log n) amortized bound of `pop` for a bounded number of links.
+    /// Every link still removes a root, so the total linking work over a sequence of operations is unchanged,
+    /// but a capped `pop` costs O(max + r), where r is the number of roots, and r stays large for as long as
+    /// pushes outpace `max` links per `pop`. With `max` at least about log2(len) plus the number of pushes per `pop`,
+    /// the root list shrinks back to O(log n) trees. [`consolidate`](Self::consolidate) always links everything,
+    /// and so does every `pop` with [eager consolidation](Self::set_eager_consolidation).
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let mut heap = FibonacciHeap::from_vec((0..1000).rev().collect());
+    /// heap.set_max_links_per_pop(Some(8));
+    /// assert_eq!(heap.pop(), Some(0));
+    /// // most of the 999 remaining roots are left for later
+    /// assert!(heap.root_count() > 900);
+    /// assert_eq!(heap.pop(), Some(1));
+    /// ```
+    pub fn set_max_links_per_pop(&mut self, max: Option<usize>) {
+        self.max_links = max.unwrap_or(usize::MAX);
+    }
+
+    pub fn max_links_per_pop(&self) -> Option<usize> {
+        (self.max_links != usize::MAX).then_some(self.max_links)
+    }
+
+    pub fn eager_consolidation(&self) -> bool {
+        self.eager
+    }
+
+    /// Breaks down the heap memory held by the heap; see [`HeapMemoryUsage`].
+    ///
+    /// Walks every node once, without recursion.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let mut heap = FibonacciHeap::from_vec((0..1000u64).collect());
+    /// heap.pop();
+    /// let usage = heap.memory_usage();
+    /// assert_eq!(usage.payload, 999 * 8);
+    /// assert_eq!(usage.total, usage.payload + usage.node_overhead + usage.unused_capacity);
+    /// ```
+    pub fn memory_usage(&self) -> HeapMemoryUsage {
+        let node = mem::size_of::<Node<T>>();
+        let mut unused = self.roots.capacity() - self.roots.len();
+        let mut stack: Vec<&Node<T>> = self.roots.iter().collect();
+        while let Some(next) = stack.pop() {
+            unused += next.children.capacity() - next.children.len();
+            stack.extend(&next.children);
+        }
+        let mut scratch = self.degrees.capacity() * mem::size_of::<usize>()
+            + self.spare.buckets.capacity() * mem::size_of::<Vec<Vec<Node<T>>>>();
+        for bucket in &self.spare.buckets {
+            scratch += bucket.capacity() * mem::size_of::<Vec<Node<T>>>();
+            scratch += bucket.iter().map(|vec| vec.capacity() * node).sum::<usize>();
+        }
+
+        let payload = self.len * mem::size_of::<T>();
+        let node_overhead = self.len * (node - mem::size_of::<T>());
+        let unused_capacity = unused * node + scratch;
+        HeapMemoryUsage { payload, node_overhead, unused_capacity, total: payload + node_overhead + unused_capacity }
+    }
+
+    /// Measures the shape of the forest; see [`HeapStats`].
+    ///
+    /// Walks every node once, without recursion, so it is cheap enough to log periodically.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let mut heap = FibonacciHeap::from_vec((0..1000).collect());
+    /// assert_eq!(heap.stats().roots, 1000);
+    /// heap.pop();
+    /// let stats = heap.stats();
+    /// assert_eq!((stats.nodes, stats.roots), (999, 999usize.count_ones() as usize));
+    /// assert!(stats.max_depth <= 9);
+    /// ```
+    pub fn stats(&self) -> HeapStats {
+        let mut stats = HeapStats { roots: self.roots.len(), ..HeapStats::default() };
+        let mut depth_sum = 0;
+        // (node, depth, whether its parent has a single child)
+        let mut stack: Vec<(&Node<T>, usize, bool)> = self.roots.iter().map(|root| (root, 0, false)).collect();
+        while let Some((node, depth, in_chain)) = stack.pop() {
+            stats.nodes += 1;
+            stats.max_degree = stats.max_degree.max(node.degree());
+            stats.max_depth = stats.max_depth.max(depth);
+            depth_sum += depth;
+            let single = node.degree() == 1;
+            if single && !in_chain {
+                stats.single_child_chains += 1;
+            }
+            stack.extend(node.children.iter().map(|child| (child, depth + 1, single)));
+        }
+        if stats.nodes > 0 {
+            stats.average_depth = depth_sum as f64 / stats.nodes as f64;
+        }
+        stats
+    }
+}
+
+/// Shape of the forest of a [`FibonacciHeap`], as reported by [`FibonacciHeap::stats`].
+///
+/// Depths count edges from the root list, so a heap of singleton roots has depth 0. A consolidated heap of n elements
+/// has at most log2(n) + 1 roots and depth log2(n); many roots mean that pushes have piled up since the last `pop`.
+/// Nodes are never marked, as there is no decrease-key, so there is no count of marked nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HeapStats {
+    /// Trees in the root list.
+    pub roots: usize,
+    /// All nodes, which is `len()`.
+    pub nodes: usize,
+    /// Most children of any node.
+    pub max_degree: usize,
+    /// Deepest node.
+    pub max_depth: usize,
+    /// Mean depth over all nodes, 0 for an empty heap.
+    pub average_depth: f64,
+    /// Maximal paths of nodes that each have exactly one child.
+    pub single_child_chains: usize,
+}
+
+/// Heap memory held by a [`FibonacciHeap`], in bytes, as reported by [`FibonacciHeap::memory_usage`].
+///
+/// Only the heap's own allocations are counted: neither the `FibonacciHeap` value itself
+/// nor memory the elements own elsewhere (e.g. the buffer of a `String`).
+/// Allocator bookkeeping and rounding come on top.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HeapMemoryUsage {
+    /// `size_of::<T>()` per element.
+    pub payload: usize,
+    /// What each node stores besides its element: its children vector and padding.
+    pub node_overhead: usize,
+    /// Allocated but unused room in the root list and the children vectors, plus the scratch space kept for popping.
+    pub unused_capacity: usize,
+    /// Sum of the above.
+    pub total: usize,
+}
+
+impl<T: PartialOrd> FibonacciHeap<T> {
     pub fn from_vec(vec: Vec<T>) -> Self {
         if vec.is_empty() {
             return Self::new();
@@ -59,9 +516,10 @@ impl<T: PartialOrd> FibonacciHeap<T> {
         let mut roots = Vec::with_capacity(len);
         let top_index = len-1; // minimum value will be inserted at last
         let mut min_cell = None;
+        let mut counters = Counters::default();
         for mut value in vec.into_iter() {
             if let Some(mut min_val) = min_cell.take() {
-                if value > min_val {
+                if less(&mut counters, &min_val, &value) {
                     mem::swap(&mut value, &mut min_val)
                 }
                 roots.push(Node::new(min_val));
@@ -71,10 +529,68 @@ impl<T: PartialOrd> FibonacciHeap<T> {
         if let Some(min_val) = min_cell {
             roots.push(Node::new(min_val));
         }
-        Self { roots, top_index, len }
+        Self { roots, top_index, len, degrees: vec![], spare: SparePool::default(), max_links: usize::MAX, eager: false, counters }
+    }
+
+    /// Builds a heap from runs that are each sorted in ascending order, such as the runs of a merge sort.
+    ///
+    /// Each run becomes a single tree in O(len) without comparing its elements: a spine down the run, each node of
+    /// which has the element after it as a leaf next to the rest of the spine. Only the first elements of the `k`
+    /// non-empty runs are compared, `k - 1` times, to find the minimum, and popping keeps taking advantage of the
+    /// order, as each pop of a spine node only adds two roots. The trees are about half as deep as their runs are long,
+    /// which is fine as the heap never recurses over a tree, not even to drop, clear or clone it.
+    /// Concatenating the runs for [`from_vec`](Self::from_vec) would cost a comparison per element instead, and lose
+    /// the order within the runs. Debug builds panic if a run isn't sorted.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let mut heap = FibonacciHeap::from_sorted_runs(vec![vec![2, 5, 8], vec![], vec![1, 9]]);
+    /// heap.push(3);
+    /// assert_eq!(heap.into_sorted_vec(), [1, 2, 3, 5, 8, 9]);
+    /// ```
+    pub fn from_sorted_runs(runs: Vec<Vec<T>>) -> Self {
+        let mut heap = Self::new();
+        heap.roots.reserve(runs.len());
+        for run in runs {
+            debug_assert!(
+                run.windows(2).all(|pair| pair[1].partial_cmp(&pair[0]) != Some(Ordering::Less)),
+                "fibheap: run passed to from_sorted_runs is not sorted"
+            );
+            heap.len += run.len();
+            let mut values = run.into_iter();
+            let Some(last) = values.next_back() else { continue };
+            // built from the end of the run, two elements at a time: the first of them over the second and the
+            // tree built so far, so that there is no recursion, and one allocation per two elements
+            let mut tree = Node::new(last);
+            while let Some(value) = values.next_back() {
+                tree = match values.next_back() {
+                    Some(parent) => Node { value: parent, children: vec![Node::new(value), tree] },
+                    None => Node { value, children: vec![tree] },
+                };
+            }
+            heap.roots.push(tree);
+        }
+        for ix in 1..heap.roots.len() {
+            if less(&mut heap.counters, heap.roots[ix].value(), heap.roots[heap.top_index].value()) {
+                heap.top_index = ix;
+            }
+        }
+        heap.debug_validate_top(false);
+        heap
     }
 
-    pub fn into_vec(mut self) -> Vec<T> {
+    /// Consumes the heap and returns its elements in ascending order.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let heap = FibonacciHeap::from_vec(vec![3, 1, 2]);
+    /// assert_eq!(heap.into_sorted_vec(), [1, 2, 3]);
+    /// ```
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
         let mut vec = Vec::with_capacity(self.len());
         while let Some(value) = self.pop() {
             vec.push(value);
@@ -82,114 +598,734 @@ impl<T: PartialOrd> FibonacciHeap<T> {
         vec
     }
 
-    pub fn len(&self) -> usize {
-        self.len
+    /// Consumes the heap and returns its elements in descending order, the reverse of
+    /// [`into_sorted_vec`](Self::into_sorted_vec).
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let heap = FibonacciHeap::from_vec(vec![3, 1, 2, 1]);
+    /// assert_eq!(heap.into_sorted_vec_desc(), [3, 2, 1, 1]);
+    /// ```
+    pub fn into_sorted_vec_desc(mut self) -> Vec<T> {
+        self.pop_all_desc()
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.len == 0
+    /// Removes every element and iterates over them in descending order.
+    ///
+    /// The largest element is only known once all the others have been popped, so the heap is emptied up front,
+    /// into a vector that the iterator then owns; the heap keeps its settings.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let mut heap = FibonacciHeap::from_vec(vec![3, 1, 2]);
+    /// assert_eq!(heap.drain_sorted_desc().next(), Some(3));
+    /// assert!(heap.is_empty());
+    /// ```
+    pub fn drain_sorted_desc(&mut self) -> vec::IntoIter<T> {
+        self.pop_all_desc().into_iter()
     }
 
-    pub fn top(&self) -> Option<&T> {
-        if self.is_empty() {
-            None
-        } else {
-            Some(self.roots[self.top_index].value())
+    // Pops the elements into a vector from its back to its front, so that no pass is needed to reverse it.
+    fn pop_all_desc(&mut self) -> Vec<T> {
+        // The slots from `start` on are initialized; if a comparison panics, they are dropped on unwinding.
+        struct Filled<'a, T> {
+            slots: &'a mut [mem::MaybeUninit<T>],
+            start: usize,
+        }
+
+        impl<T> Drop for Filled<'_, T> {
+            fn drop(&mut self) {
+                for slot in &mut self.slots[self.start..] {
+                    // SAFETY: the slots from `start` on were written and are dropped only here
+                    unsafe { slot.assume_init_drop() };
+                }
+            }
         }
+
+        let len = self.len();
+        let mut vec = Vec::with_capacity(len);
+        let mut filled = Filled { slots: &mut vec.spare_capacity_mut()[..len], start: len };
+        while filled.start > 0 {
+            filled.slots[filled.start - 1].write(self.pop().unwrap());
+            filled.start -= 1;
+        }
+        mem::forget(filled);
+        // SAFETY: the loop initialized the first `len` slots, each exactly once
+        unsafe { vec.set_len(len) };
+        vec
+    }
+
+    /// Same as [`into_sorted_vec`](Self::into_sorted_vec).
+    ///
+    /// **The result is sorted**, unlike `BinaryHeap::into_vec`, which returns the elements in arbitrary order.
+    /// Prefer `into_sorted_vec`, which says so in its name.
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_sorted_vec()
+    }
+
+    /// Iterates over references to the elements in ascending order without consuming the heap.
+    ///
+    /// The walk keeps its frontier in a heap of node references, so it costs O(log n) amortized per element
+    /// and stopping early only pays for the elements visited.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let heap = FibonacciHeap::from_vec(vec![3, 1, 2]);
+    /// assert_eq!(heap.iter_sorted().collect::<Vec<_>>(), [&1, &2, &3]);
+    /// assert_eq!(heap.len(), 3);
+    /// ```
+    pub fn iter_sorted(&self) -> IterSorted<'_, T> {
+        IterSorted::new(&self.roots)
     }
 
     pub fn push(&mut self, value: T) {
-        if !self.roots.is_empty() {
-            let cur = self.roots[self.top_index].value();
-            if &value < cur {
-                self.top_index = self.roots.len();
+        if self.eager {
+            self.roots.push(Node::new(value));
+            self.len += 1;
+            self.link_new_roots(self.roots.len() - 1);
+        } else {
+            if !self.roots.is_empty() {
+                let cur = self.roots[self.top_index].value();
+                if less(&mut self.counters, &value, cur) {
+                    self.top_index = self.roots.len();
+                }
             }
+            self.roots.push(Node::new(value));
+            self.len += 1;
         }
-        self.roots.push(Node::new(value));
-        self.len += 1;
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len = self.len, roots = self.roots.len(), top_changed = top_changed(self, self.roots.len() - 1), "push");
+        self.debug_validate_top(false);
+    }
+
+    /// Pushes every item of `items`, locating the new minimum with one scan at the end.
+    ///
+    /// The heap ends up exactly as after pushing the items one by one, so the pop order is the same,
+    /// including among equal elements. The items become roots that the next `pop` links;
+    /// call [`consolidate`](Self::consolidate) afterwards to do that linking right away instead.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let mut heap = FibonacciHeap::new();
+    /// heap.bulk_push([5, 3, 8]);
+    /// heap.bulk_push(vec![4, 1]);
+    /// heap.consolidate();
+    /// assert_eq!(heap.into_sorted_vec(), [1, 3, 4, 5, 8]);
+    /// ```
+    pub fn bulk_push(&mut self, items: impl IntoIterator<Item = T>) {
+        let start = self.roots.len();
+        self.roots.extend(items.into_iter().map(Node::new));
+        self.len += self.roots.len() - start;
+        if self.eager {
+            self.link_new_roots(start);
+        } else {
+            if start == 0 {
+                self.top_index = 0;
+            }
+            self.clear_on_unwind(|heap| {
+                for ix in start.max(1)..heap.roots.len() {
+                    if less(&mut heap.counters, heap.roots[ix].value(), heap.roots[heap.top_index].value()) {
+                        heap.top_index = ix;
+                    }
+                }
+            });
+        }
+        self.debug_validate_top(false);
     }
 
+    /// Removes and returns the smallest element.
+    ///
+    /// If a comparison panics, the heap is left empty and every element is dropped exactly once; the same goes for
+    /// the other operations that link roots or look for the new minimum (`replace_top`, `bulk_push`, `consolidate`,
+    /// and `push` and `append` with eager consolidation). `push` and `append` otherwise leave the heap unchanged.
     pub fn pop(&mut self) -> Option<T> {
         if self.is_empty() {
             return None;
         }
+        #[cfg(feature = "tracing")]
+        let promoted = self.roots[self.top_index].degree();
+        let value = self.detach_root(self.top_index);
+        self.len -= 1;
+        #[cfg(feature = "tracing")]
+        let roots_before = self.roots.len();
+        self.restore_top();
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len = self.len, promoted, links = roots_before.saturating_sub(self.roots.len()), roots = self.roots.len(), "pop");
+        self.debug_validate_top(true);
+        Some(value)
+    }
+
+    /// Pushes `value` and pops the minimum in one operation.
+    ///
+    /// If `value` is not greater than the current minimum it is returned immediately without touching the heap;
+    /// otherwise this behaves like `replace_top` and costs a single consolidation.
+    pub fn push_pop(&mut self, value: T) -> T {
+        if !self.is_empty() && less(&mut self.counters, self.roots[self.top_index].value(), &value) {
+            self.replace_top(value).unwrap()
+        } else {
+            value
+        }
+    }
 
-        // degree -> (new) root
-        // Using Rc<RefCell> here is actually
-        // just to use .and_modify().or_insert() when updating, avoiding use after move.
-        let max_new_roots_capacity = self.roots.len() - 1 + self.roots[self.top_index].children().len();
-        let mut deg_to_root: HashMap<usize, Node<T>> =  HashMap::with_capacity(max_new_roots_capacity);
+    /// Pops the minimum and pushes `value` in its place, consolidating only once.
+    ///
+    /// Returns `None` (after pushing `value`) if the heap was empty.
+    pub fn replace_top(&mut self, value: T) -> Option<T> {
+        if self.is_empty() {
+            self.push(value);
+            return None;
+        }
+        let top = self.detach_root(self.top_index);
+        self.roots.push(Node::new(value));
+        self.restore_top();
+        self.debug_validate_top(true);
+        Some(top)
+    }
 
-        let roots = mem::take(&mut self.roots);
-        let mut ret = None;
-        for (ix , node) in roots.into_iter().enumerate() {
-            if ix == self.top_index {
-                let Node { value, children } = node;
-                for node in children.into_iter() {
-                    map_update(&mut deg_to_root, node);
+    /// Repeatedly replaces the two smallest elements with `combine(smallest, second_smallest)` until one remains.
+    ///
+    /// Each round removes both elements and links the combined one with a single consolidation pass.
+    /// Returns `None` for an empty heap and the sole element for a singleton.
+    ///
+    /// # Example
+    /// Huffman code lengths from symbol frequencies:
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let freqs = [('a', 45), ('b', 13), ('c', 12), ('d', 16), ('e', 9), ('f', 5)];
+    /// // (total weight, [(symbol, code length)])
+    /// let heap = FibonacciHeap::from_vec(freqs.iter().map(|&(s, w)| (w, vec![(s, 0)])).collect());
+    /// let (_, mut lengths) = heap
+    ///     .reduce_pairwise(|(w1, mut l1), (w2, l2)| {
+    ///         l1.extend(l2);
+    ///         l1.iter_mut().for_each(|(_, len)| *len += 1);
+    ///         (w1 + w2, l1)
+    ///     })
+    ///     .unwrap();
+    /// lengths.sort();
+    /// assert_eq!(lengths, [('a', 1), ('b', 3), ('c', 3), ('d', 3), ('e', 4), ('f', 4)]);
+    /// ```
+    pub fn reduce_pairwise<F: FnMut(T, T) -> T>(mut self, mut combine: F) -> Option<T> {
+        // `from_vec` and `push` leave the roots unlinked, so consolidate once up front: from then on the root list is
+        // consolidated at the top of every iteration.
+        self.consolidate();
+        while self.len > 1 {
+            // The root list is consolidated here, so the second smallest is found with a short scan
+            // over the remaining roots and the top's children instead of a second consolidation.
+            let first = self.detach_root(self.top_index);
+            let mut second_index = 0;
+            self.counters.root_scan();
+            for (ix, node) in self.roots.iter().enumerate().skip(1) {
+                if less(&mut self.counters, node.value(), self.roots[second_index].value()) {
+                    second_index = ix;
                 }
-                let _ = ret.insert(value);
-            } else {
-                map_update(&mut deg_to_root, node);
             }
+            let second = self.detach_root(second_index);
+            self.roots.push(Node::new(combine(first, second)));
+            self.len -= 1;
+            self.consolidate();
         }
+        self.pop()
+    }
 
-        if !deg_to_root.is_empty() {
-            let len = deg_to_root.len();
-            self.roots.reserve(len);
-            self.top_index = len - 1; // minimum value will be inserted at last
-            self.len -= 1;
+    pub fn append(&mut self, mut other: FibonacciHeap<T>) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            self.replace_contents(other);
+            return;
+        }
+        #[cfg(feature = "tracing")]
+        let (moved, old_roots) = (other.len, self.roots.len());
+        self.len += other.len;
+        self.counters.merge(other.counters);
+        if self.eager {
+            let start = self.roots.len();
+            self.roots.append(&mut other.roots);
+            self.link_new_roots(start);
+        } else {
+            if less(&mut self.counters, other.roots[other.top_index].value(), self.roots[self.top_index].value()) {
+                self.top_index = self.roots.len() + other.top_index;
+            }
+            self.roots.append(&mut other.roots);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len = self.len, moved, roots = self.roots.len(), top_changed = top_changed(self, old_roots), "append");
+        self.debug_validate_top(false);
+    }
 
-            let mut min_cell: Option<Node<T>> = None;
-            for (_, mut node) in deg_to_root.into_iter() {
-                if let Some(mut other) = min_cell.take() {
-                    if node.value() > other.value() {
-                        mem::swap(&mut node, &mut other);
-                    }
-                    self.roots.push(other);
-                }
-                let _ = min_cell.insert(node);
+    /// Splits the heap into one independent heap per tree of its consolidated root list.
+    ///
+    /// Every piece is a valid heap whose minimum is its single root, and their lengths add up to `len()`,
+    /// so the pieces can be processed in parallel and merged back with [`append`](Self::append).
+    /// An empty heap yields no pieces.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let pieces = FibonacciHeap::from_vec((0..10).collect()).into_forest();
+    /// assert_eq!(pieces.iter().map(|piece| piece.len()).sum::<usize>(), 10);
+    /// let mut merged = FibonacciHeap::new();
+    /// pieces.into_iter().for_each(|piece| merged.append(piece));
+    /// assert_eq!(merged.into_sorted_vec(), (0..10).collect::<Vec<_>>());
+    /// ```
+    pub fn into_forest(mut self) -> Vec<Self> {
+        self.consolidate();
+        mem::take(&mut self.roots).into_iter().map(|node| Self::from_roots(vec![node])).collect()
+    }
+
+    /// Moves trees out of the heap into up to `n - 1` other heaps, so that with `self` there are `n` groups of balanced size.
+    ///
+    /// The heap is consolidated first and whole trees are then assigned greedily, largest first, to the lightest group;
+    /// `self` keeps the first group. Groups that end up empty (there may be fewer trees than `n`) are not returned.
+    /// Trees hold power-of-two-like sizes, so groups are balanced within roughly a factor of two of the largest tree.
+    /// `n <= 1` leaves the heap untouched and returns nothing.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let mut heap = FibonacciHeap::from_vec((0..1000).collect());
+    /// let others = heap.split_forest(4);
+    /// assert_eq!(others.len(), 3);
+    /// assert_eq!(heap.len() + others.iter().map(|other| other.len()).sum::<usize>(), 1000);
+    /// ```
+    pub fn split_forest(&mut self, n: usize) -> Vec<Self> {
+        if n <= 1 {
+            return vec![];
+        }
+        self.consolidate();
+        let mut trees: Vec<(usize, Node<T>)> = self.roots.drain(..).map(|node| (node.subtree_len(), node)).collect();
+        trees.sort_unstable_by_key(|&(size, _)| core::cmp::Reverse(size));
+
+        let mut groups: Vec<(usize, Vec<Node<T>>)> = (0..n.min(trees.len())).map(|_| (0, vec![])).collect();
+        for (size, node) in trees {
+            let lightest = groups.iter_mut().min_by_key(|(load, _)| *load).unwrap();
+            lightest.0 += size;
+            lightest.1.push(node);
+        }
+        let mut groups = groups.into_iter().map(|(_, roots)| Self::from_roots(roots));
+        self.replace_contents(groups.next().unwrap_or_default());
+        groups.collect()
+    }
+
+    /// Moves about half of the elements into a new heap, by whole trees, and returns it.
+    ///
+    /// Each tree goes to whichever side holds fewer elements so far, so the two lengths differ by at most the size of
+    /// the largest tree. A heap that is a single tree is first split below its root, whose subtrees become trees of
+    /// their own, so splitting again and again ends with heaps of one element. No element is compared except to find
+    /// the minimum of each side among its roots; counting the elements of the trees walks every node once.
+    /// `self` keeps its settings and the new heap has the default ones, as with [`split_forest`](Self::split_forest).
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let mut heap = FibonacciHeap::from_vec((0..1000).collect());
+    /// heap.pop();
+    /// let other = heap.split_half();
+    /// assert_eq!(heap.len() + other.len(), 999);
+    /// assert!(heap.len().abs_diff(other.len()) <= 512);
+    /// ```
+    pub fn split_half(&mut self) -> Self {
+        if let [root] = &mut self.roots[..] {
+            // the root stays the minimum of its former subtrees
+            let children = mem::take(&mut root.children);
+            self.roots.extend(children);
+        }
+        let (mut kept, mut moved) = ((0, vec![]), (0, vec![]));
+        for node in mem::take(&mut self.roots) {
+            let lighter = if moved.0 < kept.0 { &mut moved } else { &mut kept };
+            lighter.0 += node.subtree_len();
+            lighter.1.push(node);
+        }
+        self.replace_contents(Self::from_roots(kept.1));
+        Self::from_roots(moved.1)
+    }
+
+    // Moves the elements of `other` into `self` in place of its own, keeping the settings of `self`.
+    fn replace_contents(&mut self, mut other: Self) {
+        other.max_links = self.max_links;
+        other.eager = self.eager;
+        self.counters.merge(other.counters);
+        other.counters = self.counters;
+        if other.eager {
+            other.consolidate();
+        }
+        *self = other;
+    }
+
+    // Builds a heap around whole trees, locating the minimum among their roots.
+    fn from_roots(roots: Vec<Node<T>>) -> Self {
+        let len = roots.iter().map(Node::subtree_len).sum();
+        let mut top_index = 0;
+        let mut counters = Counters::default();
+        for (ix, node) in roots.iter().enumerate().skip(1) {
+            if less(&mut counters, node.value(), roots[top_index].value()) {
+                top_index = ix;
+            }
+        }
+        Self { roots, top_index, len, degrees: vec![], spare: SparePool::default(), max_links: usize::MAX, eager: false, counters }
+    }
+
+    // Removes the root at `index` and promotes its children to roots; `top_index` is stale until the next consolidation.
+    fn detach_root(&mut self, index: usize) -> T {
+        let Node { value, mut children } = self.roots.swap_remove(index);
+        self.roots.append(&mut children);
+        self.spare.put(children);
+        value
+    }
+
+    // Keeps at most `limit` elements by evicting minimums: pushes `value` while there is room,
+    // otherwise hands back whichever of `value` and the current minimum is smaller.
+    pub(crate) fn push_bounded(&mut self, value: T, limit: usize) -> Option<T> {
+        if self.len < limit {
+            self.push(value);
+            None
+        } else if limit == 0 {
+            Some(value)
+        } else {
+            Some(self.push_pop(value))
+        }
+    }
+
+    // Removes one element for which `cmp` (comparing it against the element searched for) returns `Equal`.
+    // Subtrees whose root compares `Greater` are skipped, since nothing below them can match.
+    pub(crate) fn remove_by<F: FnMut(&T) -> Option<Ordering>>(&mut self, mut cmp: F) -> Option<T> {
+        // child indices leading from a root to the node being inspected
+        let mut path = vec![];
+        let mut stack: Vec<(usize, usize)> = (0..self.roots.len()).map(|ix| (0, ix)).collect();
+        let found = loop {
+            let (depth, ix) = stack.pop()?;
+            path.truncate(depth);
+            path.push(ix);
+            self.counters.comparison();
+            let node = self.node_at(&path);
+            match cmp(node.value()) {
+                Some(Ordering::Equal) => break path,
+                Some(Ordering::Greater) => {}
+                _ => stack.extend((0..node.degree()).map(|child| (depth + 1, child))),
             }
-            if let Some(node) = min_cell {
-                self.roots.push(node);
+        };
+
+        self.len -= 1;
+        if let [ix] = found[..] {
+            let last = self.roots.len() - 1;
+            let value = self.detach_root(ix);
+            if ix == self.top_index || self.eager {
+                self.restore_top();
+            } else if self.top_index == last {
+                self.top_index = ix;
             }
+            return Some(value);
+        }
+        let (&child, parent_path) = found.split_last().unwrap();
+        let mut parent = &mut self.roots[parent_path[0]];
+        for &ix in &parent_path[1..] {
+            parent = &mut parent.children[ix];
+        }
+        let Node { value, children } = parent.children.swap_remove(child);
+        self.counters.cuts(children.len());
+        self.roots.extend(children);
+        if self.eager {
+            self.consolidate();
+        }
+        Some(value)
+    }
+
+    fn node_at(&self, path: &[usize]) -> &Node<T> {
+        let mut node = &self.roots[path[0]];
+        for &ix in &path[1..] {
+            node = &node.children[ix];
+        }
+        node
+    }
+
+    /// Links roots of equal degree until all root degrees are distinct, without removing anything.
+    ///
+    /// `pop` does this lazily; calling it eagerly (e.g. while idle) moves that cost out of the next `pop`.
+    /// Afterwards there are O(log n) roots, and on an already consolidated heap this only scans them.
+    /// The contents, `len()` and the value of `peek()` are unchanged.
+    ///
+    /// Linking is deterministic: roots are taken in root-list order and the degree table is a plain array,
+    /// so the same sequence of operations always builds the same trees and pops equal elements in the same order.
+    pub fn consolidate(&mut self) {
+        self.consolidate_with(usize::MAX);
+        self.debug_validate_top(true);
+    }
+
+    /// Switches linking from `pop` to `push` and `append` (`false`, the default, links lazily in `pop`).
+    ///
+    /// With eager consolidation the roots always have distinct degrees, like a binomial heap: every `push` and
+    /// `append` links the new trees into the root list right away and rescans its O(log n) roots for the minimum.
+    /// Pushing costs O(log n) instead of O(1), while a `pop` only has the popped root's children to link,
+    /// so it never pays for a long run of pushes at once. The cap set by
+    /// [`set_max_links_per_pop`](Self::set_max_links_per_pop) doesn't apply in this mode.
+    /// Elements pop in the same order either way; only the work is distributed differently.
+    /// Turning it on consolidates the heap.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let mut heap = FibonacciHeap::new();
+    /// heap.set_eager_consolidation(true);
+    /// heap.bulk_push(0..1000);
+    /// // 1000 = 0b1111101000: one tree per set bit
+    /// assert_eq!(heap.root_count(), 6);
+    /// assert_eq!(heap.pop(), Some(0));
+    /// ```
+    pub fn set_eager_consolidation(&mut self, eager: bool) {
+        self.eager = eager;
+        if eager {
+            self.consolidate();
         } else {
-            self.top_index = 0;
-            self.len = 0;
+            self.degrees.clear();
         }
-        ret
     }
 
-    pub fn append(&mut self, other: FibonacciHeap<T>) {
-        if other.is_empty() {
+    // Locates the new minimum after a removal, linking the roots unless the heap is small enough to stay flat.
+    fn restore_top(&mut self) {
+        if self.eager || self.len > FLAT_MAX_LEN {
+            self.consolidate_with(self.max_links);
             return;
         }
-        if self.is_empty() {
-            self.roots = other.roots;
-            self.top_index = other.top_index;
-            self.len = other.len;
+        if self.roots.len() != self.len && self.len <= FLAT_MAX_LEN / 2 {
+            self.flatten();
+        }
+        if self.roots.len() == self.len {
+            self.find_top();
+        } else {
+            self.consolidate_with(self.max_links);
+        }
+    }
+
+    // Turns every node into a root of its own.
+    fn flatten(&mut self) {
+        let mut ix = 0;
+        while ix < self.roots.len() {
+            let mut children = mem::take(&mut self.roots[ix].children);
+            self.counters.cuts(children.len());
+            self.roots.append(&mut children);
+            self.spare.put(children);
+            ix += 1;
+        }
+    }
+
+    // Consolidates with at most `max_links` links, then locates the minimum among all roots.
+    // In eager mode every link is made and the degree table is kept for the next `link_new_roots`.
+    fn consolidate_with(&mut self, max_links: usize) {
+        if self.roots.len() <= 1 && !self.eager {
+            self.top_index = 0;
             return;
         }
-        let FibonacciHeap { mut roots, len, top_index } = other;
-        if self.top().unwrap() > roots[top_index].value() {
-            self.top_index = self.roots.len() + top_index;
+        // Roots are processed from the back and linked in place: `roots[unprocessed..]` holds the processed roots,
+        // so a linked child is removed by swapping it with the last root and popping,
+        // and roots that take part in no link are never moved.
+        let mut budget = if self.eager { usize::MAX } else { max_links };
+        let mut unprocessed = self.roots.len();
+        #[cfg(feature = "tracing")]
+        let roots_before = self.roots.len();
+        self.degrees.clear();
+        self.clear_on_unwind(|heap| {
+            while unprocessed > 0 && budget > 0 {
+                unprocessed -= 1;
+                link_into(&mut heap.roots, &mut heap.degrees, &mut heap.spare, &mut heap.counters, &mut budget, unprocessed);
+            }
+        });
+        if !self.eager {
+            self.degrees.clear();
+        }
+        self.find_top();
+        #[cfg(feature = "tracing")]
+        trace_consolidation(roots_before, self.roots.len(), budget == 0);
+    }
+
+    // Links `roots[start..]` into the roots before them, which must all be in the degree table already.
+    fn link_new_roots(&mut self, mut start: usize) {
+        // `link_into` needs every root behind the one it links to be in the table,
+        // so several new roots move in front of the old ones, which are only O(log n).
+        let added = self.roots.len() - start;
+        if added > 1 {
+            self.roots.rotate_right(added);
+            self.degrees.iter_mut().filter(|ix| **ix != NIL).for_each(|ix| *ix += added);
+            start = 0;
+        }
+        let mut budget = usize::MAX;
+        self.clear_on_unwind(|heap| {
+            for ix in (start..start + added).rev() {
+                link_into(&mut heap.roots, &mut heap.degrees, &mut heap.spare, &mut heap.counters, &mut budget, ix);
+            }
+        });
+        self.find_top();
+    }
+
+    fn find_top(&mut self) {
+        self.clear_on_unwind(|heap| {
+            heap.top_index = 0;
+            heap.counters.root_scan();
+            for ix in 1..heap.roots.len() {
+                if less(&mut heap.counters, heap.roots[ix].value(), heap.roots[heap.top_index].value()) {
+                    heap.top_index = ix;
+                }
+            }
+        });
+    }
+
+    // Runs `f`, emptying the heap (and dropping every element once) if it unwinds: a comparison that panics halfway
+    // through linking or scanning the roots leaves `top_index` and the degree table stale, and the heap can't tell
+    // which root is the minimum without comparing again. The settings are kept.
+    fn clear_on_unwind<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        struct Guard<'a, T>(&'a mut FibonacciHeap<T>);
+
+        impl<T> Drop for Guard<'_, T> {
+            fn drop(&mut self) {
+                let torn = mem::take(self.0);
+                self.0.max_links = torn.max_links;
+                self.0.eager = torn.eager;
+                self.0.counters = torn.counters;
+            }
+        }
+
+        let guard = Guard(self);
+        let result = f(guard.0);
+        mem::forget(guard);
+        result
+    }
+
+    /// Applies `f` to every element, then restores the heap property with one bulk rebuild.
+    ///
+    /// Elements are visited exactly once, in unspecified order, and `f` may change their relative order freely.
+    /// If `f` panics, the heap is left empty and every element is dropped exactly once.
+    pub fn modify_all<F: FnMut(&mut T)>(&mut self, f: F) {
+        let mut values = self.take_values();
+        values.iter_mut().for_each(f);
+        self.replace_contents(Self::from_vec(values));
+    }
+
+    // Moves every value out of the forest without comparing anything, leaving the heap empty.
+    fn take_values(&mut self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.len);
+        let mut stack = mem::take(&mut self.roots);
+        while let Some(Node { value, children }) = stack.pop() {
+            values.push(value);
+            stack.extend(children);
         }
-        self.roots.append(&mut roots);
-        self.len += len;
+        self.degrees.clear();
+        self.top_index = 0;
+        self.len = 0;
+        values
     }
 }
 
-fn map_update<T: PartialOrd>(deg_to_root: &mut HashMap<usize, Node<T>>, mut node: Node<T>) {
-    let deg = node.degree();
-    if let Some(mut root) = deg_to_root.remove(&deg) {
-        // Root must be with smaller value
-        if node.value() < root.value() {
-            mem::swap(&mut node, &mut root);
+const NIL: usize = usize::MAX;
+const FLAT_MAX_LEN: usize = 16;
+
+// Consolidations of at least this many roots are logged at `debug` level rather than `trace`.
+#[cfg(feature = "tracing")]
+const LARGE_CONSOLIDATION: usize = 4096;
+
+// Whether the minimum is now one of the roots added from `roots[added..]` on, which is only known without
+// comparing again when the new roots weren't linked.
+#[cfg(feature = "tracing")]
+fn top_changed<T>(heap: &FibonacciHeap<T>, added: usize) -> Option<bool> {
+    (!heap.eager).then_some(heap.top_index >= added)
+}
+
+#[cfg(feature = "tracing")]
+fn trace_consolidation(roots_before: usize, roots: usize, budget_exhausted: bool) {
+    let links = roots_before - roots;
+    if roots_before >= LARGE_CONSOLIDATION {
+        tracing::debug!(roots_before, links, roots, budget_exhausted, "large consolidation");
+    } else {
+        tracing::trace!(roots_before, links, roots, budget_exhausted, "consolidation");
+    }
+}
+
+// `a < b`, for every comparison between elements.
+#[inline(always)]
+fn less<T: PartialOrd>(counters: &mut Counters, a: &T, b: &T) -> bool {
+    counters.comparison();
+    lt(a, b)
+}
+
+// `a < b`. Debug builds panic on incomparable elements instead of letting them pass as equal, which would leave a
+// wrong minimum; with a single `partial_cmp` call, so comparisons aren't doubled.
+#[inline(always)]
+pub(crate) fn lt<T: PartialOrd>(a: &T, b: &T) -> bool {
+    if cfg!(debug_assertions) {
+        match a.partial_cmp(b) {
+            Some(ordering) => ordering.is_lt(),
+            None => panic!("fibheap: incomparable elements, such as NaN, are not supported"),
         }
-        root.push_child(node);
-        map_update(deg_to_root, root);
     } else {
-        deg_to_root.insert(deg, node);
+        a < b
+    }
+}
+
+// Enters `roots[ix]` into the degree table (degree -> index in `roots` of the processed root with that degree), linking it with the processed root of equal degree (the smaller root
+// becoming the parent) and carrying the result up for as long as degrees collide.
+// Every root from `ix` on must be processed, i.e. in the table, except `roots[ix]` itself.
+// Linking keeps degrees around log2(len), but removals can leave a root with more children than that,
+// so the table grows on demand.
+// Each link uses up one unit of `budget`; once it runs out, `roots[ix]` is left where it is, unlinked.
+fn link_into<T: PartialOrd>(
+    roots: &mut Vec<Node<T>>,
+    table: &mut Vec<usize>,
+    spare: &mut SparePool<T>,
+    counters: &mut Counters,
+    budget: &mut usize,
+    mut ix: usize,
+) {
+    loop {
+        let deg = roots[ix].degree();
+        if deg >= table.len() {
+            table.resize(deg + 1, NIL);
+        }
+        let other = mem::replace(&mut table[deg], NIL);
+        if other == NIL {
+            table[deg] = ix;
+            return;
+        }
+        if *budget == 0 {
+            return;
+        }
+        *budget -= 1;
+        // Root must be with smaller value
+        let (mut parent, child) = if less(counters, roots[ix].value(), roots[other].value()) { (ix, other) } else { (other, ix) };
+        let last = roots.len() - 1;
+        if child != last {
+            roots.swap(child, last);
+            if parent == last {
+                parent = child;
+            } else {
+                table[roots[child].degree()] = child;
+            }
+        }
+        let child = roots.pop().unwrap();
+        let children = &mut roots[parent].children;
+        if children.len() == children.capacity() {
+            // instead of growing, switch to a spare vector with room for one more child
+            if let Some(mut bigger) = spare.take(children.len() + 1) {
+                bigger.append(children);
+                spare.put(mem::replace(children, bigger));
+            } else {
+                counters.allocation();
+            }
+        }
+        children.push(child);
+        counters.link();
+        ix = parent;
     }
 }
 
@@ -200,26 +1336,240 @@ impl<T: PartialOrd> Iterator for FibonacciHeap<T> {
     }
 }
 
-impl<T: PartialOrd> Default for FibonacciHeap<T> {
-    fn default() -> Self {
-        Self { roots: vec![], top_index: 0, len: 0 }
+/// Multiset union: `a + b` melds both heaps exactly like [`append`](FibonacciHeap::append).
+impl<T: PartialOrd> Add for FibonacciHeap<T> {
+    type Output = Self;
+    fn add(mut self, other: Self) -> Self {
+        self.append(other);
+        self
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Multiset union in place; same as [`append`](FibonacciHeap::append).
+impl<T: PartialOrd> AddAssign for FibonacciHeap<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.append(other);
+    }
+}
 
-    #[test]
-    fn constructors() {
-        let heap = FibonacciHeap::<i32>::new();
-        assert!(heap.top().is_none());
+/// Multiset union with the elements of a vector, inserted in bulk as by [`from_vec`](FibonacciHeap::from_vec).
+impl<T: PartialOrd> Add<Vec<T>> for FibonacciHeap<T> {
+    type Output = Self;
+    fn add(mut self, values: Vec<T>) -> Self {
+        self += values;
+        self
+    }
+}
 
-        let heap = FibonacciHeap::<i32>::from_vec(vec![]);
-        assert!(heap.top().is_none());
+/// Bulk insertion of the elements of a vector, as by [`from_vec`](FibonacciHeap::from_vec) and [`append`](FibonacciHeap::append).
+impl<T: PartialOrd> AddAssign<Vec<T>> for FibonacciHeap<T> {
+    fn add_assign(&mut self, values: Vec<T>) {
+        self.append(Self::from_vec(values));
     }
+}
 
-    #[test]
+/// Deep copy preserving the exact tree structure, so the clone pops in the same order as the original.
+impl<T: Clone> Clone for FibonacciHeap<T> {
+    fn clone(&self) -> Self {
+        Self { roots: self.roots.clone(), top_index: self.top_index, len: self.len, degrees: self.degrees.clone(), spare: SparePool::default(), max_links: self.max_links, eager: self.eager, counters: self.counters }
+    }
+
+    /// Reuses the root list and the children vectors of `self` for the copy,
+    /// so it allocates next to nothing when `self` already holds a heap of similar shape (e.g. an earlier snapshot of `source`).
+    fn clone_from(&mut self, source: &Self) {
+        // Tear down the current trees, keeping every children vector that has room in it.
+        // Binomial-like trees have at most half of their nodes as inner nodes.
+        let mut pool: Vec<Vec<Node<T>>> = Vec::with_capacity(self.len / 2 + 1);
+        while let Some(Node { value, mut children }) = self.roots.pop() {
+            drop(value);
+            self.roots.append(&mut children);
+            if children.capacity() > 0 {
+                pool.push(children);
+            }
+        }
+        pool.sort_unstable_by_key(Vec::capacity);
+        // Per capacity, the range of unused pooled vectors with exactly that capacity.
+        let max_capacity = pool.last().map_or(0, Vec::capacity);
+        let mut free: Vec<(usize, usize)> = (0..=max_capacity)
+            .map(|cap| (pool.partition_point(|v| v.capacity() < cap), pool.partition_point(|v| v.capacity() <= cap)))
+            .collect();
+        let mut buffer = |degree: usize| {
+            if degree == 0 {
+                return vec![];
+            }
+            for (next, end) in free.iter_mut().skip(degree) {
+                if next < end {
+                    *next += 1;
+                    return mem::take(&mut pool[*next - 1]);
+                }
+            }
+            Vec::with_capacity(degree)
+        };
+
+        let mut stack = vec![];
+        self.roots.reserve(source.roots.len());
+        for root in &source.roots {
+            self.roots.push(root.clone_with(&mut stack, &mut buffer));
+        }
+        self.top_index = source.top_index;
+        self.len = source.len;
+        self.max_links = source.max_links;
+        self.eager = source.eager;
+        self.degrees.clone_from(&source.degrees);
+    }
+}
+
+/// Multiset equality: heaps are equal when they hold the same elements with the same multiplicities,
+/// whatever their internal shape.
+///
+/// Lengths are compared first; otherwise both heaps are walked with [`iter_sorted`](FibonacciHeap::iter_sorted),
+/// which costs O(n log n) and stops at the first difference.
+/// A total order is required so that equal multisets are guaranteed to produce equal sorted sequences.
+impl<T: Ord> PartialEq for FibonacciHeap<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter_sorted().eq(other.iter_sorted())
+    }
+}
+
+impl<T: Ord> Eq for FibonacciHeap<T> {}
+
+/// Lexicographic comparison of the ascending element sequences, as if both heaps were turned into sorted vectors.
+///
+/// Consistent with the multiset equality. Neither heap is consumed or cloned: both are walked with
+/// [`iter_sorted`](FibonacciHeap::iter_sorted), stopping at the first difference.
+/// A heap whose sorted contents are a prefix of another's (e.g. the empty heap) compares less.
+///
+/// Since the heap is also an [`Iterator`], `a.cmp(&b)` resolves to [`Iterator::cmp`];
+/// use the comparison operators or `Ord::cmp(&a, &b)` instead.
+impl<T: Ord> PartialOrd for FibonacciHeap<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for FibonacciHeap<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter_sorted().cmp(other.iter_sorted())
+    }
+}
+
+/// Order-independent hash, consistent with the multiset equality.
+///
+/// Each element is hashed on its own with a fixed-key [`DefaultHasher`] and the results are combined by wrapping addition,
+/// so neither the internal shape nor the insertion order matters; the sum and `len()` are then fed to `state`.
+/// This is O(n) and allocation free. Requires the `std` feature.
+#[cfg(feature = "std")]
+impl<T: Hash> Hash for FibonacciHeap<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let sum = self.iter().fold(0u64, |sum, value| {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            sum.wrapping_add(hasher.finish())
+        });
+        state.write_usize(self.len);
+        state.write_u64(sum);
+    }
+}
+
+// Limits of the `{:#?}` tree dump; anything beyond is printed as `...`.
+const DEBUG_MAX_DEPTH: usize = 4;
+const DEBUG_MAX_WIDTH: usize = 8;
+
+/// `{:?}` prints a summary (`FibonacciHeap { len: 3, top: Some(1), roots: 2 }`) in O(1), whatever the size of the heap.
+///
+/// `{:#?}` lists the root forest instead, each node followed by its children.
+/// Lists longer than 8 entries are cut with a `...` entry, and children deeper than 4 levels are shown as `[...]`.
+impl<T: fmt::Debug> fmt::Debug for FibonacciHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let alternate = f.alternate();
+        let mut debug = f.debug_struct("FibonacciHeap");
+        debug.field("len", &self.len).field("top", &self.peek());
+        if alternate {
+            debug.field("roots", &DebugForest { nodes: &self.roots, depth: 0 });
+        } else {
+            debug.field("roots", &self.roots.len());
+        }
+        debug.finish()
+    }
+}
+
+struct DebugForest<'a, T> {
+    nodes: &'a [Node<T>],
+    depth: usize,
+}
+
+impl<T: fmt::Debug> fmt::Debug for DebugForest<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        for node in self.nodes.iter().take(DEBUG_MAX_WIDTH) {
+            list.entry(&DebugTree { node, depth: self.depth });
+        }
+        if self.nodes.len() > DEBUG_MAX_WIDTH {
+            list.entry(&format_args!("..."));
+        }
+        list.finish()
+    }
+}
+
+struct DebugTree<'a, T> {
+    node: &'a Node<T>,
+    depth: usize,
+}
+
+impl<T: fmt::Debug> fmt::Debug for DebugTree<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.node.value.fmt(f)?;
+        if self.node.children.is_empty() {
+            Ok(())
+        } else if self.depth + 1 < DEBUG_MAX_DEPTH {
+            f.write_str(" ")?;
+            DebugForest { nodes: &self.node.children, depth: self.depth + 1 }.fmt(f)
+        } else {
+            f.write_str(" [...]")
+        }
+    }
+}
+
+/// Tears the forest down with an explicit stack, so that dropping a deep tree can't overflow the call stack.
+impl<T> Drop for FibonacciHeap<T> {
+    fn drop(&mut self) {
+        drop_forest(&mut self.roots);
+    }
+}
+
+// Drops every tree in `roots` with an explicit stack, as trees can be far too deep for recursive drops,
+// leaving `roots` empty with its allocation.
+fn drop_forest<T>(roots: &mut Vec<Node<T>>) {
+    while let Some(mut node) = roots.pop() {
+        roots.append(&mut node.children);
+    }
+}
+
+impl<T> Default for FibonacciHeap<T> {
+    fn default() -> Self {
+        Self { roots: vec![], top_index: 0, len: 0, degrees: vec![], spare: SparePool::default(), max_links: usize::MAX, eager: false, counters: Counters::default() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{DropCounter, Rng};
+    use crate::{assert_heap_eq, assert_valid};
+    use std::cell::Cell;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::rc::Rc;
+
+    #[test]
+    fn constructors() {
+        let heap = FibonacciHeap::<i32>::new();
+        assert!(heap.peek().is_none());
+
+        let heap = FibonacciHeap::<i32>::from_vec(vec![]);
+        assert!(heap.peek().is_none());
+    }
+
+    #[test]
     fn pop() {
         let mut heap = FibonacciHeap::<i32>::from_vec(vec![3, 5, 1, 9]);
         assert_eq!(heap.pop().unwrap(), 1);
@@ -233,13 +1583,54 @@ mod tests {
         let mut heap = FibonacciHeap::<i32>::from_vec(vec![3, 5, 1, 9]);
         let heap2 = FibonacciHeap::<i32>::from_vec(vec![8, 2, 7, 4, 6]);
         heap.append(heap2);
-        for i in 1..=9 {
-            // TODO: This produces 1, 2, 3, 4, 6, 5, 7, 8, 9 (5, 6 are not correct order);
-            assert_eq!(heap.pop().unwrap(), i);
+        assert_heap_eq!(heap, (1..=9).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn append_random_pairs() {
+        // a heap with a random shape: built in bulk or by pushes, partly consolidated by pops, in either mode
+        fn random_heap(rng: &mut Rng, contents: &mut Vec<u64>) -> FibonacciHeap<u64> {
+            let values: Vec<u64> = (0..rng.below(200)).map(|_| rng.below(50)).collect();
+            let mut heap = match rng.below(3) {
+                0 => FibonacciHeap::from_vec(values),
+                1 => {
+                    let mut heap = FibonacciHeap::new();
+                    values.into_iter().for_each(|value| heap.push(value));
+                    heap
+                }
+                _ => {
+                    let mut heap = FibonacciHeap::new();
+                    heap.bulk_push(values);
+                    heap
+                }
+            };
+            match rng.below(4) {
+                0 => heap.set_eager_consolidation(true),
+                1 => heap.set_max_links_per_pop(Some(2)),
+                _ => {}
+            }
+            for _ in 0..rng.below(20) {
+                heap.pop();
+                heap.push(rng.below(50));
+            }
+            contents.extend(heap.iter().copied());
+            heap
+        }
+
+        let mut rng = Rng(0x243f6a8885a308d3);
+        for _ in 0..500 {
+            let mut contents = vec![];
+            let mut heap = random_heap(&mut rng, &mut contents);
+            heap.append(random_heap(&mut rng, &mut contents));
+            assert_valid!(heap);
+            contents.sort_unstable();
+            assert_eq!(heap.len(), contents.len());
+            assert_heap_eq!(heap, contents);
         }
     }
 
     #[test]
+    #[allow(clippy::useless_conversion)]
     fn pop_large() {
         let mut heap = FibonacciHeap::new();
         for i in (0..1000000).rev() {
@@ -249,4 +1640,916 @@ mod tests {
             assert_eq!(i, v);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn modify_all() {
+        let mut heap = FibonacciHeap::<i32>::from_vec(vec![3, 5, 1, 9, 7]);
+        heap.pop();
+        let mut visited = vec![];
+        heap.modify_all(|v| {
+            visited.push(*v);
+            *v = -*v;
+        });
+        visited.sort();
+        assert_eq!(visited, vec![3, 5, 7, 9]);
+        assert_eq!(heap.len(), 4);
+        assert_heap_eq!(heap, vec![-9, -7, -5, -3]);
+
+        let mut heap = FibonacciHeap::<i32>::new();
+        heap.modify_all(|_| unreachable!());
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn modify_all_panic() {
+        let drops = Rc::new(Cell::new(0));
+        let mut heap = FibonacciHeap::new();
+        for i in 0..100 {
+            heap.push(DropCounter::new(i, &drops));
+        }
+        heap.pop();
+        assert_eq!(drops.get(), 1);
+
+        let mut visits = 0;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            heap.modify_all(|v| {
+                visits += 1;
+                if visits == 50 {
+                    panic!("boom");
+                }
+                v.value += 1000;
+            })
+        }));
+        assert!(result.is_err());
+        assert!(heap.is_empty());
+        assert!(heap.pop().is_none());
+        assert_eq!(drops.get(), 100);
+    }
+
+    #[test]
+    fn incomparable_elements() {
+        let builds: [fn() -> FibonacciHeap<f64>; 3] = [
+            || {
+                let mut heap = FibonacciHeap::new();
+                [2.0, f64::NAN, 1.0].into_iter().for_each(|value| heap.push(value));
+                heap
+            },
+            || FibonacciHeap::from_vec(vec![2.0, f64::NAN, 1.0]),
+            || {
+                let mut heap = FibonacciHeap::from_vec(vec![2.0, 1.0]);
+                heap.append(FibonacciHeap::from_vec(vec![f64::NAN]));
+                heap
+            },
+        ];
+        for build in builds {
+            if cfg!(debug_assertions) {
+                let payload = panic::catch_unwind(build).unwrap_err();
+                assert_eq!(payload.downcast_ref(), Some(&"fibheap: incomparable elements, such as NaN, are not supported"));
+            } else {
+                // every element is kept, in whatever order
+                let heap = build();
+                assert_eq!(heap.len(), 3);
+                let mut values = heap.clone().into_vec();
+                values.sort_by(f64::total_cmp);
+                assert!(values[..2] == [1.0, 2.0] && values[2].is_nan());
+                assert_eq!(heap.into_sorted_vec().len(), 3);
+            }
+        }
+    }
+
+    #[test]
+    fn panicking_comparison() {
+        thread_local! {
+            // comparisons left before the next one panics
+            static FUSE: Cell<usize> = const { Cell::new(usize::MAX) };
+        }
+
+        #[derive(Debug)]
+        struct Fused(DropCounter);
+
+        impl PartialEq for Fused {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl PartialOrd for Fused {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                if FUSE.replace(FUSE.get().saturating_sub(1)) == 1 {
+                    panic!("comparison failed");
+                }
+                self.0.partial_cmp(&other.0)
+            }
+        }
+
+        let mut rng = Rng(0xcbbb9d5dc1059ed8);
+        for fuse in 1..400 {
+            let drops = Rc::new(Cell::new(0));
+            let mut created = 0;
+            let mut fused = |rng: &mut Rng| {
+                created += 1;
+                Fused(DropCounter::new(rng.below(100) as i32, &drops))
+            };
+            let mut heap = FibonacciHeap::new();
+            if fuse % 5 == 0 {
+                heap.set_eager_consolidation(true);
+            }
+            (0..200).for_each(|_| heap.push(fused(&mut rng)));
+            heap.pop();
+            let others: Vec<Fused> = (0..50).map(|_| fused(&mut rng)).collect();
+            let other = FibonacciHeap::from_vec((0..20).map(|_| fused(&mut rng)).collect());
+            let pushed = fused(&mut rng);
+
+            FUSE.set(fuse);
+            let result = panic::catch_unwind(AssertUnwindSafe(|| match fuse % 4 {
+                0 => {
+                    heap.push(pushed);
+                    heap.pop();
+                }
+                1 => {
+                    heap.bulk_push(others);
+                    heap.consolidate();
+                }
+                2 => {
+                    heap.append(other);
+                    heap.pop();
+                }
+                _ => (0..100).for_each(|_| drop(heap.pop())),
+            }));
+            FUSE.set(usize::MAX);
+
+            if result.is_err() {
+                // or, in debug builds, the operation completed and the check of its result panicked
+                assert!(heap.is_empty() || cfg!(debug_assertions) && heap.validate().is_ok(), "fuse {fuse}: {heap:?}");
+                assert_eq!(heap.eager_consolidation(), fuse % 5 == 0);
+            }
+            // whatever happened, the heap is usable and holds what wasn't dropped
+            let remaining = heap.len();
+            (0..10).for_each(|_| heap.push(fused(&mut rng)));
+            let drained: Vec<i32> = heap.into_sorted_vec().into_iter().map(|fused| fused.0.value).collect();
+            assert!(drained.windows(2).all(|pair| pair[0] <= pair[1]));
+            assert_eq!(drained.len(), remaining + 10);
+            assert_eq!(drops.get(), created, "fuse {fuse}");
+        }
+    }
+
+    #[test]
+    fn push_pop() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        assert_eq!(heap.push_pop(5), 5);
+        assert!(heap.is_empty());
+
+        let mut heap = FibonacciHeap::<i32>::from_vec(vec![3, 5, 1, 9]);
+        assert_eq!(heap.push_pop(0), 0);
+        assert_eq!(heap.push_pop(1), 1);
+        assert_eq!(heap.push_pop(4), 1);
+        assert_eq!(heap.len(), 4);
+        assert_heap_eq!(heap, vec![3, 4, 5, 9]);
+    }
+
+    #[test]
+    fn replace_top() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        assert_eq!(heap.replace_top(5), None);
+        assert_eq!(heap.peek(), Some(&5));
+
+        let mut heap = FibonacciHeap::<i32>::from_vec(vec![3, 5, 1, 9]);
+        assert_eq!(heap.replace_top(10), Some(1));
+        assert_eq!(heap.replace_top(0), Some(3));
+        assert_eq!(heap.len(), 4);
+        assert_heap_eq!(heap, vec![0, 5, 9, 10]);
+    }
+
+    #[test]
+    fn reduce_pairwise() {
+        let heap = FibonacciHeap::<i32>::new();
+        assert_eq!(heap.reduce_pairwise(|a, b| a + b), None);
+
+        let heap = FibonacciHeap::<i32>::from_vec(vec![7]);
+        assert_eq!(heap.reduce_pairwise(|_, _| unreachable!()), Some(7));
+
+        let mut order = vec![];
+        let heap = FibonacciHeap::<i32>::from_vec(vec![4, 1, 3, 2]);
+        let total = heap.reduce_pairwise(|a, b| {
+            order.push((a, b));
+            a + b
+        });
+        assert_eq!(total, Some(10));
+        assert_eq!(order, vec![(1, 2), (3, 3), (4, 6)]);
+    }
+
+    #[test]
+    fn reduce_pairwise_tie_order() {
+        // The Huffman cost (sum of every combined weight) must not depend on how ties are broken.
+        let mut rng = Rng(0x2545f4914f6cdd1d);
+        for _ in 0..200 {
+            let len = rng.below(40) as usize + 1;
+            let mut weights: Vec<u64> = (0..len).map(|_| rng.below(8) + 1).collect();
+
+            let mut sorted = weights.clone();
+            let mut expected = 0;
+            while sorted.len() > 1 {
+                sorted.sort_unstable_by(|a, b| b.cmp(a));
+                let merged = sorted.pop().unwrap() + sorted.pop().unwrap();
+                expected += merged;
+                sorted.push(merged);
+            }
+
+            for _ in 0..5 {
+                rng.shuffle(&mut weights);
+                let mut cost = 0;
+                let heap = FibonacciHeap::from_vec(weights.clone());
+                let total = heap.reduce_pairwise(|a, b| {
+                    cost += a + b;
+                    a + b
+                });
+                assert_eq!(total, Some(weights.iter().sum()));
+                assert_eq!(cost, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn consolidate() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        heap.consolidate();
+        assert_eq!(heap.root_count(), 0);
+        assert!(heap.peek().is_none());
+
+        for i in (0..1000).rev() {
+            heap.push(i);
+        }
+        assert_eq!(heap.root_count(), 1000);
+        heap.consolidate();
+        // 1000 = 0b1111101000: one tree per set bit
+        assert_eq!(heap.root_count(), 6);
+        assert_eq!(heap.peek(), Some(&0));
+        assert_eq!(heap.len(), 1000);
+
+        heap.consolidate();
+        assert_eq!(heap.root_count(), 6);
+        assert_heap_eq!(heap, (0..1000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn consolidate_cascade_on_small_stack() {
+        // Draining 2^k + 1 roots makes the last one cascade through every degree below k;
+        // with 4 KiB elements, a stack frame per link wouldn't fit in the thread below.
+        const K: u32 = 12;
+        let mut heap = FibonacciHeap::new();
+        for i in 0..=(1u64 << K) {
+            heap.push([i; 512]);
+        }
+        let popped = std::thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(move || {
+                let first = heap.pop().map(|value| value[0]);
+                assert_eq!(heap.root_count(), 1);
+                (first, heap.pop().map(|value| value[0]))
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(popped, (Some(0), Some(1)));
+    }
+
+    #[test]
+    fn shrink_to_fit() {
+        let mut heap = FibonacciHeap::from_vec((0..1000).rev().collect());
+        for _ in 0..500 {
+            heap.pop();
+        }
+        assert!(heap.degrees.capacity() > 0);
+        heap.shrink_to_fit();
+        assert_eq!(heap.degrees.capacity(), 0);
+        assert!(heap.spare.buckets.is_empty());
+        assert_eq!(heap.roots.capacity(), heap.roots.len());
+        assert_heap_eq!(heap, (500..1000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn max_links_per_pop() {
+        let mut rng = Rng(0x510e527fade682d1);
+        let mut heap = FibonacciHeap::new();
+        heap.set_max_links_per_pop(Some(4));
+        assert_eq!(heap.max_links_per_pop(), Some(4));
+        let mut reference = std::collections::BinaryHeap::new();
+        let mut max_roots = 0;
+        for round in 0..5_000 {
+            // bursts of pushes, so that the root list outgrows the cap
+            let pushes = if round % 100 == 0 { 500 } else { 1 };
+            for _ in 0..pushes {
+                let value = rng.below(10_000);
+                heap.push(value);
+                reference.push(std::cmp::Reverse(value));
+            }
+            let (roots, degree) = (heap.roots.len(), heap.roots[heap.top_index].degree());
+            assert_eq!(heap.pop(), reference.pop().map(|r| r.0));
+            let links = roots - 1 + degree - heap.roots.len();
+            assert!(links <= 4, "{links} links");
+            max_roots = max_roots.max(heap.roots.len());
+        }
+        assert!(max_roots > 100);
+
+        heap.set_max_links_per_pop(None);
+        assert_eq!(heap.max_links_per_pop(), None);
+        heap.pop();
+        reference.pop();
+        assert!(heap.root_count() <= usize::BITS as usize);
+        assert_eq!(heap.into_sorted_vec(), reference.into_sorted_vec().into_iter().rev().map(|r| r.0).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn eager_consolidation() {
+        fn assert_distinct_degrees(heap: &FibonacciHeap<u64>) {
+            let mut degrees: Vec<usize> = heap.roots.iter().map(Node::degree).collect();
+            degrees.sort_unstable();
+            degrees.dedup();
+            assert_eq!(degrees.len(), heap.roots.len());
+            assert_eq!(heap.peek(), heap.roots.iter().map(Node::value).min());
+        }
+
+        let mut rng = Rng(0x9bdc06a725c71235);
+        let mut heap = FibonacciHeap::from_vec((0..100).map(|_| rng.below(1000)).collect());
+        let mut lazy = heap.clone();
+        heap.set_eager_consolidation(true);
+        assert!(heap.eager_consolidation());
+        assert_distinct_degrees(&heap);
+        for round in 0..3_000 {
+            match rng.below(6) {
+                0..=2 => {
+                    let value = rng.below(1000);
+                    heap.push(value);
+                    lazy.push(value);
+                }
+                3 => {
+                    let values: Vec<u64> = (0..rng.below(10)).map(|_| rng.below(1000)).collect();
+                    heap.bulk_push(values.clone());
+                    lazy.bulk_push(values);
+                }
+                4 => {
+                    let value = rng.below(1000);
+                    let removed = heap.remove_by(|x| x.partial_cmp(&value)).is_some();
+                    assert_eq!(removed, lazy.remove_by(|x| x.partial_cmp(&value)).is_some());
+                }
+                _ => assert_eq!(heap.pop(), lazy.pop()),
+            }
+            if round % 500 == 0 {
+                heap.modify_all(|x| *x = 999 - *x);
+                lazy.modify_all(|x| *x = 999 - *x);
+                for other in heap.split_forest(3) {
+                    heap.append(other);
+                }
+                heap = heap.clone();
+            }
+            assert_distinct_degrees(&heap);
+            assert_valid!(heap);
+            assert_eq!(heap.len(), lazy.len());
+        }
+
+        heap.clear();
+        heap.append(FibonacciHeap::from_vec(vec![3, 1, 2]));
+        heap.push(0);
+        assert!(heap.eager_consolidation());
+        assert_distinct_degrees(&heap);
+        heap.set_eager_consolidation(false);
+        heap.push(4);
+        assert_eq!(heap.root_count(), 2);
+        assert_heap_eq!(heap, [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn small_heaps_stay_flat() {
+        let mut rng = Rng(0x3c6ef372fe94f82b);
+        let mut heap = FibonacciHeap::new();
+        let mut reference = std::collections::BinaryHeap::new();
+        let (mut flattened, mut linked) = (0, 0);
+        for round in 0..20_000 {
+            // the length drifts up and down across the flat limit
+            let pushes = if (round / 500) % 2 == 0 { 3 } else { 1 };
+            if rng.below(4) < pushes {
+                let value = rng.below(100);
+                heap.push(value);
+                reference.push(std::cmp::Reverse(value));
+            } else {
+                let was_flat = heap.roots.len() == heap.len;
+                assert_eq!(heap.pop(), reference.pop().map(|r| r.0));
+                let flat = heap.roots.len() == heap.len;
+                if heap.len <= FLAT_MAX_LEN / 2 {
+                    flattened += usize::from(!was_flat);
+                    assert!(flat);
+                } else if heap.len > FLAT_MAX_LEN {
+                    linked += usize::from(was_flat);
+                    assert!(!flat || heap.len < 2);
+                }
+            }
+            if round % 1000 == 999 {
+                let other: Vec<u64> = (0..rng.below(60)).map(|_| rng.below(100)).collect();
+                other.iter().for_each(|&value| reference.push(std::cmp::Reverse(value)));
+                heap.append(FibonacciHeap::from_vec(other));
+            }
+            assert_eq!(heap.peek(), reference.peek().map(|r| &r.0));
+            assert_eq!(heap.len(), reference.len());
+            assert_valid!(heap);
+        }
+        assert!(flattened > 0 && linked > 0, "{flattened} {linked}");
+    }
+
+    #[test]
+    fn into_forest() {
+        assert!(FibonacciHeap::<i32>::new().into_forest().is_empty());
+
+        let mut rng = Rng(0x3c6ef372fe94f82b);
+        let mut values: Vec<u64> = (0..1000).map(|_| rng.below(100)).collect();
+        let mut heap = FibonacciHeap::from_vec(values.clone());
+        for _ in 0..10 {
+            heap.pop();
+        }
+        values.sort_unstable();
+        values.drain(..10);
+
+        heap.consolidate();
+        let trees = heap.root_count();
+        let pieces = heap.into_forest();
+        assert_eq!(pieces.len(), trees);
+        assert_eq!(pieces.iter().map(|piece| piece.len()).sum::<usize>(), values.len());
+        let mut merged = FibonacciHeap::new();
+        for piece in pieces {
+            assert_eq!(piece.root_count(), 1);
+            assert!(!piece.is_empty());
+            let sorted = piece.into_sorted_vec();
+            assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+            merged.append(FibonacciHeap::from_vec(sorted));
+        }
+        assert_heap_eq!(merged, values);
+    }
+
+    #[test]
+    fn split_forest() {
+        let mut heap = FibonacciHeap::from_vec((0..10).collect());
+        assert!(heap.split_forest(1).is_empty());
+        assert_eq!(heap.len(), 10);
+        // 10 = 8 + 2: only two trees to hand out
+        let others = heap.split_forest(5);
+        assert_eq!(others.len(), 1);
+        assert_eq!((heap.len(), others[0].len()), (8, 2));
+
+        let mut heap = FibonacciHeap::from_vec((0..(1 << 12) - 1).rev().collect());
+        let mut groups = heap.split_forest(3);
+        assert_eq!(groups.len(), 2);
+        groups.push(heap);
+        let mut sizes: Vec<usize> = groups.iter().map(|group| group.len()).collect();
+        sizes.sort_unstable();
+        // trees of 2048, 1024, ..., 1: the two largest each get their own group
+        assert_eq!(sizes, [1023, 1024, 2048]);
+
+        let mut merged = FibonacciHeap::new();
+        for group in groups {
+            merged.append(group);
+        }
+        assert_heap_eq!(merged, (0..(1 << 12) - 1).collect::<Vec<_>>());
+
+        let mut empty = FibonacciHeap::<i32>::new();
+        assert!(empty.split_forest(4).is_empty());
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn split_half() {
+        let mut rng = Rng(0x510e527fade682d1);
+        let values: Vec<u64> = (0..1000).map(|_| rng.below(300)).collect();
+        let mut heap = FibonacciHeap::from_vec(values.clone());
+        heap.set_max_links_per_pop(Some(64));
+        heap.pop();
+        let largest = heap.roots.iter().map(Node::subtree_len).max().unwrap();
+        let other = heap.split_half();
+        assert_eq!(heap.len() + other.len(), 999);
+        assert!(heap.len().abs_diff(other.len()) <= largest);
+        assert_eq!(heap.max_links_per_pop(), Some(64));
+        assert_valid!(heap);
+        assert_valid!(other);
+
+        // down to single elements, and gathered back
+        let mut pieces = vec![heap, other];
+        let mut singles = vec![];
+        while let Some(mut piece) = pieces.pop() {
+            if piece.len() <= 1 {
+                singles.push(piece);
+                continue;
+            }
+            let other = piece.split_half();
+            assert!(!piece.is_empty() && !other.is_empty());
+            assert_valid!(piece);
+            assert_valid!(other);
+            pieces.extend([piece, other]);
+        }
+        assert_eq!(singles.len(), 999);
+        let mut merged = FibonacciHeap::new();
+        singles.into_iter().for_each(|single| merged.append(single));
+        let mut expected = values;
+        expected.sort_unstable();
+        assert_heap_eq!(merged, expected[1..]);
+
+        let mut empty = FibonacciHeap::<i32>::new();
+        assert!(empty.split_half().is_empty());
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn clone() {
+        let mut rng = Rng(0xa54ff53a5f1d36f1);
+        let mut heap = FibonacciHeap::new();
+        for _ in 0..2000 {
+            heap.push(rng.below(500));
+            if rng.below(4) == 0 {
+                heap.pop();
+            }
+        }
+        for _ in 0..10 {
+            heap.push(rng.below(500));
+        }
+        let snapshot = heap.clone();
+        assert_eq!((snapshot.len(), snapshot.root_count(), snapshot.peek()), (heap.len(), heap.root_count(), heap.peek()));
+        assert_eq!(snapshot.collect::<Vec<_>>(), heap.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn clone_deep_tree() {
+        // a single path, deeper than recursion could handle
+        let mut root = Node::new(199_999);
+        for value in (0..199_999).rev() {
+            root = Node { value, children: vec![root] };
+        }
+        let heap = FibonacciHeap { roots: vec![root], top_index: 0, len: 200_000, degrees: vec![], spare: SparePool::default(), max_links: usize::MAX, eager: false, counters: Counters::default() };
+        let snapshot = heap.clone();
+        assert!(snapshot.eq(heap));
+    }
+
+    #[test]
+    fn drop_deep_tree_on_small_stack() {
+        let drops = std::thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(|| {
+                let drops = Rc::new(Cell::new(0));
+                // a single path of 300 000 nodes
+                let mut root = Node::new(DropCounter::new(299_999, &drops));
+                for value in (0..299_999).rev() {
+                    root = Node { value: DropCounter::new(value, &drops), children: vec![root] };
+                }
+                let heap = FibonacciHeap { roots: vec![root, Node::new(DropCounter::new(-1, &drops))], top_index: 1, len: 300_001, degrees: vec![], spare: SparePool::default(), max_links: usize::MAX, eager: false, counters: Counters::default() };
+                drop(heap);
+                drops.get()
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(drops, 300_001);
+    }
+
+    #[test]
+    fn clear_deep_tree_on_small_stack() {
+        std::thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(|| {
+                // a single run builds a tree about half its length deep
+                let mut heap = FibonacciHeap::from_sorted_runs(vec![(0..300_000u32).collect()]);
+                assert!(heap.stats().max_depth > 100_000);
+                heap.clear();
+                assert!(heap.is_empty() && heap.root_count() == 0);
+                heap.push(1);
+                assert_eq!(heap.pop(), Some(1));
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn clone_from() {
+        let mut rng = Rng(0x510e527fade682d1);
+        let mut source = FibonacciHeap::new();
+        let mut scratch = FibonacciHeap::from_vec(vec![0; 100]);
+        for round in 0..50 {
+            for _ in 0..200 {
+                source.push(rng.below(1000));
+                if rng.below(3) == 0 {
+                    source.pop();
+                }
+            }
+            if round % 10 == 9 {
+                scratch = FibonacciHeap::new();
+            }
+            scratch.clone_from(&source);
+            assert_eq!((scratch.len(), scratch.root_count(), scratch.peek()), (source.len(), source.root_count(), source.peek()));
+            assert_heap_eq!(scratch, source.clone().into_sorted_vec());
+        }
+        // shrinking drops the surplus
+        let drops = Rc::new(Cell::new(0));
+        let mut big = FibonacciHeap::from_vec((0..100).map(|v| DropCounter::new(v, &drops)).collect());
+        big.pop();
+        let drops_before = drops.get();
+        let small = FibonacciHeap::from_vec(vec![DropCounter::new(7, &Rc::new(Cell::new(0)))]);
+        big.clone_from(&small);
+        assert_eq!(drops.get() - drops_before, 99);
+        assert_eq!(big.len(), 1);
+        assert_eq!(big.peek().map(|v| v.value), Some(7));
+    }
+
+    #[test]
+    fn debug_formats() {
+        let mut heap = FibonacciHeap::from_vec((0..8).collect());
+        heap.pop();
+        // small heaps stay flat through `pop`
+        assert_eq!(heap.root_count(), 7);
+        heap.consolidate();
+        assert_eq!(format!("{heap:?}"), "FibonacciHeap { len: 7, top: Some(1), roots: 3 }");
+        assert_eq!(
+            format!("{heap:#?}"),
+            "\
+FibonacciHeap {
+    len: 7,
+    top: Some(
+        1,
+    ),
+    roots: [
+        1,
+        2 [
+            3,
+        ],
+        4 [
+            5,
+            6 [
+                7,
+            ],
+        ],
+    ],
+}"
+        );
+        assert_eq!(format!("{:?}", FibonacciHeap::<u8>::new()), "FibonacciHeap { len: 0, top: None, roots: 0 }");
+
+        // wide root list
+        let mut wide = FibonacciHeap::new();
+        (0..10).for_each(|value| wide.push(value));
+        let dump = format!("{wide:#?}");
+        assert!(dump.contains("        7,\n        ...,\n    ],"), "{dump}");
+        assert!(!dump.contains(" 8,"), "{dump}");
+
+        // single tree of depth 5
+        let mut deep = FibonacciHeap::from_vec((0..33).collect());
+        deep.pop();
+        deep.consolidate();
+        let dump = format!("{deep:#?}");
+        assert!(dump.contains("                    15 [...],"), "{dump}");
+    }
+
+    #[test]
+    fn identical_runs_build_identical_heaps() {
+        // ordered by `key` only, so that the pop order among equal keys shows in `id`
+        #[derive(Debug)]
+        struct Tagged {
+            key: u64,
+            id: u64,
+        }
+
+        impl PartialEq for Tagged {
+            fn eq(&self, other: &Self) -> bool {
+                self.key == other.key
+            }
+        }
+
+        impl PartialOrd for Tagged {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                self.key.partial_cmp(&other.key)
+            }
+        }
+
+        let run = || {
+            let mut rng = Rng(0x3c6ef372fe94f82b);
+            let mut id = 0;
+            let mut tagged = |rng: &mut Rng| {
+                id += 1;
+                Tagged { key: rng.below(16), id }
+            };
+            let mut heap = FibonacciHeap::new();
+            for _ in 0..5_000 {
+                match rng.below(8) {
+                    0..=3 => heap.push(tagged(&mut rng)),
+                    4 => heap.bulk_push((0..rng.below(10)).map(|_| tagged(&mut rng)).collect::<Vec<_>>()),
+                    5 => heap.append(FibonacciHeap::from_vec((0..rng.below(10)).map(|_| tagged(&mut rng)).collect())),
+                    _ => drop(heap.pop()),
+                }
+            }
+            let dump = format!("{heap:#?}");
+            (dump, heap.map(|value| value.id).collect::<Vec<_>>())
+        };
+        let (dump, ids) = run();
+        assert!(dump.contains('['), "{dump}");
+        assert_eq!(run(), (dump, ids));
+    }
+
+    #[test]
+    fn deprecated_aliases() {
+        let heap = FibonacciHeap::from_vec(vec![2, 1, 3]);
+        #[allow(deprecated)]
+        let top = heap.top();
+        assert_eq!(top, heap.peek());
+        assert_eq!(heap.clone().into_vec(), heap.into_sorted_vec());
+    }
+
+    #[test]
+    fn bulk_push_matches_repeated_push() {
+        // ordered by key only, so the pop order among equal keys shows the shape of the heap
+        #[derive(Debug, PartialEq)]
+        struct Keyed(u64, usize);
+        impl PartialOrd for Keyed {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                self.0.partial_cmp(&other.0)
+            }
+        }
+
+        let mut rng = Rng(0x9b05688c2b3e6c1f);
+        let mut bulk = FibonacciHeap::new();
+        let mut single = FibonacciHeap::new();
+        let mut id = 0;
+        for round in 0..50 {
+            let batch: Vec<u64> = (0..rng.below(300)).map(|_| rng.below(50)).collect();
+            bulk.bulk_push(batch.iter().enumerate().map(|(ix, &key)| Keyed(key, id + ix)));
+            for &key in &batch {
+                single.push(Keyed(key, id));
+                id += 1;
+            }
+            assert_eq!((bulk.len(), bulk.root_count(), bulk.peek()), (single.len(), single.root_count(), single.peek()));
+            let pops = if round % 10 == 9 { bulk.len() } else { rng.below(200) as usize };
+            for _ in 0..pops {
+                assert_eq!(bulk.pop(), single.pop());
+            }
+        }
+        bulk.bulk_push([]);
+        assert_eq!(bulk.collect::<Vec<_>>(), single.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn add_operators() {
+        let a = FibonacciHeap::from_vec(vec![3, 1, 4]);
+        let a = a + FibonacciHeap::new();
+        assert_eq!(a.peek(), Some(&1));
+        assert_eq!(a.len(), 3);
+
+        let b = FibonacciHeap::from_vec(vec![5, 0]);
+        let c = FibonacciHeap::from_vec(vec![2, 6]);
+        let mut merged = FibonacciHeap::new() + a + b + c;
+        assert_eq!(merged.peek(), Some(&0));
+        assert_eq!(merged.len(), 7);
+
+        merged += Vec::new();
+        assert_eq!(merged.len(), 7);
+        merged += FibonacciHeap::new();
+        merged += vec![-1, 9];
+        assert_eq!(merged.peek(), Some(&-1));
+        merged += FibonacciHeap::from_vec(vec![7]);
+        let merged = merged + vec![8];
+        assert_heap_eq!(merged, [-1, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn multiset_eq() {
+        assert_eq!(FibonacciHeap::<i32>::new(), FibonacciHeap::new());
+        assert_ne!(FibonacciHeap::from_vec(vec![1]), FibonacciHeap::new());
+        assert_ne!(FibonacciHeap::from_vec(vec![1, 1, 2]), FibonacciHeap::from_vec(vec![1, 2, 2]));
+
+        let mut rng = Rng(0x1f83d9abfb41bd6b);
+        let mut history = FibonacciHeap::new();
+        for i in 0..5000 {
+            history.push(rng.below(100));
+            if i % 3 == 0 {
+                history.pop();
+            }
+        }
+        let mut values: Vec<u64> = history.iter().copied().collect();
+        rng.shuffle(&mut values);
+        let fresh = FibonacciHeap::from_vec(values.clone());
+        assert_ne!(fresh.root_count(), history.root_count());
+        assert_eq!(fresh, history);
+
+        values.pop();
+        values.push(1000);
+        assert_ne!(FibonacciHeap::from_vec(values), history);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hash_ignores_shape() {
+        fn hash_of(heap: &FibonacciHeap<u64>) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            heap.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let mut rng = Rng(0x5be0cd19137e2179);
+        let mut values: Vec<u64> = (0..1000).map(|_| rng.below(50)).collect();
+        let reference = hash_of(&FibonacciHeap::from_vec(values.clone()));
+        for _ in 0..5 {
+            rng.shuffle(&mut values);
+            let mut pushed = FibonacciHeap::new();
+            values.iter().for_each(|&value| pushed.push(value));
+            assert_eq!(hash_of(&pushed), reference);
+            // a push/pop round trip ending in the same contents
+            pushed.push(0);
+            pushed.pop();
+            assert_eq!(hash_of(&pushed), reference);
+        }
+
+        let mut hashes = std::collections::HashSet::new();
+        hashes.insert(hash_of(&FibonacciHeap::new()));
+        hashes.insert(hash_of(&FibonacciHeap::from_vec(vec![0])));
+        hashes.insert(hash_of(&FibonacciHeap::from_vec(vec![0, 0])));
+        for i in 0..1000 {
+            hashes.insert(hash_of(&FibonacciHeap::from_vec(vec![i, i + 1, i * 7])));
+        }
+        assert_eq!(hashes.len(), 1003);
+    }
+
+    #[test]
+    fn ord_by_sorted_contents() {
+        let heap = |values: &[i32]| FibonacciHeap::from_vec(values.to_vec());
+        assert_eq!(Ord::cmp(&heap(&[]), &heap(&[])), Ordering::Equal);
+        assert!(heap(&[]) < heap(&[i32::MIN]));
+        // prefix of the sorted sequence
+        assert!(heap(&[2, 1]) < heap(&[3, 1, 2]));
+        assert!(heap(&[3, 1, 2]) > heap(&[1, 2]));
+        // first difference decides, whatever the lengths
+        assert!(heap(&[1, 3]) > heap(&[2, 1, 2, 2]));
+        assert_eq!(Ord::cmp(&heap(&[2, 1, 2]), &heap(&[2, 2, 1])), Ordering::Equal);
+
+        let mut rng = Rng(0x6c44198c4a475817);
+        let mut sets: Vec<Vec<u64>> = (0..200).map(|_| (0..rng.below(6)).map(|_| rng.below(4)).collect()).collect();
+        let mut heaps: Vec<FibonacciHeap<u64>> = sets.iter().map(|set| FibonacciHeap::from_vec(set.clone())).collect();
+        sets.iter_mut().for_each(|set| set.sort_unstable());
+        sets.sort();
+        heaps.sort();
+        let sorted: Vec<Vec<u64>> = heaps.into_iter().map(FibonacciHeap::into_sorted_vec).collect();
+        assert_eq!(sorted, sets);
+
+        let keyed: std::collections::BTreeMap<_, _> = [(heap(&[2, 1]), 'a'), (heap(&[1, 2]), 'b')].into_iter().collect();
+        assert_eq!(keyed.len(), 1);
+    }
+
+    #[test]
+    fn no_bounds_for_read_only_api() {
+        // neither PartialOrd nor Clone
+        #[derive(Debug)]
+        struct Unordered(#[allow(dead_code)] u8);
+
+        // a container that never compares anything needs no bound either
+        struct Holder<T> {
+            heap: FibonacciHeap<T>,
+        }
+        impl<T> Default for Holder<T> {
+            fn default() -> Self {
+                Self { heap: FibonacciHeap::default() }
+            }
+        }
+
+        let mut holder = Holder::<Unordered>::default();
+        assert!(holder.heap.is_empty());
+        assert_eq!(holder.heap.len(), 0);
+        assert!(holder.heap.peek().is_none());
+        assert_eq!(holder.heap.iter().count(), 0);
+        assert_eq!(holder.heap.root_count(), 0);
+        holder.heap.clear();
+        let _ = FibonacciHeap::<Unordered>::new();
+        let _ = format!("{:?}", holder.heap);
+    }
+
+    #[test]
+    fn clear() {
+        let drops = Rc::new(Cell::new(0));
+        let mut heap = FibonacciHeap::from_vec((0..10).map(|v| DropCounter::new(v, &drops)).collect());
+        heap.pop();
+        heap.clear();
+        assert_eq!(drops.get(), 10);
+        assert!(heap.is_empty() && heap.peek().is_none() && heap.root_count() == 0);
+        heap.push(DropCounter::new(3, &drops));
+        assert_eq!(heap.peek().map(|v| v.value), Some(3));
+    }
+
+    #[test]
+    fn stats_of_known_shapes() {
+        let tree = |value, children| Node { value, children };
+        let leaf = |value| Node::new(value);
+        assert_eq!(FibonacciHeap::<i32>::new().stats(), HeapStats::default());
+
+        let flat = FibonacciHeap::from_vec(vec![3, 1, 2]).stats();
+        assert_eq!(flat, HeapStats { roots: 3, nodes: 3, ..HeapStats::default() });
+
+        // 0 ─ 1 ─ 2 ─ 3 (one chain), 4 ─ [5 ─ 6 (a chain), 7, 8], 9
+        let heap = FibonacciHeap::from_roots(vec![
+            tree(0, vec![tree(1, vec![tree(2, vec![leaf(3)])])]),
+            tree(4, vec![tree(5, vec![leaf(6)]), leaf(7), leaf(8)]),
+            leaf(9),
+        ]);
+        let stats = heap.stats();
+        assert_eq!(stats, HeapStats { roots: 3, nodes: 10, max_degree: 3, max_depth: 3, average_depth: 1.1, single_child_chains: 2 });
+        assert_eq!(stats.nodes, heap.len());
+    }
+}
@@ -0,0 +1,36 @@
+// The locking primitives of the blocking wrappers, swapped for loom's under `cfg(loom)` so that the loom tests can
+// model-check every interleaving of their waits and notifications.
+
+use std::time::Duration;
+
+#[cfg(loom)]
+pub(crate) use loom::sync::{Arc, Condvar, Mutex, MutexGuard};
+#[cfg(not(loom))]
+pub(crate) use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+// Waits on `condvar` for at most `timeout`, returning the guard of `mutex` again and whether the wait timed out.
+//
+// Loom doesn't model time, so its waits never time out; here the timeout instead expires as soon as the lock is
+// released, and loom explores everything other threads can do before it is taken back.
+#[cfg(not(loom))]
+pub(crate) fn wait_timeout<'a, T>(
+    condvar: &Condvar,
+    _mutex: &'a Mutex<T>,
+    guard: MutexGuard<'a, T>,
+    timeout: Duration,
+) -> (MutexGuard<'a, T>, bool) {
+    let (guard, result) = condvar.wait_timeout(guard, timeout).unwrap();
+    (guard, result.timed_out())
+}
+
+#[cfg(loom)]
+pub(crate) fn wait_timeout<'a, T>(
+    _condvar: &Condvar,
+    mutex: &'a Mutex<T>,
+    guard: MutexGuard<'a, T>,
+    _timeout: Duration,
+) -> (MutexGuard<'a, T>, bool) {
+    drop(guard);
+    loom::thread::yield_now();
+    (mutex.lock().unwrap(), true)
+}
@@ -0,0 +1,155 @@
+use core::cmp::Reverse;
+
+use crate::FibonacciHeap;
+
+/// Running median of a multiset, kept as a max-heap of the lower half and a min-heap of the upper half.
+///
+/// The lower half always holds as many elements as the upper half, or one more,
+/// so the lower median is the top of the lower half.
+///
+/// # Example
+/// ```
+/// use fibheap::MedianTracker;
+///
+/// let mut tracker = MedianTracker::new();
+/// for latency in [12, 3, 40, 7] {
+///     tracker.insert(latency);
+/// }
+/// assert_eq!(tracker.median_pair(), Some((&7, &12)));
+/// assert!(tracker.remove(&40));
+/// assert_eq!(tracker.median(), Some(&7));
+/// ```
+#[derive(Debug)]
+pub struct MedianTracker<T> {
+    low: FibonacciHeap<Reverse<T>>,
+    high: FibonacciHeap<T>,
+}
+
+impl<T: PartialOrd> MedianTracker<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.low.len() + self.high.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.low.is_empty()
+    }
+
+    pub fn insert(&mut self, value: T) {
+        match self.low.peek() {
+            Some(Reverse(max)) if &value > max => self.high.push(value),
+            _ => self.low.push(Reverse(value)),
+        }
+        self.rebalance();
+    }
+
+    /// Removes one occurrence of `value`, returning whether it was present.
+    ///
+    /// This searches the half that may contain `value`, which is O(n) in the worst case.
+    pub fn remove(&mut self, value: &T) -> bool {
+        // Duplicates of the median may sit on both sides, so try the lower half first and fall back to the upper one.
+        let in_low = matches!(self.low.peek(), Some(Reverse(max)) if value <= max)
+            && self.low.remove_by(|Reverse(x)| value.partial_cmp(x)).is_some();
+        let removed = in_low
+            || matches!(self.high.peek(), Some(min) if value >= min)
+                && self.high.remove_by(|x| x.partial_cmp(value)).is_some();
+        if removed {
+            self.rebalance();
+        }
+        removed
+    }
+
+    /// Returns the lower median: the middle element for odd counts and the smaller of the two middle ones otherwise.
+    pub fn median(&self) -> Option<&T> {
+        self.low.peek().map(|Reverse(max)| max)
+    }
+
+    /// Returns the two middle elements, which are the same element for odd counts.
+    pub fn median_pair(&self) -> Option<(&T, &T)> {
+        let lower = self.median()?;
+        if self.low.len() == self.high.len() {
+            self.high.peek().map(|upper| (lower, upper))
+        } else {
+            Some((lower, lower))
+        }
+    }
+
+    fn rebalance(&mut self) {
+        if self.low.len() > self.high.len() + 1 {
+            let Reverse(max) = self.low.pop().unwrap();
+            self.high.push(max);
+        } else if self.high.len() > self.low.len() {
+            let min = self.high.pop().unwrap();
+            self.low.push(Reverse(min));
+        }
+    }
+}
+
+impl<T: PartialOrd> Default for MedianTracker<T> {
+    fn default() -> Self {
+        Self { low: FibonacciHeap::new(), high: FibonacciHeap::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+
+    #[test]
+    fn empty() {
+        let mut tracker = MedianTracker::<i32>::new();
+        assert!(tracker.is_empty());
+        assert_eq!(tracker.median(), None);
+        assert_eq!(tracker.median_pair(), None);
+        assert!(!tracker.remove(&1));
+    }
+
+    #[test]
+    fn duplicates_straddling_median() {
+        let mut tracker = MedianTracker::new();
+        for value in [5, 5, 5, 5, 1, 9] {
+            tracker.insert(value);
+        }
+        assert_eq!(tracker.median_pair(), Some((&5, &5)));
+        for _ in 0..4 {
+            assert!(tracker.remove(&5));
+        }
+        assert!(!tracker.remove(&5));
+        assert_eq!(tracker.median_pair(), Some((&1, &9)));
+        assert_eq!(tracker.len(), 2);
+    }
+
+    #[test]
+    fn matches_sorted_samples() {
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+        let mut tracker = MedianTracker::new();
+        let mut samples: Vec<u64> = vec![];
+        for _ in 0..5000 {
+            if samples.is_empty() || rng.below(3) > 0 {
+                let value = rng.below(20);
+                tracker.insert(value);
+                samples.push(value);
+            } else {
+                let value = rng.below(22);
+                let position = samples.iter().position(|&s| s == value);
+                assert_eq!(tracker.remove(&value), position.is_some());
+                if let Some(position) = position {
+                    samples.swap_remove(position);
+                }
+            }
+
+            samples.sort_unstable();
+            assert_eq!(tracker.len(), samples.len());
+            let expected = match samples.len() {
+                0 => None,
+                len if len % 2 == 1 => Some((&samples[len / 2], &samples[len / 2])),
+                len => Some((&samples[len / 2 - 1], &samples[len / 2])),
+            };
+            assert_eq!(tracker.median_pair(), expected);
+        }
+    }
+}
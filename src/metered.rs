@@ -0,0 +1,149 @@
+use std::time::Instant;
+
+use metrics::{counter, gauge, histogram, Counter, Gauge, Histogram};
+
+use crate::FibonacciHeap;
+
+/// [`FibonacciHeap`] reporting its activity through the [`metrics`] facade.
+///
+/// Every series carries a `heap` label set to the prefix given to [`with_metrics`](Self::with_metrics):
+/// - `fibheap_len` (gauge): current number of elements,
+/// - `fibheap_pushes_total`, `fibheap_pops_total`, `fibheap_melds_total` (counters),
+/// - `fibheap_pop_seconds` (histogram): time spent in each `pop`, consolidation included.
+///
+/// The handles are registered with the recorder installed when the heap is created.
+/// Without the `metrics` feature this type doesn't exist and [`FibonacciHeap`] carries no instrumentation.
+///
+/// # Example
+/// ```
+/// use fibheap::MeteredFibonacciHeap;
+///
+/// let mut jobs = MeteredFibonacciHeap::with_metrics("jobs");
+/// jobs.push(2);
+/// jobs.push(1);
+/// assert_eq!(jobs.pop(), Some(1));
+/// ```
+pub struct MeteredFibonacciHeap<T> {
+    heap: FibonacciHeap<T>,
+    len: Gauge,
+    pushes: Counter,
+    pops: Counter,
+    melds: Counter,
+    pop_seconds: Histogram,
+}
+
+impl<T: PartialOrd> MeteredFibonacciHeap<T> {
+    pub fn with_metrics(prefix: &str) -> Self {
+        let label = || [("heap", prefix.to_owned())];
+        let len = gauge!("fibheap_len", &label());
+        len.set(0.0);
+        Self {
+            heap: FibonacciHeap::new(),
+            len,
+            pushes: counter!("fibheap_pushes_total", &label()),
+            pops: counter!("fibheap_pops_total", &label()),
+            melds: counter!("fibheap_melds_total", &label()),
+            pop_seconds: histogram!("fibheap_pop_seconds", &label()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+
+    /// The underlying heap, for read-only access such as iteration.
+    pub fn heap(&self) -> &FibonacciHeap<T> {
+        &self.heap
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.heap.push(value);
+        self.pushes.increment(1);
+        self.len.increment(1.0);
+    }
+
+    /// Pops the minimum; popping an empty heap is not counted.
+    pub fn pop(&mut self) -> Option<T> {
+        let start = Instant::now();
+        let value = self.heap.pop()?;
+        self.pop_seconds.record(start.elapsed());
+        self.pops.increment(1);
+        self.len.decrement(1.0);
+        Some(value)
+    }
+
+    /// Melds `other` into this heap; its length gauge drops to zero.
+    pub fn append(&mut self, mut other: Self) {
+        let moved = other.heap.len();
+        self.heap.append(core::mem::take(&mut other.heap));
+        other.len.set(0.0);
+        self.melds.increment(1);
+        self.len.increment(moved as f64);
+    }
+
+    /// Stops reporting and returns the underlying heap.
+    pub fn into_inner(self) -> FibonacciHeap<T> {
+        self.heap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_heap_eq;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use metrics_util::MetricKind;
+
+    #[test]
+    fn emits_series_per_heap() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let (mut jobs, mut incoming) = metrics::with_local_recorder(&recorder, || {
+            (MeteredFibonacciHeap::with_metrics("jobs"), MeteredFibonacciHeap::with_metrics("incoming"))
+        });
+        for value in [5, 3, 8, 1] {
+            jobs.push(value);
+        }
+        incoming.push(0);
+        incoming.push(9);
+        jobs.append(incoming);
+        assert_eq!(jobs.pop(), Some(0));
+        assert_eq!(jobs.pop(), Some(1));
+        assert_eq!(MeteredFibonacciHeap::<i32>::pop(&mut MeteredFibonacciHeap::with_metrics("unused")), None);
+
+        let series: Vec<_> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| {
+                let (kind, key) = key.into_parts();
+                let heap = key.labels().find(|label| label.key() == "heap").unwrap().value().to_owned();
+                ((key.name().to_owned(), heap), (kind, value))
+            })
+            .collect();
+        let get = |name: &str, heap: &str| {
+            series.iter().find(|(id, _)| id.0 == name && id.1 == heap).map(|(_, value)| value).unwrap()
+        };
+        assert_eq!(get("fibheap_len", "jobs"), &(MetricKind::Gauge, DebugValue::Gauge(4.0.into())));
+        assert_eq!(get("fibheap_len", "incoming"), &(MetricKind::Gauge, DebugValue::Gauge(0.0.into())));
+        assert_eq!(get("fibheap_pushes_total", "jobs"), &(MetricKind::Counter, DebugValue::Counter(4)));
+        assert_eq!(get("fibheap_pushes_total", "incoming"), &(MetricKind::Counter, DebugValue::Counter(2)));
+        assert_eq!(get("fibheap_pops_total", "jobs"), &(MetricKind::Counter, DebugValue::Counter(2)));
+        assert_eq!(get("fibheap_melds_total", "jobs"), &(MetricKind::Counter, DebugValue::Counter(1)));
+        match get("fibheap_pop_seconds", "jobs") {
+            (MetricKind::Histogram, DebugValue::Histogram(samples)) => assert_eq!(samples.len(), 2),
+            other => panic!("{other:?}"),
+        }
+        // the heap created outside the local recorder reported nothing
+        assert!(!series.iter().any(|(id, _)| id.1 == "unused"));
+        assert_heap_eq!(jobs.into_inner(), [3, 5, 8, 9]);
+    }
+}
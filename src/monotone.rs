@@ -0,0 +1,204 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::FibonacciHeap;
+
+/// Unsigned integer priority usable with [`MonotoneFibonacciHeap`].
+pub trait MonotoneKey: Copy + Ord {
+    /// Smallest value, which the radix buckets start from.
+    const MIN: Self;
+    /// Width of the type in bits.
+    const BITS: u32;
+
+    /// Position of the highest bit in which `self` and `other` differ, plus one (zero when equal).
+    fn radix_distance(self, other: Self) -> usize;
+}
+
+macro_rules! impl_monotone_key {
+    ($($t:ty),*) => {$(
+        impl MonotoneKey for $t {
+            const MIN: Self = 0;
+            const BITS: u32 = <$t>::BITS;
+
+            fn radix_distance(self, other: Self) -> usize {
+                (<$t>::BITS - (self ^ other).leading_zeros()) as usize
+            }
+        }
+    )*};
+}
+
+impl_monotone_key!(u8, u16, u32, u64, u128, usize);
+
+/// Min-heap of unsigned integers specialized for monotone workloads, where every pushed value is at least the last popped one
+/// (event simulation, Dijkstra with non-negative integer weights).
+///
+/// Values are kept in radix buckets relative to the last popped value, so each one is moved at most `BITS` times
+/// over its lifetime and no comparisons between elements are needed.
+/// Pushing a value smaller than the last popped one is not an error: such values go to a regular
+/// [`FibonacciHeap`] that `pop` and `top` consult as well, so ordering stays exact and only those values lose the speedup.
+///
+/// Priorities with payloads can be packed into one integer, e.g. `(distance << 32) | node`.
+///
+/// # Example
+/// ```
+/// use fibheap::MonotoneFibonacciHeap;
+///
+/// let mut heap = MonotoneFibonacciHeap::new();
+/// heap.push(5u32);
+/// heap.push(3);
+/// assert_eq!(heap.pop(), Some(3));
+/// heap.push(4);
+/// heap.push(1); // below the last popped value: handled by the fallback heap
+/// assert_eq!(heap.pop(), Some(1));
+/// assert_eq!(heap.pop(), Some(4));
+/// assert_eq!(heap.pop(), Some(5));
+/// ```
+#[derive(Debug)]
+pub struct MonotoneFibonacciHeap<T> {
+    // Bucket `i` holds values whose highest bit differing from `last` is bit `i - 1`; bucket 0 holds copies of `last`.
+    buckets: Vec<Vec<T>>,
+    last: T,
+    radix_len: usize,
+    // cached minimum of the buckets, so that `top` stays O(1)
+    radix_min: Option<T>,
+    fallback: FibonacciHeap<T>,
+}
+
+impl<T: MonotoneKey> MonotoneFibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.radix_len + self.fallback.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        match (self.radix_min.as_ref(), self.fallback.peek()) {
+            (Some(radix), Some(fallback)) => Some(radix.min(fallback)),
+            (radix, fallback) => radix.or(fallback),
+        }
+    }
+
+    #[deprecated(note = "renamed to `peek` for parity with `BinaryHeap`")]
+    pub fn top(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    pub fn push(&mut self, value: T) {
+        if value < self.last {
+            self.fallback.push(value);
+            return;
+        }
+        self.buckets[value.radix_distance(self.last)].push(value);
+        self.radix_len += 1;
+        if self.radix_min.is_none_or(|min| value < min) {
+            self.radix_min = Some(value);
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let radix_min = match (self.radix_min, self.fallback.peek()) {
+            (Some(radix), Some(fallback)) if radix <= *fallback => radix,
+            (Some(radix), None) => radix,
+            _ => return self.fallback.pop(),
+        };
+        if self.buckets[0].is_empty() {
+            // Re-base on the minimum; the bucket holding it then spreads over strictly lower buckets.
+            let ix = self.buckets.iter().position(|bucket| !bucket.is_empty()).unwrap();
+            self.last = radix_min;
+            let bucket = core::mem::take(&mut self.buckets[ix]);
+            for value in bucket.iter().copied() {
+                self.buckets[value.radix_distance(self.last)].push(value);
+            }
+            // Hand the allocation back so that buckets don't have to regrow.
+            self.buckets[ix] = bucket;
+            self.buckets[ix].clear();
+        }
+        let value = self.buckets[0].pop();
+        self.radix_len -= 1;
+        self.radix_min = if self.buckets[0].is_empty() {
+            self.buckets.iter().find(|bucket| !bucket.is_empty()).and_then(|bucket| bucket.iter().copied().min())
+        } else {
+            Some(self.last)
+        };
+        value
+    }
+}
+
+impl<T: MonotoneKey> Default for MonotoneFibonacciHeap<T> {
+    fn default() -> Self {
+        Self {
+            buckets: (0..=T::BITS).map(|_| vec![]).collect(),
+            last: T::MIN,
+            radix_len: 0,
+            radix_min: None,
+            fallback: FibonacciHeap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    #[test]
+    fn empty() {
+        let mut heap = MonotoneFibonacciHeap::<u8>::new();
+        assert!(heap.is_empty());
+        assert_eq!(heap.peek(), None);
+        assert_eq!(heap.pop(), None);
+    }
+
+    #[test]
+    fn extremes() {
+        let mut heap = MonotoneFibonacciHeap::new();
+        for value in [u64::MAX, 0, u64::MAX, 1 << 63, 0] {
+            heap.push(value);
+        }
+        let popped: Vec<_> = std::iter::from_fn(|| heap.pop()).collect();
+        assert_eq!(popped, [0, 0, 1 << 63, u64::MAX, u64::MAX]);
+    }
+
+    fn check_against_reference(rng: &mut Rng, monotone: bool) {
+        let mut heap = MonotoneFibonacciHeap::new();
+        let mut reference = BinaryHeap::new();
+        let mut last = 0;
+        for _ in 0..20_000 {
+            if rng.below(3) > 0 {
+                let value = if monotone { last + rng.below(1000) } else { rng.below(1 << 20) } as u32;
+                heap.push(value);
+                reference.push(Reverse(value));
+            } else {
+                let expected = reference.pop().map(|Reverse(v)| v);
+                assert_eq!(heap.pop(), expected);
+                if let Some(popped) = expected {
+                    last = popped as u64;
+                }
+            }
+            assert_eq!(heap.len(), reference.len());
+            assert_eq!(heap.peek(), reference.peek().map(|Reverse(v)| v));
+        }
+        while let Some(Reverse(expected)) = reference.pop() {
+            assert_eq!(heap.pop(), Some(expected));
+        }
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn monotone_workload() {
+        check_against_reference(&mut Rng(0x1d8e4e27c47d124f), true);
+    }
+
+    #[test]
+    fn violations_degrade_gracefully() {
+        check_against_reference(&mut Rng(0x7a3c9f1e5b2d8064), false);
+    }
+}
@@ -0,0 +1,230 @@
+use std::cell::Cell;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::FibonacciHeap;
+
+thread_local! {
+    static RNG: Cell<u64> = Cell::new(RandomState::new().build_hasher().finish() | 1);
+}
+
+// xorshift64; shard choice only needs to be cheap and roughly uniform.
+fn xorshift(mut x: u64) -> u64 {
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    x
+}
+
+/// Relaxed concurrent priority queue spreading elements over several independently locked heaps.
+///
+/// `push` goes to the shards in round-robin order, and `pop` samples two random shards and pops the smaller of their tops.
+/// Contention therefore drops with the number of shards, at the cost of strictness:
+/// a popped element is near-minimal rather than minimal, typically ranking within a small multiple of the shard count
+/// among the elements present. A pop only returns `None` when every shard was seen empty.
+/// The queue is `Send` and `Sync` when `T` is `Send`.
+///
+/// # Example
+/// ```
+/// use fibheap::MultiQueue;
+///
+/// let queue = MultiQueue::new(4);
+/// for i in 0..100 {
+///     queue.push(i);
+/// }
+/// let mut popped: Vec<_> = std::iter::from_fn(|| queue.pop()).collect();
+/// popped.sort();
+/// assert_eq!(popped, (0..100).collect::<Vec<_>>());
+/// ```
+pub struct MultiQueue<T> {
+    shards: Box<[Mutex<FibonacciHeap<T>>]>,
+    next_shard: AtomicUsize,
+    // generator shared by every thread, for queues built by `with_seed`; the others use one per thread
+    rng: Option<AtomicU64>,
+}
+
+impl<T: PartialOrd> MultiQueue<T> {
+    /// Creates a queue with `shards` internal heaps; a few per thread is the usual choice.
+    ///
+    /// # Panics
+    /// Panics if `shards` is zero.
+    pub fn new(shards: usize) -> Self {
+        assert!(shards > 0, "MultiQueue needs at least one shard");
+        let shards = (0..shards).map(|_| Mutex::new(FibonacciHeap::new())).collect();
+        Self { shards, next_shard: AtomicUsize::new(0), rng: None }
+    }
+
+    /// Like [`new`](Self::new), but samples shards from a generator seeded with `seed`, so that the same sequence of
+    /// operations from a single thread pops in the same order every time.
+    ///
+    /// The generator is shared by every thread, which costs an atomic update of one word per sample.
+    ///
+    /// # Panics
+    /// Panics if `shards` is zero.
+    pub fn with_seed(shards: usize, seed: u64) -> Self {
+        Self { rng: Some(AtomicU64::new(seed | 1)), ..Self::new(shards) }
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Total number of elements, which may already be stale when other threads are operating on the queue.
+    pub fn len(&self) -> usize {
+        (0..self.shards.len()).map(|ix| self.lock(ix).len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..self.shards.len()).all(|ix| self.lock(ix).is_empty())
+    }
+
+    pub fn push(&self, value: T) {
+        let ix = self.next_shard.fetch_add(1, Ordering::Relaxed) % self.shards.len();
+        self.lock(ix).push(value);
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let n = self.shards.len();
+        if n > 1 {
+            let a = self.random_below(n);
+            let b = (a + 1 + self.random_below(n - 1)) % n;
+            // Lock in index order so that concurrent pops can't deadlock.
+            let (first, second) = (a.min(b), a.max(b));
+            let mut first = self.lock(first);
+            let mut second = self.lock(second);
+            let better = match (first.peek(), second.peek()) {
+                (Some(x), Some(y)) if y < x => &mut second,
+                (None, Some(_)) => &mut second,
+                _ => &mut first,
+            };
+            if let Some(value) = better.pop() {
+                return Some(value);
+            }
+        }
+        // Both samples were empty; fall back to a full scan so that elements can't be stranded.
+        let start = self.random_below(n);
+        (0..n).find_map(|offset| self.lock((start + offset) % n).pop())
+    }
+
+    fn random_below(&self, n: usize) -> usize {
+        let x = match &self.rng {
+            Some(rng) => {
+                let previous = rng.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| Some(xorshift(x)));
+                xorshift(previous.unwrap())
+            }
+            None => RNG.with(|rng| {
+                let x = xorshift(rng.get());
+                rng.set(x);
+                x
+            }),
+        };
+        (x % n as u64) as usize
+    }
+
+    fn lock(&self, ix: usize) -> MutexGuard<'_, FibonacciHeap<T>> {
+        self.shards[ix].lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn single_shard_is_strict() {
+        let queue = MultiQueue::new(1);
+        for value in [5, 3, 8, 1] {
+            queue.push(value);
+        }
+        assert_eq!(queue.len(), 4);
+        assert_eq!(std::iter::from_fn(|| queue.pop()).collect::<Vec<_>>(), [1, 3, 5, 8]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn same_seed_same_order() {
+        let order = |seed| {
+            let queue = MultiQueue::with_seed(8, seed);
+            (0..1000).for_each(|value| queue.push(value));
+            std::iter::from_fn(|| queue.pop()).collect::<Vec<_>>()
+        };
+        assert_eq!(order(7), order(7));
+        assert_ne!(order(7), order(8));
+    }
+
+    #[test]
+    fn rank_error_is_bounded() {
+        const SHARDS: usize = 8;
+        const N: usize = 20000;
+        let mut values: Vec<usize> = (0..N).collect();
+        Rng(0x5deece66d).shuffle(&mut values);
+        let queue = MultiQueue::with_seed(SHARDS, 0x9e3779b97f4a7c15);
+        for value in values {
+            queue.push(value);
+        }
+
+        // Fenwick tree over the values still present, to count how many smaller ones each pop skipped.
+        let mut tree = vec![0usize; N + 1];
+        let add = |tree: &mut Vec<usize>, mut i: usize, delta: isize| {
+            i += 1;
+            while i <= N {
+                tree[i] = tree[i].wrapping_add_signed(delta);
+                i += i & i.wrapping_neg();
+            }
+        };
+        let smaller = |tree: &Vec<usize>, mut i: usize| {
+            let mut sum = 0;
+            while i > 0 {
+                sum += tree[i];
+                i -= i & i.wrapping_neg();
+            }
+            sum
+        };
+        for value in 0..N {
+            add(&mut tree, value, 1);
+        }
+
+        let mut total_rank = 0;
+        let mut max_rank = 0;
+        while let Some(value) = queue.pop() {
+            let rank = smaller(&tree, value);
+            total_rank += rank;
+            max_rank = max_rank.max(rank);
+            add(&mut tree, value, -1);
+        }
+        let mean_rank = total_rank as f64 / N as f64;
+        assert!(mean_rank < 2.0 * SHARDS as f64, "mean rank error {mean_rank}");
+        assert!(max_rank < 20 * SHARDS, "max rank error {max_rank}");
+    }
+
+    #[test]
+    fn concurrent_exactly_once() {
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 5000;
+        let queue = Arc::new(MultiQueue::new(2 * THREADS));
+        let workers: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let queue = Arc::clone(&queue);
+                thread::spawn(move || {
+                    let mut popped = vec![];
+                    for i in 0..PER_THREAD {
+                        queue.push(i * THREADS + t);
+                        if i % 2 == 1 {
+                            popped.extend(queue.pop());
+                        }
+                    }
+                    popped
+                })
+            })
+            .collect();
+        let mut popped: Vec<_> = workers.into_iter().flat_map(|w| w.join().unwrap()).collect();
+        popped.extend(std::iter::from_fn(|| queue.pop()));
+        popped.sort_unstable();
+        assert_eq!(popped, (0..THREADS * PER_THREAD).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,244 @@
+use alloc::boxed::Box;
+use core::fmt;
+
+use crate::FibonacciHeap;
+
+/// [`FibonacciHeap`] calling a hook whenever the value returned by [`peek`](Self::peek) changes, e.g. to
+/// recompute a sleep deadline when an earlier one arrives or the current one is popped.
+///
+/// The hook gets the new minimum, or `None` once the heap is empty. It runs once at the end of each operation
+/// whose result has a different minimum than before, however the trees were reshuffled in between, and not at all
+/// when the minimum is equal to the old one, e.g. after popping one of two equal minimums.
+/// Telling whether it changed costs one or two comparisons per operation.
+/// The hook doesn't have to be `Send`, so the heap is neither `Send` nor `Sync`.
+///
+/// # Example
+/// ```
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+/// use fibheap::ObservedFibonacciHeap;
+///
+/// let seen = Rc::new(RefCell::new(vec![]));
+/// let mut heap = ObservedFibonacciHeap::new();
+/// let log = Rc::clone(&seen);
+/// heap.set_top_changed_hook(move |top: Option<&u32>| log.borrow_mut().push(top.copied()));
+/// heap.push(5);
+/// heap.push(7);
+/// heap.push(3);
+/// heap.pop();
+/// heap.pop();
+/// heap.pop();
+/// assert_eq!(*seen.borrow(), [Some(5), Some(3), Some(5), Some(7), None]);
+/// ```
+pub struct ObservedFibonacciHeap<T> {
+    heap: FibonacciHeap<T>,
+    hook: Option<Hook<T>>,
+}
+
+type Hook<T> = Box<dyn FnMut(Option<&T>)>;
+
+impl<T: PartialOrd> ObservedFibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the hook called with the new minimum after an operation changes it, replacing any earlier one.
+    pub fn set_top_changed_hook(&mut self, hook: impl FnMut(Option<&T>) + 'static) {
+        self.hook = Some(Box::new(hook));
+    }
+
+    /// Removes the hook, returning whether there was one.
+    pub fn clear_top_changed_hook(&mut self) -> bool {
+        self.hook.take().is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+
+    /// The underlying heap, for read-only access such as iteration.
+    pub fn heap(&self) -> &FibonacciHeap<T> {
+        &self.heap
+    }
+
+    /// The underlying heap, without the hook.
+    pub fn into_inner(self) -> FibonacciHeap<T> {
+        self.heap
+    }
+
+    pub fn push(&mut self, value: T) {
+        let changed = self.smaller_than_top(&value);
+        self.heap.push(value);
+        self.notify(changed);
+    }
+
+    /// Pushes every item of `items` like [`FibonacciHeap::bulk_push`], with at most one call to the hook.
+    pub fn bulk_push(&mut self, items: impl IntoIterator<Item = T>) {
+        let mut changed = false;
+        for value in items {
+            changed |= self.smaller_than_top(&value);
+            self.heap.push(value);
+        }
+        self.notify(changed);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.heap.pop()?;
+        let changed = self.heap.peek() != Some(&value);
+        self.notify(changed);
+        Some(value)
+    }
+
+    /// See [`FibonacciHeap::push_pop`]; the minimum changes only if `value` is pushed and the old one popped.
+    pub fn push_pop(&mut self, value: T) -> T {
+        let pushed = self.heap.peek().is_some_and(|top| *top < value);
+        let value = self.heap.push_pop(value);
+        let changed = pushed && self.heap.peek() != Some(&value);
+        self.notify(changed);
+        value
+    }
+
+    pub fn replace_top(&mut self, value: T) -> Option<T> {
+        let old = self.heap.replace_top(value);
+        let changed = old.as_ref() != self.heap.peek();
+        self.notify(changed);
+        old
+    }
+
+    pub fn append(&mut self, other: FibonacciHeap<T>) {
+        let changed = other.peek().is_some_and(|top| self.smaller_than_top(top));
+        self.heap.append(other);
+        self.notify(changed);
+    }
+
+    pub fn clear(&mut self) {
+        let changed = !self.heap.is_empty();
+        self.heap.clear();
+        self.notify(changed);
+    }
+
+    /// See [`FibonacciHeap::consolidate`]; the minimum doesn't change.
+    pub fn consolidate(&mut self) {
+        self.heap.consolidate();
+    }
+
+    // Whether pushing `value` makes it the new minimum.
+    fn smaller_than_top(&self, value: &T) -> bool {
+        self.heap.peek().is_none_or(|top| value < top)
+    }
+
+    fn notify(&mut self, changed: bool) {
+        if let (true, Some(hook)) = (changed, &mut self.hook) {
+            hook(self.heap.peek());
+        }
+    }
+}
+
+impl<T> Default for ObservedFibonacciHeap<T> {
+    fn default() -> Self {
+        Self { heap: FibonacciHeap::new(), hook: None }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ObservedFibonacciHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObservedFibonacciHeap").field("heap", &self.heap).field("hook", &self.hook.is_some()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // the minimums the hook was called with
+    type Calls = Rc<RefCell<Vec<Option<i32>>>>;
+
+    fn recorded() -> (ObservedFibonacciHeap<i32>, Calls) {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let mut heap = ObservedFibonacciHeap::new();
+        let log = Rc::clone(&calls);
+        heap.set_top_changed_hook(move |top| log.borrow_mut().push(top.copied()));
+        (heap, calls)
+    }
+
+    #[test]
+    fn scripted_workload() {
+        let (mut heap, calls) = recorded();
+        let expect = |expected: &[Option<i32>]| assert_eq!(calls.borrow_mut().drain(..).collect::<Vec<_>>(), expected);
+
+        heap.push(10);
+        expect(&[Some(10)]);
+        heap.push(20);
+        heap.push(10);
+        expect(&[]);
+        heap.bulk_push([15, 5, 3, 30]);
+        expect(&[Some(3)]);
+        heap.consolidate();
+        heap.append(FibonacciHeap::from_vec(vec![4, 8]));
+        expect(&[]);
+        heap.append(FibonacciHeap::from_vec(vec![1, 2]));
+        expect(&[Some(1)]);
+        assert_eq!(heap.pop(), Some(1));
+        expect(&[Some(2)]);
+        // smaller than the minimum: handed back untouched
+        assert_eq!(heap.push_pop(0), 0);
+        expect(&[]);
+        assert_eq!(heap.push_pop(6), 2);
+        expect(&[Some(3)]);
+        assert_eq!(heap.replace_top(3), Some(3));
+        expect(&[]);
+        assert_eq!(heap.replace_top(50), Some(3));
+        expect(&[Some(4)]);
+        heap.clear();
+        expect(&[None]);
+        heap.clear();
+        assert_eq!(heap.pop(), None);
+        expect(&[]);
+
+        // equal minimums: only the last one popped changes anything
+        heap.bulk_push([7, 7, 9]);
+        expect(&[Some(7)]);
+        heap.pop();
+        expect(&[]);
+        heap.pop();
+        expect(&[Some(9)]);
+        heap.pop();
+        expect(&[None]);
+    }
+
+    #[test]
+    fn fires_exactly_when_the_minimum_changes() {
+        let (mut heap, calls) = recorded();
+        let mut rng = Rng(0xa54ff53a5f1d36f1);
+        for _ in 0..5000 {
+            let before = heap.peek().copied();
+            let value = rng.below(100) as i32;
+            match rng.below(6) {
+                0 | 1 => heap.push(value),
+                2 => drop(heap.pop()),
+                3 => drop(heap.push_pop(value)),
+                4 => drop(heap.replace_top(value)),
+                _ => heap.append(FibonacciHeap::from_vec(vec![value, value + 7])),
+            }
+            let after = heap.peek().copied();
+            let calls: Vec<_> = calls.borrow_mut().drain(..).collect();
+            assert_eq!(calls, if before == after { vec![] } else { vec![after] });
+        }
+        assert!(heap.clear_top_changed_hook());
+        heap.clear();
+        assert!(calls.borrow().is_empty());
+    }
+}
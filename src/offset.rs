@@ -0,0 +1,308 @@
+use alloc::rc::Rc;
+use alloc::vec;
+use core::cell::RefCell;
+use core::cmp::Ordering;
+use core::fmt;
+
+use crate::FibonacciHeap;
+
+/// Value that can be shifted by a delta of its own type, for [`OffsetFibonacciHeap`].
+///
+/// `apply` must behave like addition: shifting two values by the same delta keeps their order, and shifting by `a`
+/// then by `b` is the same as shifting by `a` shifted by `b`, in either order.
+pub trait Offsetable: Clone + PartialOrd {
+    /// Shifts `self` by `delta`.
+    fn apply(&mut self, delta: &Self);
+}
+
+macro_rules! impl_offsetable {
+    ($($t:ty),*) => {$(
+        impl Offsetable for $t {
+            fn apply(&mut self, delta: &Self) {
+                *self += *delta;
+            }
+        }
+    )*};
+}
+
+impl_offsetable!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+/// Min-heap whose elements can all be shifted at once in O(1), as graph algorithms like Chu-Liu/Edmonds need when
+/// they adjust every edge weight into a contracted vertex.
+///
+/// [`add_all`](Self::add_all) shifts every element by a delta, and [`append_with_offset`](Self::append_with_offset)
+/// melds in another heap with all of its elements shifted. Neither touches the elements: each element belongs to a
+/// group carrying a pending offset, and groups hang off each other like the sets of a union-find structure, so
+/// shifting a heap only changes the offset of its root group. An element's value is worked out when it is compared
+/// or returned, by adding up the offsets on the way to the root; that walk shortcuts the path it takes, which keeps
+/// it close to O(1) amortized. Comparing two elements of the same group skips the offsets altogether.
+///
+/// [`pop`](Self::pop) and [`top`](Self::top) return values with every offset applied. Since values are computed,
+/// `top` returns a copy rather than a reference. Groups are shared through `Rc`, so the heap is neither `Send` nor
+/// `Sync`.
+///
+/// # Example
+/// ```
+/// use fibheap::OffsetFibonacciHeap;
+///
+/// let mut incoming = OffsetFibonacciHeap::new();
+/// incoming.push(5);
+/// incoming.push(8);
+/// incoming.add_all(-3);
+/// let mut other = OffsetFibonacciHeap::new();
+/// other.push(1);
+/// incoming.append_with_offset(other, 10);
+/// assert_eq!(incoming.peek_value(), Some(2));
+/// assert_eq!(incoming.pop(), Some(2));
+/// assert_eq!(incoming.pop(), Some(5));
+/// assert_eq!(incoming.pop(), Some(11));
+/// ```
+pub struct OffsetFibonacciHeap<T> {
+    heap: FibonacciHeap<Entry<T>>,
+    // the group at the top of every element's path; new elements join it directly while its offset is still zero
+    root: GroupRef<T>,
+}
+
+type GroupRef<T> = Rc<RefCell<Group<T>>>;
+
+// Elements of a group are shifted by its offset and those of all its ancestors; `None` stands for zero.
+// Only the offset of a root changes, so once a group has a parent its offset is final.
+struct Group<T> {
+    offset: Option<T>,
+    parent: Option<GroupRef<T>>,
+}
+
+struct Entry<T> {
+    // the value before the offsets of `group` and its ancestors
+    value: T,
+    group: GroupRef<T>,
+}
+
+fn new_group<T>() -> GroupRef<T> {
+    Rc::new(RefCell::new(Group { offset: None, parent: None }))
+}
+
+fn add<T: Offsetable>(sum: &mut Option<T>, delta: &Option<T>) {
+    match (sum.as_mut(), delta) {
+        (Some(sum), Some(delta)) => sum.apply(delta),
+        (None, Some(delta)) => *sum = Some(delta.clone()),
+        (_, None) => {}
+    }
+}
+
+// Sum of the offsets from `group` up to its root. Longer paths are compressed: every group on the way is pointed at
+// the root directly, taking over the offsets of the groups it skips, which is sound since those are final.
+fn total_offset<T: Offsetable>(group: &GroupRef<T>) -> Option<T> {
+    let borrowed = group.borrow();
+    match &borrowed.parent {
+        None => return borrowed.offset.clone(),
+        Some(parent) if parent.borrow().parent.is_none() => {
+            let mut total = borrowed.offset.clone();
+            add(&mut total, &parent.borrow().offset);
+            return total;
+        }
+        Some(_) => drop(borrowed),
+    }
+    let mut chain = vec![Rc::clone(group)];
+    loop {
+        let parent = chain.last().unwrap().borrow().parent.clone();
+        match parent {
+            Some(parent) => chain.push(parent),
+            None => break,
+        }
+    }
+    let root = chain.pop().unwrap();
+    // offsets of the groups between the current one and the root
+    let mut above = None;
+    for group in chain.iter().rev() {
+        let mut group = group.borrow_mut();
+        let own = group.offset.clone();
+        add(&mut group.offset, &above);
+        group.parent = Some(Rc::clone(&root));
+        add(&mut above, &own);
+    }
+    add(&mut above, &root.borrow().offset);
+    above
+}
+
+impl<T: Offsetable> Entry<T> {
+    fn value(&self) -> T {
+        let mut value = self.value.clone();
+        if let Some(offset) = total_offset(&self.group) {
+            value.apply(&offset);
+        }
+        value
+    }
+}
+
+impl<T: Offsetable> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl<T: Offsetable> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if Rc::ptr_eq(&self.group, &other.group) {
+            self.value.partial_cmp(&other.value)
+        } else {
+            self.value().partial_cmp(&other.value())
+        }
+    }
+}
+
+impl<T: Offsetable> OffsetFibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Returns the smallest element with every offset applied, or `None` if the heap is empty. Unlike `peek` on the
+    /// other heaps it returns a value, as the offsets are only applied on the way out.
+    pub fn peek_value(&self) -> Option<T> {
+        self.heap.peek().map(Entry::value)
+    }
+
+    pub fn push(&mut self, value: T) {
+        let group = Rc::clone(self.clean_root());
+        self.heap.push(Entry { value, group });
+    }
+
+    /// Removes and returns the smallest element, with every offset applied.
+    pub fn pop(&mut self) -> Option<T> {
+        self.heap.pop().map(|entry| entry.value())
+    }
+
+    /// Shifts every element by `delta`, in O(1).
+    pub fn add_all(&mut self, delta: T) {
+        add(&mut self.root.borrow_mut().offset, &Some(delta));
+    }
+
+    /// Moves the elements of `other` into `self`, shifting each of them by `delta`, in O(1).
+    pub fn append_with_offset(&mut self, other: Self, delta: T) {
+        if other.is_empty() {
+            return;
+        }
+        let root = Rc::clone(self.clean_root());
+        let mut other_root = other.root.borrow_mut();
+        add(&mut other_root.offset, &Some(delta));
+        other_root.parent = Some(root);
+        drop(other_root);
+        self.heap.append(other.heap);
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.root = new_group();
+    }
+
+    // The root, first replaced by a new one with a zero offset if its own is pending, so that the elements joining it
+    // aren't shifted by the deltas that came before them.
+    fn clean_root(&mut self) -> &GroupRef<T> {
+        if self.root.borrow().offset.is_some() {
+            let root = new_group();
+            self.root.borrow_mut().parent = Some(Rc::clone(&root));
+            self.root = root;
+        }
+        &self.root
+    }
+}
+
+impl<T> Default for OffsetFibonacciHeap<T> {
+    fn default() -> Self {
+        Self { heap: FibonacciHeap::new(), root: new_group() }
+    }
+}
+
+impl<T: Offsetable + fmt::Debug> fmt::Debug for OffsetFibonacciHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OffsetFibonacciHeap")
+            .field("len", &self.len())
+            .field("top", &self.peek_value())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Releases the ancestors that no other group refers to one at a time, so that dropping a long path of groups can't
+/// overflow the call stack.
+impl<T> Drop for Group<T> {
+    fn drop(&mut self) {
+        let mut parent = self.parent.take();
+        while let Some(group) = parent {
+            match Rc::try_unwrap(group) {
+                Ok(mut group) => parent = group.get_mut().parent.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn long_paths() {
+        // every push after a shift starts a new root, so this builds a path of 100k groups
+        let mut heap = OffsetFibonacciHeap::new();
+        for i in 0..100_000i64 {
+            heap.push(i);
+            heap.add_all(-1);
+        }
+        // `i` was shifted by each of the 100_000 - i shifts after it
+        assert_eq!(heap.peek_value(), Some(-100_000));
+        for i in 0..1000 {
+            assert_eq!(heap.pop(), Some(2 * i - 100_000));
+        }
+        let mut other = OffsetFibonacciHeap::new();
+        for i in 0..100_000i64 {
+            other.add_all(1);
+            other.push(i);
+        }
+        drop(other);
+        heap.clear();
+        assert_eq!(heap.pop(), None);
+    }
+
+    #[test]
+    fn nested_appends() {
+        let mut rng = Rng(0x9b05688c2b3e6c1f);
+        let mut heap = OffsetFibonacciHeap::new();
+        let mut eager: Vec<i64> = vec![];
+        for _ in 0..50 {
+            let mut other = OffsetFibonacciHeap::new();
+            let mut other_eager = vec![];
+            for _ in 0..rng.below(20) {
+                let value = rng.below(100) as i64;
+                other.push(value);
+                other_eager.push(value);
+                let delta = rng.below(9) as i64 - 4;
+                other.add_all(delta);
+                other_eager.iter_mut().for_each(|x| *x += delta);
+            }
+            let delta = rng.below(21) as i64 - 10;
+            heap.append_with_offset(other, delta);
+            eager.extend(other_eager.iter().map(|x| x + delta));
+            heap.add_all(3);
+            eager.iter_mut().for_each(|x| *x += 3);
+            if rng.below(2) == 0 {
+                eager.sort_unstable();
+                let popped = heap.pop();
+                assert_eq!(popped, (!eager.is_empty()).then(|| eager.remove(0)));
+            }
+            assert_eq!(heap.len(), eager.len());
+        }
+        eager.sort_unstable();
+        let drained: Vec<_> = core::iter::from_fn(|| heap.pop()).collect();
+        assert_eq!(drained, eager);
+    }
+}
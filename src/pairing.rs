@@ -0,0 +1,252 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem;
+
+use crate::{Iter, IterSorted, Node};
+
+/// Pairing heap with the same interface as [`FibonacciHeap`](crate::FibonacciHeap), so switching between the two
+/// is a one-line change.
+///
+/// The heap is a single tree: `push` and `append` link the new tree under the smaller root in O(1),
+/// and `pop` melds the root's children in two passes (neighbouring pairs from the newest, then the pairs from the oldest).
+/// `pop` costs O(log n) amortized like a Fibonacci heap, with less bookkeeping and usually smaller constant factors.
+/// See `cargo bench --bench compare` to pick one for a given workload.
+///
+/// # Example
+/// ```
+/// use fibheap::PairingHeap;
+///
+/// let mut heap = PairingHeap::from_vec(vec![5, 3, 8]);
+/// heap.push(1);
+/// heap.append(PairingHeap::from_vec(vec![4]));
+/// assert_eq!(heap.peek(), Some(&1));
+/// assert_eq!(heap.pop(), Some(1));
+/// assert_eq!(heap.into_sorted_vec(), [3, 4, 5, 8]);
+/// ```
+pub struct PairingHeap<T> {
+    root: Option<Node<T>>,
+    len: usize,
+    // scratch space for the first pass of `pop`, kept so that popping doesn't allocate each time
+    pairs: Vec<Node<T>>,
+}
+
+impl<T> PairingHeap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over references to the elements in unspecified order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self.root.as_slice(), self.len)
+    }
+
+    /// Returns the smallest element, or `None` if the heap is empty.
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(Node::value)
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl<T: PartialOrd> PairingHeap<T> {
+    /// Builds a heap from `vec` in O(n): the minimum becomes the root and every other element its child.
+    pub fn from_vec(vec: Vec<T>) -> Self {
+        if vec.is_empty() {
+            return Self::new();
+        }
+        let len = vec.len();
+        let mut children: Vec<Node<T>> = vec.into_iter().map(Node::new).collect();
+        let mut top = 0;
+        for ix in 1..children.len() {
+            if children[ix].value() < children[top].value() {
+                top = ix;
+            }
+        }
+        let mut root = children.swap_remove(top);
+        root.children = children;
+        Self { root: Some(root), len, pairs: vec![] }
+    }
+
+    /// Consumes the heap and returns its elements in ascending order.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut vec = Vec::with_capacity(self.len);
+        while let Some(value) = self.pop() {
+            vec.push(value);
+        }
+        vec
+    }
+
+    /// Same as [`into_sorted_vec`](Self::into_sorted_vec), as for [`FibonacciHeap::into_vec`](crate::FibonacciHeap::into_vec).
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_sorted_vec()
+    }
+
+    /// Iterates over references to the elements in ascending order without consuming the heap.
+    pub fn iter_sorted(&self) -> IterSorted<'_, T> {
+        IterSorted::new(self.root.as_slice())
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.meld(Node::new(value));
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let Node { value, mut children } = self.root.take()?;
+        self.len -= 1;
+        // First pass: link neighbouring children in pairs, starting from the most recently linked ones at the back.
+        while let Some(last) = children.pop() {
+            self.pairs.push(match children.pop() {
+                Some(previous) => link(previous, last),
+                None => last,
+            });
+        }
+        // Second pass: meld the pairs into one tree, starting from the oldest one.
+        let mut melded = self.pairs.pop();
+        while let Some(pair) = self.pairs.pop() {
+            melded = melded.map(|tree| link(pair, tree));
+        }
+        self.root = melded;
+        Some(value)
+    }
+
+    /// Moves every element of `other` into `self` in O(1).
+    pub fn append(&mut self, mut other: Self) {
+        if let Some(root) = other.root.take() {
+            self.meld(root);
+            self.len += mem::take(&mut other.len);
+        }
+    }
+
+    fn meld(&mut self, tree: Node<T>) {
+        self.root = Some(match self.root.take() {
+            Some(root) => link(root, tree),
+            None => tree,
+        });
+    }
+}
+
+// Links two trees under the smaller root, preferring `a` on ties.
+fn link<T: PartialOrd>(mut a: Node<T>, mut b: Node<T>) -> Node<T> {
+    if b.value() < a.value() {
+        mem::swap(&mut a, &mut b);
+    }
+    a.children.push(b);
+    a
+}
+
+impl<T: PartialOrd> Iterator for PairingHeap<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pop()
+    }
+}
+
+impl<T: Clone> Clone for PairingHeap<T> {
+    fn clone(&self) -> Self {
+        Self { root: self.root.clone(), len: self.len, pairs: vec![] }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PairingHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PairingHeap").field("len", &self.len).field("top", &self.peek()).finish()
+    }
+}
+
+// Pushing in descending order builds a path as long as the heap, so trees are torn down without recursion.
+impl<T> Drop for PairingHeap<T> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Node<T>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.append(&mut node.children);
+        }
+    }
+}
+
+impl<T> Default for PairingHeap<T> {
+    fn default() -> Self {
+        Self { root: None, len: 0, pairs: vec![] }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{DropCounter, Rng};
+    use crate::FibonacciHeap;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn matches_fibonacci_heap() {
+        let mut rng = Rng(0x428a2f98d728ae22);
+        let initial: Vec<u64> = (0..200).map(|_| rng.below(1000)).collect();
+        let mut pairing = PairingHeap::from_vec(initial.clone());
+        let mut fib = FibonacciHeap::from_vec(initial);
+        for _ in 0..20_000 {
+            match rng.below(8) {
+                0..=3 => {
+                    let value = rng.below(1000);
+                    pairing.push(value);
+                    fib.push(value);
+                }
+                4 => {
+                    let values: Vec<u64> = (0..rng.below(20)).map(|_| rng.below(1000)).collect();
+                    pairing.append(PairingHeap::from_vec(values.clone()));
+                    fib.append(FibonacciHeap::from_vec(values));
+                }
+                _ => assert_eq!(pairing.pop(), fib.pop()),
+            }
+            assert_eq!((pairing.len(), pairing.peek()), (fib.len(), fib.peek()));
+        }
+        let mut all: Vec<_> = pairing.iter().copied().collect();
+        all.sort_unstable();
+        assert_eq!(pairing.iter_sorted().copied().collect::<Vec<_>>(), all);
+        assert_eq!(pairing.clone().into_vec(), all);
+        assert_eq!(pairing.collect::<Vec<_>>(), fib.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn drop_deep_tree_on_small_stack() {
+        let drops = std::thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(|| {
+                let drops = Rc::new(Cell::new(0));
+                let mut heap = PairingHeap::new();
+                // every push becomes the new root, leaving a single path
+                for value in (0..300_000).rev() {
+                    heap.push(DropCounter::new(value, &drops));
+                }
+                drop(heap.clone());
+                drop(heap);
+                drops.get()
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(drops, 600_000);
+    }
+
+    #[test]
+    fn empty() {
+        let mut heap = PairingHeap::<u8>::from_vec(vec![]);
+        assert_eq!((heap.peek(), heap.iter().count()), (None, 0));
+        assert_eq!(heap.pop(), None);
+        heap.append(PairingHeap::new());
+        heap.push(1);
+        heap.clear();
+        assert!(heap.is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+use alloc::vec::Vec;
+use core::mem;
+
+use rayon::prelude::*;
+
+use crate::instrument::Counters;
+use crate::{less, FibonacciHeap, Node};
+
+// Below this many roots, handing chunks to other threads costs more than linking them serially.
+const MIN_PARALLEL_ROOTS: usize = 1 << 16;
+// Roots linked by one task before rayon stops splitting the work further.
+const MIN_CHUNK: usize = 1 << 12;
+
+impl<T: PartialOrd + Send> FibonacciHeap<T> {
+    /// Same as [`consolidate`](Self::consolidate), linking the roots on the rayon thread pool.
+    ///
+    /// The root list is split into chunks that are linked independently, each into trees of distinct degrees,
+    /// and the O(log n) trees of every chunk are then merged serially. The contents and the minimum are the same
+    /// as with `consolidate`, but the trees may be shaped differently, so elements that compare equal may pop
+    /// in a different order. Heaps with fewer than 65536 roots are consolidated serially.
+    ///
+    /// `consolidate` and `pop` stay serial since they can't require `T: Send`; this pays off after something
+    /// left millions of roots, such as a large `bulk_push` or melding many shards, and [`par_pop`](Self::par_pop)
+    /// does it as part of the next `pop`.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let mut heap = FibonacciHeap::from_vec((0..1_000_000).rev().collect());
+    /// heap.par_consolidate();
+    /// assert!(heap.root_count() <= 20);
+    /// assert_eq!(heap.pop(), Some(0));
+    /// ```
+    pub fn par_consolidate(&mut self) {
+        if self.roots.len() >= MIN_PARALLEL_ROOTS {
+            let forest = mem::take(&mut self.roots)
+                .into_par_iter()
+                .with_min_len(MIN_CHUNK)
+                .fold(Forest::default, Forest::insert)
+                .reduce(Forest::default, Forest::merge);
+            self.roots = forest.trees.into_iter().flatten().collect();
+            self.counters.merge(forest.counters);
+        }
+        // links nothing on the parallel path, but locates the minimum and keeps the eager degree table
+        self.consolidate();
+    }
+
+    /// Pops the minimum like [`pop`](Self::pop), consolidating with [`par_consolidate`](Self::par_consolidate)
+    /// first when the heap has at least 65536 roots.
+    pub fn par_pop(&mut self) -> Option<T> {
+        if self.roots.len() >= MIN_PARALLEL_ROOTS {
+            self.par_consolidate();
+        }
+        self.pop()
+    }
+}
+
+// Trees of distinct degrees, indexed by degree, and what linking them took.
+struct Forest<T> {
+    trees: Vec<Option<Node<T>>>,
+    counters: Counters,
+}
+
+impl<T: PartialOrd> Forest<T> {
+    // Adds a tree, linking it with the tree of equal degree (the smaller root becoming the parent)
+    // for as long as degrees collide.
+    fn insert(mut self, mut node: Node<T>) -> Self {
+        loop {
+            let degree = node.degree();
+            if degree >= self.trees.len() {
+                self.trees.resize_with(degree + 1, || None);
+            }
+            match self.trees[degree].take() {
+                Some(mut other) => {
+                    if less(&mut self.counters, other.value(), node.value()) {
+                        mem::swap(&mut node, &mut other);
+                    }
+                    if node.children.len() == node.children.capacity() {
+                        self.counters.allocation();
+                    }
+                    node.children.push(other);
+                    self.counters.link();
+                }
+                None => {
+                    self.trees[degree] = Some(node);
+                    return self;
+                }
+            }
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        self.counters.merge(other.counters);
+        other.trees.into_iter().flatten().fold(self, Self::insert)
+    }
+}
+
+impl<T> Default for Forest<T> {
+    fn default() -> Self {
+        Self { trees: Vec::new(), counters: Counters::default() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+    use crate::assert_heap_eq;
+
+    #[test]
+    fn matches_serial_consolidation() {
+        let mut rng = Rng(0x6a09e667f3bcc908);
+        let values: Vec<u64> = (0..300_000).map(|_| rng.below(1_000_000)).collect();
+        let mut heap = FibonacciHeap::from_vec(values.clone());
+        heap.par_consolidate();
+        let mut degrees: Vec<usize> = heap.roots.iter().map(Node::degree).collect();
+        degrees.sort_unstable();
+        degrees.dedup();
+        assert_eq!(degrees.len(), heap.root_count());
+        assert_eq!(heap.len(), values.len());
+        assert_eq!(heap.peek(), values.iter().min());
+
+        // melded shards, some roots already having children
+        let mut shards = FibonacciHeap::new();
+        for chunk in values.chunks(1000) {
+            let mut shard = FibonacciHeap::from_vec(chunk.to_vec());
+            shard.pop();
+            shards.append(shard);
+        }
+        shards.bulk_push(values[..100_000].iter().copied());
+        let mut serial = shards.clone();
+        serial.consolidate();
+        assert_eq!(shards.par_pop(), serial.pop());
+        assert!(shards.root_count() <= usize::BITS as usize);
+        assert_heap_eq!(shards, serial.into_sorted_vec());
+    }
+
+    #[test]
+    fn small_heaps() {
+        let mut heap = FibonacciHeap::from_vec(vec![3, 1, 2]);
+        heap.par_consolidate();
+        assert_eq!(heap.root_count(), 2);
+        assert_eq!(heap.par_pop(), Some(1));
+        heap.set_eager_consolidation(true);
+        heap.par_consolidate();
+        heap.push(0);
+        assert_eq!(heap.root_count(), 2);
+        assert_heap_eq!(heap, [0, 2, 3]);
+    }
+}
@@ -0,0 +1,254 @@
+use alloc::rc::Rc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Immutable Fibonacci heap: every operation returns a new heap and leaves the one it was called on untouched.
+///
+/// Versions share their nodes through `Rc`, so keeping many snapshots that differ by a few operations costs
+/// little more than the differences. Elements are stored as `Rc<T>` and `pop` hands them out that way, so `T`
+/// doesn't need to be `Clone`. The `Rc`s make versions neither `Send` nor `Sync`.
+///
+/// Costs, with `r` the number of roots of the version operated on:
+/// - `push`, `peek`, `len` and `clone`: O(1);
+/// - `meld`: O(r) of the second heap, whose root list is copied in front of the first one's;
+/// - `pop`: O(r + log n) to link the roots into at most O(log n) trees.
+///
+/// A mutable heap pays for its linking once, but a version here can be popped any number of times, and every one
+/// of those pops links the same roots again. The amortized bounds of [`FibonacciHeap`](crate::FibonacciHeap)
+/// therefore don't carry over. After a run of pushes, pop the version once and keep working from the result.
+///
+/// # Example
+/// ```
+/// use fibheap::PersistentFibonacciHeap;
+///
+/// let base = PersistentFibonacciHeap::new().push(3).push(1).push(2);
+/// let (min, rest) = base.pop().unwrap();
+/// assert_eq!(*min, 1);
+/// let branch = rest.push(0);
+/// assert_eq!(branch.peek(), Some(&0));
+/// // the older versions are unaffected
+/// assert_eq!(rest.peek(), Some(&2));
+/// assert_eq!((base.len(), base.peek()), (3, Some(&1)));
+/// ```
+pub struct PersistentFibonacciHeap<T> {
+    roots: List<T>,
+    // the root holding the minimum, also somewhere in `roots`
+    top: Option<Rc<PNode<T>>>,
+    len: usize,
+}
+
+struct PNode<T> {
+    value: Rc<T>,
+    degree: usize,
+    children: List<T>,
+}
+
+type List<T> = Option<Rc<Cons<T>>>;
+
+struct Cons<T> {
+    head: Rc<PNode<T>>,
+    tail: List<T>,
+}
+
+fn cons<T>(head: Rc<PNode<T>>, tail: List<T>) -> List<T> {
+    Some(Rc::new(Cons { head, tail }))
+}
+
+fn iter<T>(list: &List<T>) -> impl Iterator<Item = &Rc<PNode<T>>> {
+    let mut next = list.as_deref();
+    core::iter::from_fn(move || {
+        let cell = next?;
+        next = cell.tail.as_deref();
+        Some(&cell.head)
+    })
+}
+
+// Makes the greater root a child of the other one, in a new node: both trees stay as they were for other versions.
+fn link<T: PartialOrd>(a: Rc<PNode<T>>, b: Rc<PNode<T>>) -> Rc<PNode<T>> {
+    let (parent, child) = if b.value < a.value { (b, a) } else { (a, b) };
+    Rc::new(PNode { value: Rc::clone(&parent.value), degree: parent.degree + 1, children: cons(child, parent.children.clone()) })
+}
+
+impl<T> PersistentFibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the smallest element, or `None` if the heap is empty.
+    pub fn peek(&self) -> Option<&T> {
+        self.top.as_ref().map(|top| &*top.value)
+    }
+}
+
+impl<T: PartialOrd> PersistentFibonacciHeap<T> {
+    /// A new version with `value` added.
+    pub fn push(&self, value: T) -> Self {
+        let node = Rc::new(PNode { value: Rc::new(value), degree: 0, children: None });
+        let top = match &self.top {
+            Some(top) if node.value < top.value => Rc::clone(&node),
+            Some(top) => Rc::clone(top),
+            None => Rc::clone(&node),
+        };
+        Self { roots: cons(node, self.roots.clone()), top: Some(top), len: self.len + 1 }
+    }
+
+    /// The minimum and a new version without it, or `None` if the heap is empty.
+    pub fn pop(&self) -> Option<(Rc<T>, Self)> {
+        let top = self.top.as_ref()?;
+        // roots taken by degree, linked until all degrees are distinct
+        let mut table: Vec<Option<Rc<PNode<T>>>> = vec![];
+        let others = iter(&self.roots).filter(|root| !Rc::ptr_eq(root, top));
+        for tree in others.chain(iter(&top.children)) {
+            let mut tree = Rc::clone(tree);
+            loop {
+                if tree.degree >= table.len() {
+                    table.resize(tree.degree + 1, None);
+                }
+                match table[tree.degree].take() {
+                    Some(other) => tree = link(other, tree),
+                    None => break,
+                }
+            }
+            let degree = tree.degree;
+            table[degree] = Some(tree);
+        }
+        let mut rest = Self::new();
+        rest.len = self.len - 1;
+        for tree in table.into_iter().rev().flatten() {
+            if rest.top.as_ref().is_none_or(|top| tree.value < top.value) {
+                rest.top = Some(Rc::clone(&tree));
+            }
+            rest.roots = cons(tree, rest.roots.take());
+        }
+        Some((Rc::clone(&top.value), rest))
+    }
+
+    /// A new version holding the elements of both heaps.
+    pub fn meld(&self, other: &Self) -> Self {
+        let (Some(top), Some(other_top)) = (&self.top, &other.top) else {
+            return if self.is_empty() { other.clone() } else { self.clone() };
+        };
+        let roots: Vec<_> = iter(&other.roots).collect();
+        let mut merged = self.roots.clone();
+        for root in roots.into_iter().rev() {
+            merged = cons(Rc::clone(root), merged);
+        }
+        let top = if other_top.value < top.value { other_top } else { top };
+        Self { roots: merged, top: Some(Rc::clone(top)), len: self.len + other.len }
+    }
+}
+
+impl<T> Clone for PersistentFibonacciHeap<T> {
+    fn clone(&self) -> Self {
+        Self { roots: self.roots.clone(), top: self.top.clone(), len: self.len }
+    }
+}
+
+impl<T> Default for PersistentFibonacciHeap<T> {
+    fn default() -> Self {
+        Self { roots: None, top: None, len: 0 }
+    }
+}
+
+/// Shows the length and the minimum; listing every element would take a pop per element.
+impl<T: fmt::Debug> fmt::Debug for PersistentFibonacciHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PersistentFibonacciHeap").field("len", &self.len).field("top", &self.peek()).finish_non_exhaustive()
+    }
+}
+
+/// Releases the rest of a list that no other version shares one cell at a time, so that dropping the long root list
+/// left by many pushes can't overflow the call stack.
+impl<T> Drop for Cons<T> {
+    fn drop(&mut self) {
+        let mut tail = self.tail.take();
+        while let Some(cell) = tail {
+            match Rc::try_unwrap(cell) {
+                Ok(mut cell) => tail = cell.tail.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+
+    fn drain(heap: &PersistentFibonacciHeap<u64>) -> Vec<u64> {
+        let mut values = vec![];
+        let mut heap = heap.clone();
+        while let Some((value, rest)) = heap.pop() {
+            values.push(*value);
+            heap = rest;
+        }
+        values
+    }
+
+    #[test]
+    fn branches_stay_independent() {
+        let mut rng = Rng(0x6a09e667f3bcc909);
+        let mut base = PersistentFibonacciHeap::new();
+        let mut base_model = vec![];
+        for _ in 0..300 {
+            let value = rng.below(1000);
+            base = base.push(value);
+            base_model.push(value);
+        }
+        base = base.pop().unwrap().1;
+        base_model.sort_unstable();
+        base_model.remove(0);
+
+        // every branch starts from `base` and diverges with its own operations
+        let mut branches = vec![];
+        for branch in 0..20 {
+            let (mut heap, mut model) = (base.clone(), base_model.clone());
+            for step in 0..50 {
+                if rng.below(3) == 0 {
+                    let (min, rest) = heap.pop().unwrap();
+                    assert_eq!(*min, model.remove(0), "branch {branch}, step {step}");
+                    heap = rest;
+                } else {
+                    let value = rng.below(1000);
+                    heap = heap.push(value);
+                    model.push(value);
+                    model.sort_unstable();
+                }
+                assert_eq!(heap.len(), model.len());
+                assert_eq!(heap.peek(), model.first());
+            }
+            branches.push((heap, model));
+        }
+        let melded = branches[0].0.meld(&branches[1].0);
+        let mut model = [branches[0].1.clone(), branches[1].1.clone()].concat();
+        model.sort_unstable();
+        assert_eq!(drain(&melded), model);
+
+        assert_eq!(drain(&base), base_model);
+        for (heap, model) in &branches {
+            assert_eq!(drain(heap), *model);
+        }
+    }
+
+    #[test]
+    fn long_root_list() {
+        let mut heap = PersistentFibonacciHeap::new();
+        for i in (0..200_000).rev() {
+            heap = heap.push(i);
+        }
+        let (min, rest) = heap.pop().unwrap();
+        assert_eq!((*min, rest.len()), (0, 199_999));
+        assert_eq!(heap.meld(&PersistentFibonacciHeap::new()).len(), 200_000);
+        assert!(PersistentFibonacciHeap::<u64>::new().pop().is_none());
+    }
+}
@@ -0,0 +1,208 @@
+use alloc::boxed::Box;
+use core::cmp::Ordering;
+use core::fmt;
+use core::ptr::NonNull;
+
+use crate::FibonacciHeap;
+
+/// Min-heap whose elements stay at the same address from `push` until they are popped, so that outside code
+/// (e.g. a C callback registry) can keep pointers to queued values.
+///
+/// Every value gets its own allocation and only pointers to it move around the trees.
+/// [`push_pinned`](Self::push_pinned) returns the address of the value, which stays valid until that value is popped
+/// or the heap is cleared or dropped. Melding keeps addresses too: after `a.append(b)`, pointers into `b` stay valid
+/// and the values now belong to `a`.
+///
+/// The heap reads values through shared references whenever it compares them, so writing through a returned pointer
+/// while the heap is in use is undefined behavior, as is reading through it after the value was popped.
+///
+/// # Example
+/// ```
+/// use fibheap::PinnedFibonacciHeap;
+///
+/// let mut heap = PinnedFibonacciHeap::new();
+/// let three = heap.push_pinned(3);
+/// for value in [5, 1, 4] {
+///     heap.push_pinned(value);
+/// }
+/// assert_eq!(heap.pop(), Some(1));
+/// // still queued, at the address it was pushed to
+/// assert_eq!(unsafe { *three.as_ref() }, 3);
+/// assert!(core::ptr::eq(heap.peek().unwrap(), three.as_ptr()));
+/// ```
+pub struct PinnedFibonacciHeap<T> {
+    heap: FibonacciHeap<Pinned<T>>,
+}
+
+// Owns a value allocated by `Box`. A raw pointer rather than a `Box`, since moving a `Box` would assert
+// unique access and invalidate the pointers handed out for the value.
+struct Pinned<T>(NonNull<T>);
+
+// `Pinned` owns its value like a `Box` does.
+unsafe impl<T: Send> Send for Pinned<T> {}
+unsafe impl<T: Sync> Sync for Pinned<T> {}
+
+impl<T> Pinned<T> {
+    fn value(&self) -> &T {
+        // SAFETY: the pointer comes from `Box::into_raw` and is freed only when `self` is dropped or consumed.
+        unsafe { self.0.as_ref() }
+    }
+
+    fn into_inner(self) -> T {
+        let ptr = self.0;
+        core::mem::forget(self);
+        // SAFETY: as in `value`; `self` is forgotten, so the allocation is reclaimed exactly once.
+        *unsafe { Box::from_raw(ptr.as_ptr()) }
+    }
+}
+
+impl<T> Drop for Pinned<T> {
+    fn drop(&mut self) {
+        // SAFETY: as in `value`.
+        drop(unsafe { Box::from_raw(self.0.as_ptr()) });
+    }
+}
+
+impl<T: PartialEq> PartialEq for Pinned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value() == other.value()
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for Pinned<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value().partial_cmp(other.value())
+    }
+}
+
+impl<T> PinnedFibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Returns the smallest element, or `None` if the heap is empty.
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek().map(Pinned::value)
+    }
+
+    /// Iterates over references to the elements in unspecified order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.heap.iter().map(Pinned::value)
+    }
+
+    /// Drops every element, invalidating every pointer returned by `push_pinned`.
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+}
+
+impl<T: PartialOrd> PinnedFibonacciHeap<T> {
+    /// Pushes `value` and returns its address, valid until the value is popped or the heap is cleared or dropped.
+    pub fn push_pinned(&mut self, value: T) -> NonNull<T> {
+        let ptr = NonNull::from(Box::leak(Box::new(value)));
+        self.heap.push(Pinned(ptr));
+        ptr
+    }
+
+    /// Removes the smallest element, moving it out of its allocation.
+    pub fn pop(&mut self) -> Option<T> {
+        self.heap.pop().map(Pinned::into_inner)
+    }
+
+    /// Moves every element of `other` into `self` without moving any value, so pointers into `other` stay valid.
+    pub fn append(&mut self, mut other: Self) {
+        self.heap.append(core::mem::take(&mut other.heap));
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PinnedFibonacciHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PinnedFibonacciHeap").field("len", &self.len()).field("top", &self.peek()).finish()
+    }
+}
+
+impl<T> Default for PinnedFibonacciHeap<T> {
+    fn default() -> Self {
+        Self { heap: FibonacciHeap::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{DropCounter, Rng};
+    use std::cell::Cell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    #[test]
+    fn addresses_survive_churn() {
+        let rounds = if cfg!(miri) { 300 } else { 20_000 };
+        let mut rng = Rng(0x1f83d9abfb41bd6b);
+        let mut heap = PinnedFibonacciHeap::new();
+        // every value is distinct, so it identifies its element
+        let mut next = 0u64;
+        let mut fresh = |rng: &mut Rng| {
+            next += 1;
+            rng.below(1 << 20) << 20 | next
+        };
+        let mut addresses = HashMap::new();
+        for round in 0..rounds {
+            match rng.below(8) {
+                0..=3 => {
+                    let value = fresh(&mut rng);
+                    addresses.insert(value, heap.push_pinned(value));
+                }
+                4 => {
+                    let mut other = PinnedFibonacciHeap::new();
+                    for _ in 0..rng.below(10) {
+                        let value = fresh(&mut rng);
+                        addresses.insert(value, other.push_pinned(value));
+                    }
+                    heap.append(other);
+                }
+                _ => {
+                    if let Some(top) = heap.peek() {
+                        assert!(core::ptr::eq(top, addresses[top].as_ptr()));
+                    }
+                    if let Some(value) = heap.pop() {
+                        addresses.remove(&value);
+                    }
+                }
+            }
+            if round % 100 == 0 {
+                assert_eq!(heap.len(), addresses.len());
+                for value in heap.iter() {
+                    assert!(core::ptr::eq(value, addresses[value].as_ptr()));
+                }
+                for (value, ptr) in &addresses {
+                    assert_eq!(unsafe { ptr.as_ref() }, value);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn drops_every_value_once() {
+        let drops = Rc::new(Cell::new(0));
+        let mut heap = PinnedFibonacciHeap::new();
+        for value in [3, 1, 2, 5, 4] {
+            heap.push_pinned(DropCounter::new(value, &drops));
+        }
+        assert_eq!(heap.pop().map(|counter| counter.value), Some(1));
+        assert_eq!(drops.get(), 1);
+        heap.clear();
+        assert_eq!(drops.get(), 5);
+        heap.push_pinned(DropCounter::new(0, &drops));
+        drop(heap);
+        assert_eq!(drops.get(), 6);
+    }
+}
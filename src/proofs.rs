@@ -0,0 +1,125 @@
+// Kani harnesses for the core invariants, on sequences of a few nondeterministic operations. Kani also checks every
+// harness for undefined behaviour, which is what the slab harnesses are for: the `MaybeUninit` slots of
+// `CompactFibonacciHeap` and `FixedFibonacciHeap` must only be read while initialized, and dropped exactly once.
+//
+//     cargo kani --lib
+//     cargo kani --lib --harness pop_returns_minimum
+
+use alloc::vec::Vec;
+
+use crate::{CompactFibonacciHeap, FibonacciHeap, FixedFibonacciHeap};
+
+// Operations per harness; the unwind bounds below cover loops over this many elements, plus the 64-entry degree
+// table of `FixedFibonacciHeap`.
+const OPS: usize = 5;
+
+// The smallest of `model`, which is what the next pop must return.
+fn model_min(model: &[u8]) -> Option<u8> {
+    model.iter().copied().min()
+}
+
+fn model_remove(model: &mut Vec<u8>, value: u8) {
+    let ix = model.iter().position(|&v| v == value).unwrap();
+    model.swap_remove(ix);
+}
+
+#[kani::proof]
+#[kani::unwind(7)]
+fn pop_returns_minimum() {
+    let mut heap = FibonacciHeap::new();
+    let mut model = Vec::new();
+    for _ in 0..OPS {
+        if kani::any() {
+            let value: u8 = kani::any();
+            heap.push(value);
+            model.push(value);
+        } else {
+            let popped = heap.pop();
+            assert_eq!(popped, model_min(&model));
+            if let Some(value) = popped {
+                model_remove(&mut model, value);
+            }
+        }
+        assert_eq!(heap.peek().copied(), model_min(&model));
+    }
+}
+
+#[kani::proof]
+#[kani::unwind(7)]
+fn len_matches_reachable_values() {
+    let mut heap = FibonacciHeap::new();
+    for _ in 0..OPS {
+        match kani::any::<u8>() % 3 {
+            0 => heap.push(kani::any::<u8>()),
+            1 => {
+                heap.pop();
+            }
+            _ => {
+                let mut other = FibonacciHeap::new();
+                other.push(kani::any::<u8>());
+                heap.append(other);
+            }
+        }
+        assert_eq!(heap.iter().count(), heap.len());
+        assert!(heap.validate().is_ok());
+    }
+}
+
+// Every pushed element, told apart by its position, comes out exactly once.
+#[kani::proof]
+#[kani::unwind(7)]
+fn elements_are_never_lost_or_duplicated() {
+    let mut heap = FibonacciHeap::new();
+    let mut seen = [0u8; OPS];
+    for ix in 0..OPS {
+        heap.push((kani::any::<u8>() % 4, ix));
+        if kani::any() {
+            let (_, popped) = heap.pop().unwrap();
+            seen[popped] += 1;
+        }
+    }
+    while let Some((_, popped)) = heap.pop() {
+        seen[popped] += 1;
+    }
+    assert!(seen.iter().all(|&count| count == 1));
+}
+
+// Pops match the reference heap, and values left in the slots are dropped with the heap.
+#[kani::proof]
+#[kani::unwind(7)]
+fn compact_slots_are_sound() {
+    let mut heap = CompactFibonacciHeap::new();
+    let mut reference = FibonacciHeap::new();
+    for _ in 0..OPS {
+        if kani::any() {
+            let value: u8 = kani::any();
+            heap.push(value);
+            reference.push(value);
+        } else {
+            assert_eq!(heap.pop(), reference.pop());
+        }
+        assert_eq!(heap.len(), reference.len());
+        assert_eq!(heap.peek(), reference.peek());
+    }
+}
+
+#[kani::proof]
+#[kani::unwind(66)]
+fn fixed_slots_are_sound() {
+    let mut heap = FixedFibonacciHeap::<u8, 3>::new();
+    let mut reference = FibonacciHeap::new();
+    for _ in 0..OPS {
+        if kani::any() {
+            let value: u8 = kani::any();
+            // a full heap hands the value back instead of writing past its slots
+            match heap.push(value) {
+                Ok(()) => reference.push(value),
+                Err(back) => assert!(back == value && reference.len() == 3),
+            }
+        } else {
+            assert_eq!(heap.pop(), reference.pop());
+        }
+        assert_eq!(heap.len(), reference.len());
+        assert_eq!(heap.top(), reference.peek());
+    }
+}
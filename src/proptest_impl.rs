@@ -0,0 +1,47 @@
+use std::fmt::Debug;
+
+use proptest::collection::{self, SizeRange};
+use proptest::prelude::*;
+
+use crate::FibonacciHeap;
+
+/// Strategy generating heaps of `size` elements drawn from `element`.
+///
+/// Heaps are built by replaying a sequence of pushes, some of them followed by a push/pop pair,
+/// so they carry consolidated trees rather than the flat root list of [`FibonacciHeap::from_vec`];
+/// since every operation sequence yields a valid heap, shrunk cases keep that property and an interesting structure.
+///
+/// # Example
+/// ```
+/// use fibheap::heap_strategy;
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///     fn pops_in_order(mut heap in heap_strategy(any::<u8>(), 0..50)) {
+///         let mut last = None;
+///         while let Some(value) = heap.pop() {
+///             prop_assert!(last <= Some(value));
+///             last = Some(value);
+///         }
+///     }
+/// }
+/// # pops_in_order();
+/// ```
+pub fn heap_strategy<S>(element: S, size: impl Into<SizeRange>) -> impl Strategy<Value = FibonacciHeap<S::Value>>
+where
+    S: Strategy + Clone,
+    S::Value: PartialOrd + Clone + Debug,
+{
+    let churn = proptest::option::weighted(0.3, element.clone());
+    collection::vec((element, churn), size).prop_map(|ops| {
+        let mut heap = FibonacciHeap::new();
+        for (value, churn) in ops {
+            heap.push(value);
+            if let Some(extra) = churn {
+                heap.push(extra);
+                heap.pop();
+            }
+        }
+        heap
+    })
+}
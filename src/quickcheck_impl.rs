@@ -0,0 +1,31 @@
+use quickcheck::{Arbitrary, Gen};
+
+use crate::FibonacciHeap;
+
+/// Heaps are built by replaying random pushes, some followed by a push/pop pair, so they carry consolidated trees.
+///
+/// Shrinking shrinks the multiset of elements (dropping elements or shrinking them) and rebuilds a consolidated heap
+/// from each candidate, so every shrunk heap is valid.
+impl<T: Arbitrary + PartialOrd> Arbitrary for FibonacciHeap<T> {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let ops = Vec::<(T, Option<T>)>::arbitrary(g);
+        let mut heap = FibonacciHeap::new();
+        for (value, churn) in ops {
+            heap.push(value);
+            if let Some(extra) = churn {
+                heap.push(extra);
+                heap.pop();
+            }
+        }
+        heap
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let values: Vec<T> = self.iter().cloned().collect();
+        Box::new(values.shrink().map(|values| {
+            let mut heap = FibonacciHeap::from_vec(values);
+            heap.consolidate();
+            heap
+        }))
+    }
+}
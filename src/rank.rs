@@ -0,0 +1,95 @@
+use alloc::vec::Vec;
+
+use crate::{FibonacciHeap, Node};
+
+impl<T: PartialOrd> FibonacciHeap<T> {
+    /// Number of elements strictly less than `value`.
+    ///
+    /// Heap order lets the walk skip every subtree whose root is not less than `value`, so the cost is the number
+    /// of roots plus, for each element counted, its number of children: O(rank · log n) after a `pop`, and
+    /// independent of how many elements are larger.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let heap = FibonacciHeap::from_vec(vec![5, 1, 3, 3, 8]);
+    /// assert_eq!(heap.rank(&3), 1);
+    /// assert_eq!(heap.rank(&4), 3);
+    /// assert_eq!(heap.rank(&0), 0);
+    /// ```
+    pub fn rank(&self, value: &T) -> usize {
+        let mut rank = 0;
+        let mut stack: Vec<&Node<T>> = self.roots.iter().collect();
+        while let Some(node) = stack.pop() {
+            if node.value() < value {
+                rank += 1;
+                stack.extend(&node.children);
+            }
+        }
+        rank
+    }
+
+    /// The `k`-th smallest element, counting from 0, or `None` if the heap holds `k` elements or fewer.
+    ///
+    /// This walks the heap in ascending order like [`iter_sorted`](Self::iter_sorted), in O(k log k) after a `pop`.
+    /// Among equal elements, which one is returned is unspecified.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let heap = FibonacciHeap::from_vec(vec![5, 1, 3, 3, 8]);
+    /// assert_eq!(heap.select(0), Some(&1));
+    /// assert_eq!(heap.select(2), Some(&3));
+    /// assert_eq!(heap.select(5), None);
+    /// ```
+    pub fn select(&self, k: usize) -> Option<&T> {
+        if k >= self.len {
+            return None;
+        }
+        self.iter_sorted().nth(k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::Rng;
+    use crate::FibonacciHeap;
+
+    #[test]
+    fn rank_skips_larger_subtrees() {
+        let mut heap = FibonacciHeap::from_vec((0..100_000).collect());
+        heap.pop();
+        // pruned at the roots of every tree that holds nothing below 10
+        assert_eq!(heap.rank(&10), 9);
+        assert_eq!(heap.rank(&100_000), 99_999);
+        assert_eq!(heap.select(8), Some(&9));
+        assert_eq!(heap.select(99_998), Some(&99_999));
+        assert_eq!(heap.select(99_999), None);
+
+        let empty = FibonacciHeap::<u8>::new();
+        assert_eq!(empty.rank(&0), 0);
+        assert_eq!(empty.select(0), None);
+
+        // nothing is less than NaN
+        let heap = FibonacciHeap::from_vec(vec![1.0, 2.0, 3.0]);
+        assert_eq!(heap.rank(&f64::NAN), 0);
+        assert_eq!(heap.rank(&2.5), 2);
+    }
+
+    #[test]
+    fn duplicates() {
+        let mut rng = Rng(0x5d1e5b6c84a3f0e7);
+        let mut heap = FibonacciHeap::new();
+        for _ in 0..1000 {
+            heap.push(rng.below(50));
+        }
+        heap.pop();
+        let sorted = heap.clone().into_sorted_vec();
+        for k in 0..sorted.len() {
+            assert_eq!(heap.select(k), Some(&sorted[k]));
+            assert_eq!(heap.rank(&sorted[k]), sorted.partition_point(|&v| v < sorted[k]));
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use alloc::vec::Vec;
+
+use crate::FibonacciHeap;
+
+/// One operation recorded by a [`RecordingHeap`], with its arguments.
+///
+/// With the `serde` feature a log of operations can be serialized, e.g. to attach it to a bug report.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Op<T> {
+    Push(T),
+    BulkPush(Vec<T>),
+    Pop,
+    PushPop(T),
+    ReplaceTop(T),
+    /// Appending a heap built by these operations.
+    Append(Vec<Op<T>>),
+    Consolidate,
+    Clear,
+    SetMaxLinksPerPop(Option<usize>),
+    SetEagerConsolidation(bool),
+}
+
+/// [`FibonacciHeap`] logging every operation that changes it, so that the heap can be rebuilt with [`replay`].
+///
+/// The log covers the heap from its creation and records arguments by cloning them. Replaying it repeats the same
+/// operations on a new heap, and since the heap is deterministic that rebuilds the same forest, not just the same
+/// contents: stepping through a log with [`replay_until`] shows how the heap got into a given shape.
+///
+/// # Example
+/// ```
+/// use fibheap::{replay, replay_until, RecordingHeap};
+///
+/// let mut heap = RecordingHeap::new();
+/// heap.bulk_push([4, 2, 7, 1]);
+/// assert_eq!(heap.pop(), Some(1));
+/// heap.push(3);
+///
+/// let ops = heap.ops().to_vec();
+/// assert_eq!(replay(&ops), *heap.heap());
+/// // the forest after each step, e.g. to render it with `to_dot`
+/// for step in 0..=ops.len() {
+///     println!("{}", replay_until(&ops, step).display_tree());
+/// }
+/// assert_eq!(replay_until(&ops, 1).len(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecordingHeap<T> {
+    heap: FibonacciHeap<T>,
+    ops: Vec<Op<T>>,
+}
+
+impl<T> RecordingHeap<T> {
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// The underlying heap, for read-only access such as iteration or rendering.
+    pub fn heap(&self) -> &FibonacciHeap<T> {
+        &self.heap
+    }
+
+    /// The operations recorded so far, oldest first.
+    pub fn ops(&self) -> &[Op<T>] {
+        &self.ops
+    }
+
+    /// Splits the wrapper into the heap and its log.
+    pub fn into_parts(self) -> (FibonacciHeap<T>, Vec<Op<T>>) {
+        (self.heap, self.ops)
+    }
+}
+
+impl<T: PartialOrd + Clone> RecordingHeap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.ops.push(Op::Push(value.clone()));
+        self.heap.push(value);
+    }
+
+    pub fn bulk_push(&mut self, items: impl IntoIterator<Item = T>) {
+        let items: Vec<T> = items.into_iter().collect();
+        self.ops.push(Op::BulkPush(items.clone()));
+        self.heap.bulk_push(items);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.ops.push(Op::Pop);
+        self.heap.pop()
+    }
+
+    pub fn push_pop(&mut self, value: T) -> T {
+        self.ops.push(Op::PushPop(value.clone()));
+        self.heap.push_pop(value)
+    }
+
+    pub fn replace_top(&mut self, value: T) -> Option<T> {
+        self.ops.push(Op::ReplaceTop(value.clone()));
+        self.heap.replace_top(value)
+    }
+
+    /// Appends `other`, recording its whole log so that its trees are rebuilt as they were.
+    pub fn append(&mut self, other: RecordingHeap<T>) {
+        self.ops.push(Op::Append(other.ops));
+        self.heap.append(other.heap);
+    }
+
+    pub fn consolidate(&mut self) {
+        self.ops.push(Op::Consolidate);
+        self.heap.consolidate();
+    }
+
+    pub fn clear(&mut self) {
+        self.ops.push(Op::Clear);
+        self.heap.clear();
+    }
+
+    pub fn set_max_links_per_pop(&mut self, max: Option<usize>) {
+        self.ops.push(Op::SetMaxLinksPerPop(max));
+        self.heap.set_max_links_per_pop(max);
+    }
+
+    pub fn set_eager_consolidation(&mut self, eager: bool) {
+        self.ops.push(Op::SetEagerConsolidation(eager));
+        self.heap.set_eager_consolidation(eager);
+    }
+}
+
+impl<T> Default for RecordingHeap<T> {
+    fn default() -> Self {
+        Self { heap: FibonacciHeap::new(), ops: Vec::new() }
+    }
+}
+
+/// Rebuilds the heap that a [`RecordingHeap`] held after recording `ops`.
+pub fn replay<T: PartialOrd + Clone>(ops: &[Op<T>]) -> FibonacciHeap<T> {
+    replay_until(ops, ops.len())
+}
+
+/// Rebuilds the heap as it was after the first `n` operations of `ops` (all of them if there are fewer).
+pub fn replay_until<T: PartialOrd + Clone>(ops: &[Op<T>], n: usize) -> FibonacciHeap<T> {
+    let mut heap = FibonacciHeap::new();
+    for op in &ops[..n.min(ops.len())] {
+        match op {
+            Op::Push(value) => heap.push(value.clone()),
+            Op::BulkPush(items) => heap.bulk_push(items.iter().cloned()),
+            Op::Pop => drop(heap.pop()),
+            Op::PushPop(value) => drop(heap.push_pop(value.clone())),
+            Op::ReplaceTop(value) => drop(heap.replace_top(value.clone())),
+            Op::Append(other) => heap.append(replay(other)),
+            Op::Consolidate => heap.consolidate(),
+            Op::Clear => heap.clear(),
+            Op::SetMaxLinksPerPop(max) => heap.set_max_links_per_pop(*max),
+            Op::SetEagerConsolidation(eager) => heap.set_eager_consolidation(*eager),
+        }
+    }
+    heap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+    use alloc::vec;
+
+    #[test]
+    fn replay_matches_live_heap() {
+        let mut rng = Rng(0x2545f4914f6cdd1d);
+        let mut live = RecordingHeap::new();
+        live.set_max_links_per_pop(Some(8));
+        for step in 0..3000 {
+            let value = rng.below(500);
+            match rng.below(10) {
+                0..=3 => live.push(value),
+                4..=5 => drop(live.pop()),
+                6 => drop(live.push_pop(value)),
+                7 => drop(live.replace_top(value)),
+                8 => live.bulk_push((0..rng.below(20)).map(|i| value + i)),
+                _ if step % 7 == 0 => {
+                    let mut other = RecordingHeap::new();
+                    other.bulk_push(vec![value, value / 2, value * 2]);
+                    other.pop();
+                    live.append(other);
+                }
+                _ => live.consolidate(),
+            }
+            if step == 1500 {
+                live.set_eager_consolidation(true);
+            }
+        }
+
+        let replayed = replay(live.ops());
+        assert_eq!(replayed, *live.heap());
+        // same trees, so the same rendering
+        assert_eq!(replayed.to_dot(), live.heap().to_dot());
+        assert_eq!(replayed.eager_consolidation(), live.heap().eager_consolidation());
+        let (heap, ops) = live.into_parts();
+        assert_eq!(replayed.into_sorted_vec(), heap.into_sorted_vec());
+
+        // every prefix stops at the right length
+        let mut len = 0usize;
+        for (ix, op) in ops.iter().enumerate().take(200) {
+            len = match op {
+                Op::Push(_) => len + 1,
+                Op::BulkPush(items) => len + items.len(),
+                Op::Pop => len.saturating_sub(1),
+                Op::PushPop(_) | Op::Consolidate | Op::SetMaxLinksPerPop(_) | Op::SetEagerConsolidation(_) => len,
+                Op::ReplaceTop(_) => len.max(1),
+                Op::Append(other) => len + replay(other).len(),
+                Op::Clear => 0,
+            };
+            assert_eq!(replay_until(&ops, ix + 1).len(), len);
+        }
+        assert!(replay_until(&ops, 0).is_empty());
+        assert_eq!(replay_until(&ops, usize::MAX).len(), replay(&ops).len());
+    }
+}
@@ -0,0 +1,80 @@
+use core::slice;
+
+use rkyv::munge::munge;
+use rkyv::rancor::Fallible;
+use rkyv::ser::{Allocator, Writer};
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{Archive, Deserialize, Place, Portable, Serialize};
+
+use crate::FibonacciHeap;
+
+/// Archived form of a [`FibonacciHeap`]: its elements in a flat array, in unspecified order.
+///
+/// It can be read in place (length and iteration) without deserializing;
+/// deserializing bulk-builds a working heap with [`FibonacciHeap::from_vec`].
+/// Validation through `rkyv::access` checks the array like an archived `Vec`.
+///
+/// # Example
+/// ```
+/// use fibheap::{ArchivedFibonacciHeap, FibonacciHeap};
+/// use rkyv::rancor::Error;
+///
+/// let heap = FibonacciHeap::from_vec(vec![3u32, 1, 2]);
+/// let bytes = rkyv::to_bytes::<Error>(&heap).unwrap();
+///
+/// let archived = rkyv::access::<ArchivedFibonacciHeap<rkyv::Archived<u32>>, Error>(&bytes).unwrap();
+/// assert_eq!(archived.len(), 3);
+/// assert_eq!(archived.iter().map(|v| v.to_native()).sum::<u32>(), 6);
+///
+/// let mut back: FibonacciHeap<u32> = rkyv::deserialize::<_, Error>(archived).unwrap();
+/// assert_eq!(back.pop(), Some(1));
+/// ```
+#[derive(Portable, rkyv::bytecheck::CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck)]
+#[repr(transparent)]
+pub struct ArchivedFibonacciHeap<T> {
+    elements: ArchivedVec<T>,
+}
+
+impl<T> ArchivedFibonacciHeap<T> {
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Iterates over the archived elements in unspecified order.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.elements.iter()
+    }
+}
+
+impl<T: Archive> Archive for FibonacciHeap<T> {
+    type Archived = ArchivedFibonacciHeap<T::Archived>;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: VecResolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedFibonacciHeap { elements } = out);
+        ArchivedVec::resolve_from_len(self.len, resolver, elements);
+    }
+}
+
+impl<T: Serialize<S>, S: Fallible + Allocator + Writer + ?Sized> Serialize<S> for FibonacciHeap<T> {
+    fn serialize(&self, serializer: &mut S) -> Result<VecResolver, S::Error> {
+        ArchivedVec::<T::Archived>::serialize_from_iter::<T, _, _>(self.iter(), serializer)
+    }
+}
+
+impl<T, D> Deserialize<FibonacciHeap<T>, D> for ArchivedFibonacciHeap<T::Archived>
+where
+    T: Archive + PartialOrd,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<FibonacciHeap<T>, D::Error> {
+        let values = self.iter().map(|value| value.deserialize(deserializer)).collect::<Result<_, _>>()?;
+        Ok(FibonacciHeap::from_vec(values))
+    }
+}
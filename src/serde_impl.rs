@@ -0,0 +1,130 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+use crate::FibonacciHeap;
+
+/// Serializes the elements as a flat sequence in ascending order.
+///
+/// Only the elements are written, never the internal structure, so the format is the same as that of a sorted `Vec<T>`
+/// and stays readable across crate versions.
+impl<T: Serialize + PartialOrd> Serialize for FibonacciHeap<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for value in self.iter_sorted() {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
+
+/// Accepts any sequence of elements, in any order, and bulk-builds the heap with [`FibonacciHeap::from_vec`].
+/// Elements that don't compare equal to themselves, such as NaN, are rejected with [`DecodeError::Incomparable`].
+///
+/// For input that may be hostile, [`DeserializeOptions`] bounds the number of elements and checks their order.
+impl<'de, T: Deserialize<'de> + PartialOrd> Deserialize<'de> for FibonacciHeap<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        DeserializeOptions::default().deserialize(deserializer)
+    }
+}
+
+/// Limits and checks for deserializing a [`FibonacciHeap`] from untrusted input.
+///
+/// The default accepts what the plain `Deserialize` impl does. Every rejection is a [`DecodeError`], passed to the
+/// format through `de::Error::custom`, so malformed input never panics.
+///
+/// # Example
+/// ```
+/// use fibheap::{DeserializeOptions, FibonacciHeap};
+///
+/// let options = DeserializeOptions { max_len: Some(3), validate: true };
+/// let heap: FibonacciHeap<i32> = options.deserialize(&mut serde_json::Deserializer::from_str("[1,2,2]")).unwrap();
+/// assert_eq!(heap.len(), 3);
+///
+/// let too_long = options.deserialize::<i32, _>(&mut serde_json::Deserializer::from_str("[1,2,3,4]"));
+/// assert!(too_long.unwrap_err().to_string().contains("more than 3 elements"));
+/// let unordered = options.deserialize::<i32, _>(&mut serde_json::Deserializer::from_str("[2,1]"));
+/// assert!(unordered.unwrap_err().to_string().contains("element 1"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DeserializeOptions {
+    /// Most elements accepted. A length prefix above it is rejected before anything is allocated, and input without
+    /// one is cut off at the first element past it. Memory reserved up front never exceeds room for this many.
+    pub max_len: Option<usize>,
+    /// Require the elements in the ascending order that `Serialize` writes, each comparable with the one before.
+    pub validate: bool,
+}
+
+impl DeserializeOptions {
+    pub fn deserialize<'de, T, D>(self, deserializer: D) -> Result<FibonacciHeap<T>, D::Error>
+    where
+        T: Deserialize<'de> + PartialOrd,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(HeapVisitor { options: self, marker: PhantomData })
+    }
+}
+
+/// Why [`DeserializeOptions::deserialize`] rejected its input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input holds, or claims to hold, more than `max_len` elements.
+    TooLong { max_len: usize },
+    /// The element at `index` is smaller than, or not comparable with, the one before it.
+    Unordered { index: usize },
+    /// The element at `index` doesn't compare equal to itself, as NaN doesn't, so it has no place in a heap.
+    Incomparable { index: usize },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLong { max_len } => write!(f, "heap has more than {max_len} elements"),
+            Self::Unordered { index } => write!(f, "element {index} is out of ascending order"),
+            Self::Incomparable { index } => write!(f, "element {index} is not comparable with itself"),
+        }
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+struct HeapVisitor<T> {
+    options: DeserializeOptions,
+    marker: PhantomData<T>,
+}
+
+impl<'de, T: Deserialize<'de> + PartialOrd> Visitor<'de> for HeapVisitor<T> {
+    type Value = FibonacciHeap<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of heap elements")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let DeserializeOptions { max_len, validate } = self.options;
+        let hint = seq.size_hint();
+        let max_len = max_len.unwrap_or(usize::MAX);
+        if hint.is_some_and(|len| len > max_len) {
+            return Err(de::Error::custom(DecodeError::TooLong { max_len }));
+        }
+        // Don't trust the size hint with more than a bounded preallocation.
+        let mut values: Vec<T> = Vec::with_capacity(hint.unwrap_or(0).min(max_len).min(4096));
+        while let Some(value) = seq.next_element()? {
+            if values.len() == max_len {
+                return Err(de::Error::custom(DecodeError::TooLong { max_len }));
+            }
+            if validate && values.last().is_some_and(|last| !last.partial_cmp(&value).is_some_and(Ordering::is_le)) {
+                return Err(de::Error::custom(DecodeError::Unordered { index: values.len() }));
+            }
+            if value.partial_cmp(&value) != Some(Ordering::Equal) {
+                return Err(de::Error::custom(DecodeError::Incomparable { index: values.len() }));
+            }
+            values.push(value);
+        }
+        Ok(FibonacciHeap::from_vec(values))
+    }
+}
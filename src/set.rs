@@ -0,0 +1,234 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use crate::FibonacciHeap;
+
+/// Min-heap of distinct values: [`insert`](Self::insert) refuses a value equal to one already present, and
+/// [`remove`](Self::remove) takes a value out by equality.
+///
+/// Each value is shared, through an `Arc`, between the heap and a hash set that indexes membership, so `T` needn't be
+/// `Clone`. A removed value leaves the set at once and its heap entry, no longer shared, is dropped once it reaches
+/// the top; removals are compacted away all at once when they outnumber the live values. The top is always live, so
+/// [`peek`](Self::peek) needs no `&mut self`. Popping a value frees it for reinsertion.
+///
+/// `Hash` and `Eq` must agree with `PartialOrd` about which values are equal.
+///
+/// # Example
+/// ```
+/// use fibheap::FibonacciHeapSet;
+///
+/// let mut set = FibonacciHeapSet::new();
+/// assert!(set.insert(3));
+/// assert!(set.insert(1));
+/// assert!(!set.insert(3));
+///
+/// let mut other = FibonacciHeapSet::new();
+/// other.extend([2, 3]);
+/// set.meld(other);
+/// assert!(set.remove(&2));
+/// assert_eq!(set.pop(), Some(1));
+/// assert_eq!(set.pop(), Some(3));
+/// assert_eq!(set.pop(), None);
+/// ```
+pub struct FibonacciHeapSet<T> {
+    // every value in `members`, plus removed ones, which are the only entries not shared with `members`
+    heap: FibonacciHeap<Arc<T>>,
+    members: HashSet<Arc<T>>,
+}
+
+// Removed entries are compacted away once there are more of them than live values, and at least this many.
+const COMPACT_MIN_GARBAGE: usize = 64;
+
+impl<T: Hash + Eq + PartialOrd> FibonacciHeapSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Number of removed values whose heap entries are still stored.
+    pub fn garbage_len(&self) -> usize {
+        self.heap.len() - self.members.len()
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.members.contains(value)
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek().map(|value| &**value)
+    }
+
+    /// Inserts `value` unless an equal value is present, in which case `value` is dropped and `false` returned.
+    pub fn insert(&mut self, value: T) -> bool {
+        if self.members.contains(&value) {
+            return false;
+        }
+        let value = Arc::new(value);
+        self.members.insert(Arc::clone(&value));
+        self.heap.push(value);
+        true
+    }
+
+    /// Removes the value equal to `value`, returning `false` if there is none.
+    pub fn remove(&mut self, value: &T) -> bool {
+        if !self.members.remove(value) {
+            return false;
+        }
+        self.drop_removed_top();
+        if self.garbage_len() >= COMPACT_MIN_GARBAGE && self.garbage_len() > self.len() {
+            self.compact();
+        }
+        true
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.heap.pop()?;
+        self.members.remove(&*value);
+        self.drop_removed_top();
+        Some(Arc::into_inner(value).expect("popped value is still shared"))
+    }
+
+    /// Moves the values of `other` that aren't present here into `self`; of two equal values, the one in `self`
+    /// is kept.
+    ///
+    /// Costs a set lookup per value of `other` and an O(1) heap append; the duplicates left behind in the heap are
+    /// dropped like removed values.
+    pub fn meld(&mut self, other: Self) {
+        let Self { heap, members } = other;
+        for value in members {
+            if !self.members.contains(&*value) {
+                self.members.insert(value);
+            }
+        }
+        self.heap.append(heap);
+        self.drop_removed_top();
+        if self.garbage_len() >= COMPACT_MIN_GARBAGE && self.garbage_len() > self.len() {
+            self.compact();
+        }
+    }
+
+    /// Drops every removed entry, rebuilding the heap from the live values in O(n).
+    pub fn compact(&mut self) {
+        if self.garbage_len() == 0 {
+            return;
+        }
+        let mut values = self.heap.take_values();
+        values.retain(is_member);
+        self.heap.bulk_push(values);
+    }
+
+    fn drop_removed_top(&mut self) {
+        while self.heap.peek().is_some_and(|value| !is_member(value)) {
+            self.heap.pop();
+        }
+    }
+}
+
+// An entry is live while `members` holds the other reference.
+fn is_member<T>(value: &Arc<T>) -> bool {
+    Arc::strong_count(value) == 2
+}
+
+impl<T> Default for FibonacciHeapSet<T> {
+    fn default() -> Self {
+        Self { heap: FibonacciHeap::new(), members: HashSet::new() }
+    }
+}
+
+impl<T: Hash + Eq + PartialOrd> Extend<T> for FibonacciHeapSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T: Hash + Eq + PartialOrd> FromIterator<T> for FibonacciHeapSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+/// Shows the values in unspecified order.
+impl<T: fmt::Debug> fmt::Debug for FibonacciHeapSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.members.iter().map(|value| &**value)).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removed_values_are_reinsertable() {
+        let mut set: FibonacciHeapSet<u32> = (0..10).collect();
+        assert!(set.remove(&5));
+        assert!(!set.remove(&5));
+        assert!(!set.contains(&5));
+        assert_eq!(set.garbage_len(), 1);
+        assert!(set.insert(5));
+        // the old entry is still garbage, the new one is live
+        assert_eq!((set.len(), set.garbage_len()), (10, 1));
+        assert_eq!((0..10).map(|_| set.pop().unwrap()).collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+        assert_eq!(set.garbage_len(), 0);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn removals_are_compacted() {
+        let mut set: FibonacciHeapSet<u32> = (0..1000).collect();
+        set.pop();
+        for value in (1..1000).rev().step_by(3) {
+            assert!(set.remove(&value));
+        }
+        for value in (1..1000).filter(|value| value % 3 != 0) {
+            assert!(set.remove(&value));
+        }
+        // once removals outnumbered live values, the heap was rebuilt
+        assert!(set.garbage_len() <= set.len().max(COMPACT_MIN_GARBAGE));
+        assert_eq!(set.len(), 0);
+        assert_eq!(set.peek(), None);
+    }
+
+    #[test]
+    fn meld_keeps_one_representative() {
+        // equal by value, told apart by a tag that is neither hashed nor compared
+        #[derive(Debug)]
+        struct Tagged(u32, char);
+        impl PartialEq for Tagged {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+        impl Eq for Tagged {}
+        impl Hash for Tagged {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                self.0.hash(state);
+            }
+        }
+        impl PartialOrd for Tagged {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.0.partial_cmp(&other.0)
+            }
+        }
+
+        let mut set: FibonacciHeapSet<_> = [Tagged(1, 'a'), Tagged(3, 'a')].into_iter().collect();
+        set.meld([Tagged(1, 'b'), Tagged(2, 'b'), Tagged(3, 'b')].into_iter().collect());
+        assert_eq!(set.len(), 3);
+        let popped: Vec<_> = std::iter::from_fn(|| set.pop()).map(|Tagged(value, tag)| (value, tag)).collect();
+        assert_eq!(popped, [(1, 'a'), (2, 'b'), (3, 'a')]);
+        assert_eq!(set.garbage_len(), 0);
+    }
+}
@@ -0,0 +1,227 @@
+use std::time::{Duration, Instant};
+
+use crate::lock::{self, Arc, Condvar, Mutex, MutexGuard};
+use crate::FibonacciHeap;
+
+struct State<T> {
+    heap: FibonacciHeap<T>,
+    closed: bool,
+}
+
+struct Inner<T> {
+    state: Mutex<State<T>>,
+    available: Condvar,
+}
+
+/// Cloneable, thread-safe handle to a heap, with pops that can wait for elements.
+///
+/// Handles are `Send` and `Sync` when `T` is `Send`, as the heap is only ever reached through its lock.
+///
+/// Every clone refers to the same heap. [`close`](Self::close) wakes all waiters:
+/// from then on the blocking pops stop waiting, return whatever is left, and then `None`.
+///
+/// The internal lock is held while `T`'s comparisons and destructors run,
+/// so a `PartialOrd` or `Drop` impl that calls back into the same heap deadlocks.
+///
+/// # Example
+/// ```
+/// use std::thread;
+/// use fibheap::SharedFibonacciHeap;
+///
+/// let heap = SharedFibonacciHeap::new();
+/// let worker = {
+///     let heap = heap.clone();
+///     thread::spawn(move || heap.pop_blocking())
+/// };
+/// heap.push(42);
+/// assert_eq!(worker.join().unwrap(), Some(42));
+/// ```
+pub struct SharedFibonacciHeap<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T: PartialOrd> SharedFibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self::from(FibonacciHeap::new())
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().heap.is_empty()
+    }
+
+    /// Pushes `value` and wakes one waiting pop; pushing after `close` is still allowed.
+    pub fn push(&self, value: T) {
+        self.lock().heap.push(value);
+        self.inner.available.notify_one();
+    }
+
+    pub fn try_pop(&self) -> Option<T> {
+        self.lock().heap.pop()
+    }
+
+    /// Waits until an element is available and pops the minimum, or returns `None` once closed and empty.
+    pub fn pop_blocking(&self) -> Option<T> {
+        let mut state = self.lock();
+        loop {
+            if let Some(value) = state.heap.pop() {
+                return Some(value);
+            }
+            if state.closed {
+                return None;
+            }
+            state = self.inner.available.wait(state).unwrap();
+        }
+    }
+
+    /// Like [`pop_blocking`](Self::pop_blocking), but gives up with `None` after `timeout`; a timeout too large to be
+    /// a deadline waits like `pop_blocking`.
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<T> {
+        let Some(deadline) = Instant::now().checked_add(timeout) else {
+            return self.pop_blocking();
+        };
+        let mut state = self.lock();
+        let mut timed_out = false;
+        loop {
+            if let Some(value) = state.heap.pop() {
+                return Some(value);
+            }
+            let now = Instant::now();
+            if state.closed || timed_out || now >= deadline {
+                return None;
+            }
+            (state, timed_out) = lock::wait_timeout(&self.inner.available, &self.inner.state, state, deadline - now);
+        }
+    }
+
+    /// Wakes every waiter and stops blocking pops from waiting for new elements.
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.inner.available.notify_all();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.inner.state.lock().unwrap()
+    }
+}
+
+impl<T> Clone for SharedFibonacciHeap<T> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<T: PartialOrd> Default for SharedFibonacciHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialOrd> From<FibonacciHeap<T>> for SharedFibonacciHeap<T> {
+    fn from(heap: FibonacciHeap<T>) -> Self {
+        let state = Mutex::new(State { heap, closed: false });
+        Self { inner: Arc::new(Inner { state, available: Condvar::new() }) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn try_pop_and_timeout() {
+        let heap = SharedFibonacciHeap::from(FibonacciHeap::from_vec(vec![3, 1, 2]));
+        assert_eq!(heap.len(), 3);
+        assert_eq!(heap.try_pop(), Some(1));
+        assert_eq!(heap.pop_timeout(Duration::from_millis(10)), Some(2));
+        assert_eq!(heap.pop_timeout(Duration::from_millis(10)), Some(3));
+        assert_eq!(heap.pop_timeout(Duration::from_millis(10)), None);
+        assert_eq!(heap.try_pop(), None);
+    }
+
+    #[test]
+    fn unbounded_timeout() {
+        let heap = SharedFibonacciHeap::from(FibonacciHeap::from_vec(vec![2]));
+        assert_eq!(heap.pop_timeout(Duration::MAX), Some(2));
+        let pusher = {
+            let heap = heap.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(20));
+                heap.push(1);
+                heap.close();
+            })
+        };
+        assert_eq!(heap.pop_timeout(Duration::MAX), Some(1));
+        pusher.join().unwrap();
+        assert_eq!(heap.pop_timeout(Duration::MAX), None);
+    }
+
+    #[test]
+    fn close_wakes_waiters() {
+        let heap = SharedFibonacciHeap::<i32>::new();
+        let waiters: Vec<_> = (0..4)
+            .map(|_| {
+                let heap = heap.clone();
+                thread::spawn(move || heap.pop_blocking())
+            })
+            .collect();
+        thread::sleep(Duration::from_millis(20));
+        heap.close();
+        for waiter in waiters {
+            assert_eq!(waiter.join().unwrap(), None);
+        }
+        assert!(heap.is_closed());
+
+        heap.push(5);
+        assert_eq!(heap.pop_blocking(), Some(5));
+        assert_eq!(heap.pop_blocking(), None);
+    }
+
+    #[test]
+    fn producers_and_consumers_exactly_once() {
+        const PRODUCERS: usize = 4;
+        const CONSUMERS: usize = 4;
+        const PER_PRODUCER: usize = 5000;
+
+        let heap = SharedFibonacciHeap::new();
+        let consumers: Vec<_> = (0..CONSUMERS)
+            .map(|_| {
+                let heap = heap.clone();
+                thread::spawn(move || {
+                    let mut popped = vec![];
+                    while let Some(value) = heap.pop_blocking() {
+                        popped.push(value);
+                    }
+                    popped
+                })
+            })
+            .collect();
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|p| {
+                let heap = heap.clone();
+                thread::spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        heap.push(i * PRODUCERS + p);
+                    }
+                })
+            })
+            .collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        heap.close();
+
+        let mut popped: Vec<_> = consumers.into_iter().flat_map(|c| c.join().unwrap()).collect();
+        popped.sort_unstable();
+        assert_eq!(popped, (0..PRODUCERS * PER_PRODUCER).collect::<Vec<_>>());
+        assert!(heap.is_empty());
+    }
+}
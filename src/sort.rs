@@ -0,0 +1,208 @@
+use alloc::vec::Vec;
+use core::cmp::{Ordering, Reverse};
+
+use crate::FibonacciHeap;
+
+/// Sorts `vec` in ascending order by bulk-building a heap and draining it.
+///
+/// The sort is not stable.
+///
+/// # Example
+/// ```
+/// assert_eq!(fibheap::sort(vec![3, 1, 2]), [1, 2, 3]);
+/// ```
+pub fn sort<T: PartialOrd>(vec: Vec<T>) -> Vec<T> {
+    FibonacciHeap::from_vec(vec).into_sorted_vec()
+}
+
+/// Sorts `slice` in ascending order; equal elements keep their relative order.
+///
+/// The heap orders references into the slice, and the resulting permutation is applied with swaps,
+/// so no element is ever duplicated even if a comparison panics.
+///
+/// # Example
+/// ```
+/// let mut words = ["pear", "fig", "apple"];
+/// fibheap::sort_in_place(&mut words);
+/// assert_eq!(words, ["apple", "fig", "pear"]);
+/// ```
+pub fn sort_in_place<T: PartialOrd>(slice: &mut [T]) {
+    let heap = FibonacciHeap::from_vec(slice.iter().enumerate().map(|(index, value)| Indexed { value, index }).collect());
+    let order: Vec<usize> = heap.map(|entry| entry.index).collect();
+    permute(slice, order);
+}
+
+/// Rearranges `slice` so that its first `k` positions hold the `k` smallest elements in ascending order.
+///
+/// The order of the remaining elements is unspecified.
+/// A heap bounded to `k` elements selects the prefix in O(n log k), so this beats a full sort when `k` is much smaller than the slice.
+///
+/// # Example
+/// ```
+/// let mut latencies = [40, 7, 12, 3, 25, 9];
+/// fibheap::partial_sort(&mut latencies, 3);
+/// assert_eq!(latencies[..3], [3, 7, 9]);
+/// ```
+pub fn partial_sort<T: PartialOrd>(slice: &mut [T], k: usize) {
+    if k >= slice.len() {
+        return sort_in_place(slice);
+    }
+    let mut heap = FibonacciHeap::new();
+    for (index, value) in slice.iter().enumerate() {
+        heap.push_bounded(Reverse(Indexed { value, index }), k);
+    }
+    let mut chosen: Vec<usize> = heap.map(|Reverse(entry)| entry.index).collect();
+    chosen.sort_unstable();
+
+    // Swap the chosen elements found past the prefix into the prefix slots not already holding chosen ones.
+    let inside = chosen.partition_point(|&index| index < k);
+    let mut taken = chosen[..inside].iter().peekable();
+    let free = (0..k).filter(|&slot| taken.next_if_eq(&&slot).is_none());
+    for (slot, &index) in free.zip(&chosen[inside..]) {
+        slice.swap(slot, index);
+    }
+    sort_in_place(&mut slice[..k]);
+}
+
+// Rearranges `slice` so that position `i` receives the element previously at `order[i]`.
+pub(crate) fn permute<T>(slice: &mut [T], mut order: Vec<usize>) {
+    for start in 0..order.len() {
+        let mut cur = start;
+        // Each visited position is marked by pointing at itself, which also closes the cycle at `start`.
+        loop {
+            let src = core::mem::replace(&mut order[cur], cur);
+            if src == start || src == cur {
+                break;
+            }
+            slice.swap(cur, src);
+            cur = src;
+        }
+    }
+}
+
+// Element reference ordered by value, with ties broken by position so that sorting is stable.
+pub(crate) struct Indexed<'a, T> {
+    pub(crate) value: &'a T,
+    pub(crate) index: usize,
+}
+
+impl<T: PartialOrd> PartialEq for Indexed<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for Indexed<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.value.partial_cmp(other.value)? {
+            Ordering::Equal => Some(self.index.cmp(&other.index)),
+            ord => Some(ord),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+
+    #[test]
+    fn empty_and_single() {
+        assert!(sort(Vec::<i32>::new()).is_empty());
+        assert_eq!(sort(vec![1]), [1]);
+        let mut empty: [i32; 0] = [];
+        sort_in_place(&mut empty);
+        let mut single = [1];
+        sort_in_place(&mut single);
+        assert_eq!(single, [1]);
+    }
+
+    #[test]
+    fn matches_slice_sort() {
+        let mut rng = Rng(0x853c49e6748fea9b);
+        for _ in 0..200 {
+            let len = rng.below(300) as usize;
+            let range = rng.below(1000) + 1;
+            let values: Vec<u64> = (0..len).map(|_| rng.below(range)).collect();
+            let mut expected = values.clone();
+            expected.sort();
+
+            assert_eq!(sort(values.clone()), expected);
+            let mut in_place = values;
+            sort_in_place(&mut in_place);
+            assert_eq!(in_place, expected);
+        }
+    }
+
+    #[test]
+    fn sort_in_place_is_stable() {
+        let mut rng = Rng(0xda942042e4dd58b5);
+        let mut pairs: Vec<(u64, usize)> = (0..2000).map(|i| (rng.below(10), i)).collect();
+        let mut expected = pairs.clone();
+        expected.sort_by_key(|&(key, _)| key);
+
+        struct ByKey((u64, usize));
+        impl PartialEq for ByKey {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 .0 == other.0 .0
+            }
+        }
+        impl PartialOrd for ByKey {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                self.0 .0.partial_cmp(&other.0 .0)
+            }
+        }
+        let mut keyed: Vec<ByKey> = pairs.drain(..).map(ByKey).collect();
+        sort_in_place(&mut keyed);
+        assert_eq!(keyed.into_iter().map(|k| k.0).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn partial_sort_matches_full_sort_prefix() {
+        let mut rng = Rng(0x4f1bbcdcbfa53e0a);
+        for _ in 0..300 {
+            let len = rng.below(200) as usize;
+            let range = rng.below(50) + 1;
+            let values: Vec<u64> = (0..len).map(|_| rng.below(range)).collect();
+            let k = rng.below(len as u64 + 5) as usize;
+            let mut expected = values.clone();
+            expected.sort();
+
+            let mut partial = values;
+            partial_sort(&mut partial, k);
+            let k = k.min(len);
+            assert_eq!(partial[..k], expected[..k]);
+            partial[k..].sort();
+            assert_eq!(partial, expected);
+        }
+    }
+
+    #[test]
+    fn partial_sort_edge_cases() {
+        let mut values = [3, 1, 2];
+        partial_sort(&mut values, 0);
+        assert_eq!(values, [3, 1, 2]);
+        partial_sort(&mut values, 10);
+        assert_eq!(values, [1, 2, 3]);
+
+        let mut equal = vec![5u8; 1000];
+        equal.push(1);
+        partial_sort(&mut equal, 3);
+        assert_eq!(equal[..3], [1, 5, 5]);
+    }
+
+    #[test]
+    fn degenerate_inputs() {
+        let mut sorted: Vec<u32> = (0..100_000).collect();
+        sort_in_place(&mut sorted);
+        assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+
+        let mut reversed: Vec<u32> = (0..100_000).rev().collect();
+        sort_in_place(&mut reversed);
+        assert_eq!(reversed, sorted);
+
+        let mut equal = vec![7u8; 100_000];
+        sort_in_place(&mut equal);
+        assert!(equal.iter().all(|&v| v == 7));
+    }
+}
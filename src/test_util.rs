@@ -0,0 +1,424 @@
+//! Assertions and workloads for testing code that embeds heaps, enabled by the `test-util` feature.
+//!
+//! [`assert_heap_eq!`](crate::assert_heap_eq) compares the contents of a heap with the elements expected,
+//! [`assert_valid!`](crate::assert_valid) checks a [`FibonacciHeap`]'s invariants, [`workload`] generates
+//! reproducible operation sequences for soak tests, and [`Tracker`] counts the values it hands out to catch leaks
+//! and double drops. Failures panic with a message meant to be read as is: the
+//! difference between the contents and the expected elements, or the violated invariant with the forest in DOT
+//! format.
+//!
+//! # Example
+//! ```
+//! use fibheap::test_util::workload;
+//! use fibheap::{assert_heap_eq, assert_valid, replay, FibonacciHeap};
+//!
+//! let mut heap = FibonacciHeap::from_vec(vec![3, 1, 2]);
+//! heap.pop();
+//! assert_valid!(heap);
+//! assert_heap_eq!(heap, [2, 3]);
+//!
+//! for seed in 0..10 {
+//!     let heap = replay(&workload(seed, 200));
+//!     assert_valid!(heap, "seed {seed}");
+//! }
+//! ```
+
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::cmp::Ordering;
+use core::fmt::{self, Write};
+
+use crate::{FibonacciHeap, Heap, Op};
+
+/// Asserts that a heap holds exactly the given elements, listed in ascending order.
+///
+/// The heap is cloned and the clone drained, so the order its elements come out in is checked too. On failure the
+/// message shows the first position where the drained elements differ from the expected ones, and which elements
+/// are missing or unexpected. Works with every [`Heap`](crate::Heap) that is `Clone`; an optional message in
+/// `format!` syntax can follow.
+///
+/// ```should_panic
+/// use fibheap::{assert_heap_eq, FibonacciHeap};
+///
+/// let heap = FibonacciHeap::from_vec(vec![1, 3, 4]);
+/// // panics: position 1 holds 3 where 2 was expected; 2 is missing and 4 unexpected
+/// assert_heap_eq!(heap, [1, 2, 3]);
+/// ```
+#[macro_export]
+macro_rules! assert_heap_eq {
+    ($heap:expr, $expected:expr $(,)?) => {
+        $crate::test_util::assert_heap_eq(&$heap, &$expected[..], ::core::option::Option::None)
+    };
+    ($heap:expr, $expected:expr, $($arg:tt)+) => {
+        $crate::test_util::assert_heap_eq(&$heap, &$expected[..], ::core::option::Option::Some(format_args!($($arg)+)))
+    };
+}
+
+/// Asserts that a [`FibonacciHeap`](crate::FibonacciHeap) passes [`validate`](crate::FibonacciHeap::validate).
+///
+/// On failure the message names the violated invariant and renders the forest with
+/// [`to_dot_with`](crate::FibonacciHeap::to_dot_with), labeling nodes with their `Debug` form. An optional message
+/// in `format!` syntax can follow.
+#[macro_export]
+macro_rules! assert_valid {
+    ($heap:expr $(,)?) => {
+        $crate::test_util::assert_valid(&$heap, ::core::option::Option::None)
+    };
+    ($heap:expr, $($arg:tt)+) => {
+        $crate::test_util::assert_valid(&$heap, ::core::option::Option::Some(format_args!($($arg)+)))
+    };
+}
+
+/// Implementation of [`assert_heap_eq!`](crate::assert_heap_eq).
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_heap_eq<T, H>(heap: &H, expected: &[T], message: Option<fmt::Arguments<'_>>)
+where
+    T: PartialOrd + fmt::Debug,
+    H: Heap<T> + Clone,
+{
+    let mut drained = Vec::with_capacity(heap.len());
+    let mut heap = heap.clone();
+    while let Some(value) = heap.pop() {
+        drained.push(value);
+    }
+    let Some(at) = first_difference(&drained, expected) else {
+        return;
+    };
+    let mut report = String::new();
+    if let Some(message) = message {
+        let _ = writeln!(report, "{message}");
+    }
+    let _ = writeln!(report, "heap contents differ from the expected elements at position {at}");
+    let _ = writeln!(report, "  heap     ({} elements): {}", drained.len(), excerpt(&drained, at));
+    let _ = writeln!(report, "  expected ({} elements): {}", expected.len(), excerpt(expected, at));
+    let (missing, unexpected) = multiset_difference(&drained, expected);
+    let _ = writeln!(report, "  missing from the heap: {}", excerpt(&missing, 0));
+    let _ = write!(report, "  unexpected in the heap: {}", excerpt(&unexpected, 0));
+    panic!("{report}");
+}
+
+/// Implementation of [`assert_valid!`](crate::assert_valid).
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_valid<T: PartialOrd + fmt::Debug>(heap: &FibonacciHeap<T>, message: Option<fmt::Arguments<'_>>) {
+    if let Err(violation) = heap.validate() {
+        let dot = heap.to_dot_with(|value| format!("{value:?}"));
+        match message {
+            Some(message) => panic!("{message}\nheap is invalid: {violation}\n{dot}"),
+            None => panic!("heap is invalid: {violation}\n{dot}"),
+        }
+    }
+}
+
+/// A reproducible mix of `len` operations on a heap of `u64`, for soak tests; run it with
+/// [`replay`](crate::replay) or apply the operations to the heap under test.
+///
+/// The same `seed` always gives the same operations. Pushes outnumber pops, so the heap grows over the run, and
+/// values are drawn from a range narrow enough for duplicates to be common. The mix covers every [`Op`]: pushes,
+/// bulk pushes, pops, `push_pop`, `replace_top`, appends of heaps built from short workloads of their own, and
+/// occasional consolidations, clears, link budgets and switches of eager consolidation.
+pub fn workload(seed: u64, len: usize) -> Vec<Op<u64>> {
+    // xorshift gets stuck at zero
+    let mut rng = Rng(seed.wrapping_mul(0x9e3779b97f4a7c15) | 1);
+    (0..len).map(|_| random_op(&mut rng, 2)).collect()
+}
+
+// `nesting` bounds how deep appended heaps may themselves contain appends.
+fn random_op(rng: &mut Rng, nesting: u32) -> Op<u64> {
+    let value = rng.below(1000);
+    match rng.below(100) {
+        0..=39 => Op::Push(value),
+        40..=44 => Op::BulkPush((0..rng.below(20)).map(|_| rng.below(1000)).collect()),
+        45..=69 => Op::Pop,
+        70..=79 => Op::PushPop(value),
+        80..=89 => Op::ReplaceTop(value),
+        90..=94 if nesting > 0 => Op::Append((0..rng.below(30)).map(|_| random_op(rng, nesting - 1)).collect()),
+        95 => Op::Consolidate,
+        96 if rng.below(10) == 0 => Op::Clear,
+        97 => Op::SetMaxLinksPerPop([None, Some(1), Some(16)][rng.below(3) as usize]),
+        98 => Op::SetEagerConsolidation(rng.below(4) == 0),
+        _ => Op::Push(value),
+    }
+}
+
+/// Counts the [`Tracked`] values created and dropped, so that tests can check a heap neither leaks nor drops twice,
+/// and can make their clones or comparisons panic to exercise the unwinding paths.
+///
+/// Every value made by [`track`](Self::track) or by cloning a tracked value counts as created, and every drop of
+/// one as dropped; once all of them are gone the two counts must be equal.
+///
+/// # Example
+/// ```
+/// use fibheap::test_util::Tracker;
+/// use fibheap::FibonacciHeap;
+///
+/// let tracker = Tracker::new();
+/// let mut heap = FibonacciHeap::from_vec((0..10).map(|value| tracker.track(value)).collect());
+/// heap.pop();
+/// tracker.panic_on_comparison(1);
+/// let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| heap.pop()));
+/// assert!(result.is_err());
+/// tracker.defuse();
+/// drop(heap);
+/// tracker.assert_balanced();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Tracker(Rc<Counts>);
+
+#[derive(Debug, Default)]
+struct Counts {
+    created: Cell<usize>,
+    dropped: Cell<usize>,
+    // clones or comparisons left until the one that panics, 0 when disarmed
+    clone_fuse: Cell<usize>,
+    comparison_fuse: Cell<usize>,
+}
+
+impl Tracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps `value`, counting it as created.
+    pub fn track<T>(&self, value: T) -> Tracked<T> {
+        self.0.created.set(self.0.created.get() + 1);
+        Tracked { value, counts: Rc::clone(&self.0) }
+    }
+
+    pub fn created(&self) -> usize {
+        self.0.created.get()
+    }
+
+    pub fn dropped(&self) -> usize {
+        self.0.dropped.get()
+    }
+
+    /// Values created and not yet dropped.
+    pub fn live(&self) -> usize {
+        self.created().wrapping_sub(self.dropped())
+    }
+
+    /// Makes the `n`th clone of a tracked value from now on panic, counting from 1.
+    pub fn panic_on_clone(&self, n: usize) {
+        self.0.clone_fuse.set(n);
+    }
+
+    /// Makes the `n`th comparison between tracked values from now on panic, counting from 1.
+    pub fn panic_on_comparison(&self, n: usize) {
+        self.0.comparison_fuse.set(n);
+    }
+
+    /// Disarms both [`panic_on_clone`](Self::panic_on_clone) and [`panic_on_comparison`](Self::panic_on_comparison).
+    pub fn defuse(&self) {
+        self.0.clone_fuse.set(0);
+        self.0.comparison_fuse.set(0);
+    }
+
+    /// Asserts that every value created has been dropped exactly once.
+    #[track_caller]
+    pub fn assert_balanced(&self) {
+        let (created, dropped) = (self.created(), self.dropped());
+        assert!(created == dropped, "{created} tracked values created but {dropped} dropped");
+    }
+}
+
+impl Counts {
+    #[track_caller]
+    fn burn(fuse: &Cell<usize>, what: &str) {
+        match fuse.get() {
+            0 => {}
+            1 => {
+                fuse.set(0);
+                panic!("tracked {what} failed as configured");
+            }
+            left => fuse.set(left - 1),
+        }
+    }
+}
+
+/// A value handed out by a [`Tracker`], which counts its clones and drops and can make them or its comparisons
+/// panic. Compares as the value it wraps.
+pub struct Tracked<T> {
+    pub value: T,
+    counts: Rc<Counts>,
+}
+
+impl<T: Clone> Clone for Tracked<T> {
+    fn clone(&self) -> Self {
+        Counts::burn(&self.counts.clone_fuse, "clone");
+        self.counts.created.set(self.counts.created.get() + 1);
+        Self { value: self.value.clone(), counts: Rc::clone(&self.counts) }
+    }
+}
+
+impl<T> Drop for Tracked<T> {
+    fn drop(&mut self) {
+        self.counts.dropped.set(self.counts.dropped.get() + 1);
+    }
+}
+
+impl<T: PartialEq> PartialEq for Tracked<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Counts::burn(&self.counts.comparison_fuse, "comparison");
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for Tracked<T> {}
+
+impl<T: PartialOrd> PartialOrd for Tracked<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Counts::burn(&self.counts.comparison_fuse, "comparison");
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: Ord> Ord for Tracked<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Counts::burn(&self.counts.comparison_fuse, "comparison");
+        self.value.cmp(&other.value)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Tracked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+// Index of the first element that differs, or of the end of the shorter slice if one is a prefix of the other.
+fn first_difference<T: PartialOrd>(actual: &[T], expected: &[T]) -> Option<usize> {
+    let common = actual.iter().zip(expected).position(|(a, b)| a != b);
+    common.or((actual.len() != expected.len()).then(|| actual.len().min(expected.len())))
+}
+
+// Elements of `expected` that `actual` lacks, and elements of `actual` beyond those in `expected`, counting duplicates.
+fn multiset_difference<'a, T: PartialOrd>(actual: &'a [T], expected: &'a [T]) -> (Vec<&'a T>, Vec<&'a T>) {
+    let sorted = |values: &'a [T]| {
+        let mut values: Vec<&T> = values.iter().collect();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        values
+    };
+    let (actual, expected) = (sorted(actual), sorted(expected));
+    let (mut missing, mut unexpected) = (vec![], vec![]);
+    let (mut a, mut e) = (actual.into_iter().peekable(), expected.into_iter().peekable());
+    loop {
+        match (a.peek(), e.peek()) {
+            (Some(x), Some(y)) if x < y => unexpected.push(a.next().unwrap()),
+            (Some(x), Some(y)) if y < x => missing.push(e.next().unwrap()),
+            (Some(_), Some(_)) => {
+                a.next();
+                e.next();
+            }
+            (Some(_), None) => unexpected.push(a.next().unwrap()),
+            (None, Some(_)) => missing.push(e.next().unwrap()),
+            (None, None) => return (missing, unexpected),
+        }
+    }
+}
+
+// Up to `EXCERPT` elements on each side of `at`, eliding the rest.
+fn excerpt<T: fmt::Debug>(values: &[T], at: usize) -> String {
+    const EXCERPT: usize = 8;
+    let start = at.saturating_sub(EXCERPT);
+    let end = values.len().min(at + EXCERPT);
+    let mut text = String::from("[");
+    if start > 0 {
+        let _ = write!(text, "… {start} more, ");
+    }
+    for (ix, value) in values[start.min(end)..end].iter().enumerate() {
+        let sep = if ix == 0 { "" } else { ", " };
+        let _ = write!(text, "{sep}{value:?}");
+    }
+    if end < values.len() {
+        let _ = write!(text, ", … {} more", values.len() - end);
+    }
+    text.push(']');
+    text
+}
+
+// xorshift64, good enough to drive randomized tests deterministically
+pub(crate) struct Rng(pub(crate) u64);
+
+impl Rng {
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub(crate) fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    #[cfg(test)]
+    pub(crate) fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            slice.swap(i, self.below(i as u64 + 1) as usize);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic;
+
+    fn panic_message(f: impl FnOnce() + panic::UnwindSafe) -> String {
+        let payload = panic::catch_unwind(f).unwrap_err();
+        payload.downcast_ref::<String>().cloned().unwrap_or_default()
+    }
+
+    #[test]
+    fn reports_the_difference() {
+        let heap = FibonacciHeap::from_vec(vec![1, 3, 4, 4]);
+        assert_heap_eq!(heap, [1, 3, 4, 4]);
+        let message = panic_message(|| assert_heap_eq!(heap, vec![1, 2, 3, 4], "case {}", 7));
+        assert_eq!(
+            message,
+            "case 7\n\
+             heap contents differ from the expected elements at position 1\n  \
+             heap     (4 elements): [1, 3, 4, 4]\n  \
+             expected (4 elements): [1, 2, 3, 4]\n  \
+             missing from the heap: [2]\n  \
+             unexpected in the heap: [4]"
+        );
+        let long: Vec<u32> = (0..100).collect();
+        let message = panic_message(|| assert_heap_eq!(FibonacciHeap::from_vec(long[1..].to_vec()), long));
+        assert!(message.contains("heap     (99 elements): [1, 2, 3, 4, 5, 6, 7, 8, … 91 more]"), "{message}");
+        assert!(message.contains("missing from the heap: [0]\n  unexpected in the heap: []"), "{message}");
+    }
+
+    #[test]
+    fn reports_invalid_heaps_with_their_forest() {
+        let mut heap = FibonacciHeap::from_vec(vec![2, 1]);
+        heap.consolidate();
+        assert_valid!(heap);
+        // an inconsistent order gets past the heap's checks but not past `validate`
+        heap.roots[0].children[0].value = 0;
+        let message = panic_message(|| assert_valid!(heap, "after {}", "tampering"));
+        assert!(message.starts_with("after tampering\nheap is invalid: node at [0, 0] is smaller than its parent"));
+        assert!(message.contains("digraph FibonacciHeap {") && message.contains("n1 [label=\"0\\ndegree 0\""), "{message}");
+    }
+
+    #[test]
+    fn workloads_are_reproducible() {
+        assert_eq!(workload(7, 500), workload(7, 500));
+        assert_ne!(workload(7, 500), workload(8, 500));
+        assert_eq!(workload(0, 10).len(), 10);
+        let ops = workload(1, 5000);
+        let count = |kind: fn(&Op<u64>) -> bool| ops.iter().filter(|op| kind(op)).count();
+        assert!(count(|op| matches!(op, Op::Append(_))) > 0);
+        assert!(count(|op| matches!(op, Op::SetEagerConsolidation(_))) > 0);
+        assert!(count(|op| matches!(op, Op::Push(_))) > count(|op| matches!(op, Op::Pop)));
+        let heap = crate::replay(&ops);
+        assert_valid!(heap);
+        assert!(!heap.is_empty());
+    }
+}
@@ -0,0 +1,37 @@
+// Helpers shared by the unit tests of every module.
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+pub(crate) use crate::test_util::Rng;
+
+// Clones share the counter of the original.
+#[derive(Debug, Clone)]
+pub(crate) struct DropCounter {
+    pub(crate) value: i32,
+    drops: Rc<Cell<usize>>,
+}
+
+impl DropCounter {
+    pub(crate) fn new(value: i32, drops: &Rc<Cell<usize>>) -> Self {
+        Self { value, drops: Rc::clone(drops) }
+    }
+}
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+impl PartialEq for DropCounter {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl PartialOrd for DropCounter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
@@ -0,0 +1,198 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::iter;
+use std::time::Instant;
+
+use crate::FibonacciHeap;
+
+/// Identifies an entry scheduled on a [`TimerQueue`] so that it can be cancelled.
+///
+/// Keys of one queue are ordered by when their entries were scheduled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimerKey(u64);
+
+#[derive(Debug)]
+struct Entry<T> {
+    deadline: Instant,
+    key: u64,
+    value: T,
+}
+
+// Entries are ordered by deadline and then by scheduling order; the payload is never compared.
+impl<T> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline && self.key == other.key
+    }
+}
+
+impl<T> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some((self.deadline, self.key).cmp(&(other.deadline, other.key)))
+    }
+}
+
+/// Queue of values that become due at a deadline.
+///
+/// Entries with identical deadlines fire in the order they were scheduled.
+/// Cancellation is lazy: a cancelled entry stays in the underlying heap and is dropped once it reaches the front,
+/// so the front of the queue is always a live entry.
+///
+/// # Example
+/// ```
+/// use std::time::{Duration, Instant};
+/// use fibheap::TimerQueue;
+///
+/// let start = Instant::now();
+/// let mut timers = TimerQueue::new();
+/// timers.schedule(start + Duration::from_millis(20), "second");
+/// let key = timers.schedule(start + Duration::from_millis(5), "cancelled");
+/// timers.schedule(start + Duration::from_millis(10), "first");
+/// timers.cancel(key);
+///
+/// assert_eq!(timers.next_deadline(), Some(start + Duration::from_millis(10)));
+/// let fired: Vec<_> = timers.drain_expired(start + Duration::from_millis(30)).collect();
+/// assert_eq!(fired, ["first", "second"]);
+/// ```
+#[derive(Debug)]
+pub struct TimerQueue<T> {
+    heap: FibonacciHeap<Entry<T>>,
+    pending: HashSet<u64>,
+    next_key: u64,
+}
+
+impl<T> TimerQueue<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of scheduled entries that have neither fired nor been cancelled.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    pub fn schedule(&mut self, deadline: Instant, value: T) -> TimerKey {
+        let key = self.next_key;
+        self.next_key += 1;
+        self.pending.insert(key);
+        self.heap.push(Entry { deadline, key, value });
+        TimerKey(key)
+    }
+
+    /// Cancels a pending entry, returning `false` if it has already fired or been cancelled.
+    ///
+    /// The cancelled value is dropped once it reaches the front of the queue, or when the queue is dropped.
+    pub fn cancel(&mut self, key: TimerKey) -> bool {
+        let cancelled = self.pending.remove(&key.0);
+        self.skip_cancelled();
+        cancelled
+    }
+
+    /// The earliest pending deadline, i.e. how long the caller may sleep.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.heap.peek().map(|entry| entry.deadline)
+    }
+
+    /// Pops the earliest entry if its deadline is not later than `now`.
+    pub fn pop_expired(&mut self, now: Instant) -> Option<T> {
+        if self.next_deadline()? > now {
+            return None;
+        }
+        let Entry { key, value, .. } = self.heap.pop().unwrap();
+        self.pending.remove(&key);
+        self.skip_cancelled();
+        Some(value)
+    }
+
+    /// Pops every entry whose deadline is not later than `now`, in firing order.
+    pub fn drain_expired(&mut self, now: Instant) -> impl Iterator<Item = T> + '_ {
+        iter::from_fn(move || self.pop_expired(now))
+    }
+
+    fn skip_cancelled(&mut self) {
+        while matches!(self.heap.peek(), Some(entry) if !self.pending.contains(&entry.key)) {
+            self.heap.pop();
+        }
+    }
+}
+
+impl<T> Default for TimerQueue<T> {
+    fn default() -> Self {
+        Self { heap: FibonacciHeap::new(), pending: HashSet::new(), next_key: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn empty() {
+        let mut timers = TimerQueue::<()>::new();
+        assert!(timers.is_empty());
+        assert_eq!(timers.next_deadline(), None);
+        assert_eq!(timers.pop_expired(Instant::now()), None);
+    }
+
+    #[test]
+    fn identical_deadlines_fire_in_insertion_order() {
+        let now = Instant::now();
+        let mut timers = TimerQueue::new();
+        for i in 0..100 {
+            timers.schedule(now, i);
+        }
+        assert_eq!(timers.drain_expired(now).collect::<Vec<_>>(), (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn cancel() {
+        let now = Instant::now();
+        let mut timers = TimerQueue::new();
+        let first = timers.schedule(now, 'a');
+        let second = timers.schedule(now + Duration::from_secs(1), 'b');
+        assert!(timers.cancel(first));
+        assert!(!timers.cancel(first));
+        assert_eq!(timers.len(), 1);
+        assert_eq!(timers.next_deadline(), Some(now + Duration::from_secs(1)));
+
+        assert_eq!(timers.pop_expired(now + Duration::from_secs(1)), Some('b'));
+        assert!(!timers.cancel(second));
+        assert!(timers.is_empty());
+        assert_eq!(timers.next_deadline(), None);
+    }
+
+    #[test]
+    fn simulated_clock() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut timers = TimerQueue::new();
+        let mut keys = vec![];
+        for (ms, name) in [(30, "c1"), (10, "a"), (30, "c2"), (50, "e"), (20, "b"), (40, "d"), (30, "c3")] {
+            keys.push((name, timers.schedule(at(ms), name)));
+        }
+        let key_of = |name| keys.iter().find(|(n, _)| *n == name).unwrap().1;
+        assert!(timers.cancel(key_of("d")));
+
+        let mut fired = vec![];
+        let mut clock = 0;
+        while let Some(deadline) = timers.next_deadline() {
+            // Sleep exactly until the next deadline, in 5ms ticks.
+            while at(clock) < deadline {
+                assert_eq!(timers.pop_expired(at(clock)), None);
+                clock += 5;
+            }
+            for name in timers.drain_expired(at(clock)) {
+                fired.push((clock, name));
+            }
+            if clock == 20 {
+                timers.schedule(at(25), "late");
+                assert!(timers.cancel(key_of("c2")));
+            }
+        }
+        assert_eq!(fired, [(10, "a"), (20, "b"), (25, "late"), (30, "c1"), (30, "c3"), (50, "e")]);
+    }
+}
@@ -0,0 +1,219 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::FibonacciHeap;
+
+/// Min-heap with O(1) lazy removal: [`mark_removed`](Self::mark_removed) records a tombstone, and the element it
+/// stands for is discarded once it reaches the top instead of being searched for.
+///
+/// Tombstones are kept in a second heap, and an element is dead while a tombstone equal to it is waiting.
+/// Whenever the smallest element and the smallest tombstone are equal, both are dropped, right in the operation
+/// that brought them together (`push`, `pop`, `mark_removed` or `compact`), so the minimum is always live and
+/// [`peek`](Self::peek) needs no `&mut self`. [`compact`](Self::compact) drops every tombstone with its element
+/// at once; it also runs by itself when tombstones outnumber live elements.
+///
+/// Only elements that are in the heap may be marked. A tombstone for an element smaller than the minimum is
+/// dropped right away, but a tombstone for a missing element above it waits, and removes an equal element pushed
+/// later, or is dropped by the next compaction.
+///
+/// # Example
+/// ```
+/// use fibheap::TombstoneFibonacciHeap;
+///
+/// let mut heap = TombstoneFibonacciHeap::new();
+/// heap.extend([5, 1, 3, 4]);
+/// assert!(heap.mark_removed(4));
+/// assert!(heap.mark_removed(1));
+/// // 1 was the minimum and is gone already, 4 is still stored
+/// assert_eq!((heap.len(), heap.garbage_len()), (2, 1));
+/// assert_eq!(heap.peek(), Some(&3));
+/// assert_eq!(heap.pop(), Some(3));
+/// assert_eq!(heap.garbage_len(), 0);
+/// assert_eq!(heap.pop(), Some(5));
+/// ```
+#[derive(Debug)]
+pub struct TombstoneFibonacciHeap<T> {
+    heap: FibonacciHeap<T>,
+    removed: FibonacciHeap<T>,
+}
+
+// Tombstones are compacted away once there are more of them than live elements, and at least this many.
+const COMPACT_MIN_GARBAGE: usize = 64;
+
+impl<T: PartialOrd> TombstoneFibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of live elements, assuming every tombstone stands for an element of the heap.
+    pub fn len(&self) -> usize {
+        self.heap.len().saturating_sub(self.removed.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of tombstones waiting for their element to reach the top, which is also the number of dead elements
+    /// still stored.
+    pub fn garbage_len(&self) -> usize {
+        self.removed.len()
+    }
+
+    /// Returns the smallest live element, or `None` if there is none.
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.heap.push(value);
+        self.drop_dead_top();
+    }
+
+    /// Removes and returns the smallest live element, dropping the dead ones that come before it.
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.heap.pop();
+        self.drop_dead_top();
+        value
+    }
+
+    /// Marks one element equal to `value` as removed, in O(1).
+    ///
+    /// Returns `false` if no element can be equal to `value` because it is smaller than the minimum, or the heap
+    /// is empty; `value` is dropped then. Otherwise the element is dead from now on and `len()` no longer counts it.
+    pub fn mark_removed(&mut self, value: T) -> bool {
+        match self.heap.peek() {
+            Some(top) if value >= *top => {}
+            _ => return false,
+        }
+        self.removed.push(value);
+        self.drop_dead_top();
+        if self.removed.len() >= COMPACT_MIN_GARBAGE && self.removed.len() > self.len() {
+            self.compact();
+        }
+        true
+    }
+
+    /// Drops every tombstone together with its element, rebuilding the heap from the live ones.
+    ///
+    /// This sorts the elements and the tombstones to match them up, O(n log n), and drops the tombstones that
+    /// match no element as well.
+    pub fn compact(&mut self) {
+        if self.removed.is_empty() {
+            return;
+        }
+        let mut removed = self.removed.take_values();
+        removed.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        let mut values = self.heap.take_values();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        let mut removed = removed.into_iter().peekable();
+        let mut live = Vec::with_capacity(values.len());
+        for value in values {
+            while removed.next_if(|dead| *dead < value).is_some() {}
+            if removed.next_if(|dead| *dead == value).is_none() {
+                live.push(value);
+            }
+        }
+        self.heap.bulk_push(live);
+    }
+
+    // Drops the minimum with a matching tombstone for as long as there is one, and tombstones below the minimum,
+    // which can't match anything.
+    fn drop_dead_top(&mut self) {
+        while let Some(dead) = self.removed.peek() {
+            let matches = match self.heap.peek() {
+                Some(top) if dead > top => break,
+                top => top == Some(dead),
+            };
+            if matches {
+                self.heap.pop();
+            }
+            self.removed.pop();
+        }
+    }
+}
+
+impl<T: PartialOrd> Extend<T> for TombstoneFibonacciHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.heap.bulk_push(iter);
+        self.drop_dead_top();
+    }
+}
+
+impl<T> Default for TombstoneFibonacciHeap<T> {
+    fn default() -> Self {
+        Self { heap: FibonacciHeap::new(), removed: FibonacciHeap::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+    use alloc::vec;
+
+    #[test]
+    fn matches_eager_removal() {
+        let mut rng = Rng(0xbb67ae8584caa73b);
+        let mut lazy = TombstoneFibonacciHeap::new();
+        let mut eager: FibonacciHeap<u64> = FibonacciHeap::new();
+        // live elements, for picking one to cancel
+        let mut live: Vec<u64> = vec![];
+        let (mut compacted, mut max_garbage) = (false, 0);
+        for step in 0..20_000 {
+            match rng.below(4) {
+                // half of the operations cancel an element
+                0 | 1 if !live.is_empty() => {
+                    let value = live.swap_remove(rng.below(live.len() as u64) as usize);
+                    let garbage = lazy.garbage_len();
+                    assert!(lazy.mark_removed(value));
+                    compacted |= lazy.garbage_len() + 1 < garbage;
+                    assert!(eager.remove_by(|x| x.partial_cmp(&value)).is_some());
+                }
+                2 if step % 3 == 0 => {
+                    let popped = lazy.pop();
+                    assert_eq!(popped, eager.pop(), "step {step}");
+                    if let Some(value) = popped {
+                        live.swap_remove(live.iter().position(|&x| x == value).unwrap());
+                    }
+                }
+                _ => {
+                    let value = rng.below(10_000);
+                    lazy.push(value);
+                    eager.push(value);
+                    live.push(value);
+                }
+            }
+            assert_eq!(lazy.len(), eager.len());
+            assert_eq!(lazy.peek(), eager.peek());
+            max_garbage = max_garbage.max(lazy.garbage_len());
+        }
+        assert!(compacted, "garbage never went past {max_garbage}");
+        lazy.compact();
+        assert_eq!(lazy.garbage_len(), 0);
+        let mut drained = vec![];
+        while let Some(value) = lazy.pop() {
+            drained.push(value);
+        }
+        assert_eq!(drained, eager.into_sorted_vec());
+    }
+
+    #[test]
+    fn tombstones_for_missing_elements() {
+        let mut heap = TombstoneFibonacciHeap::new();
+        assert!(!heap.mark_removed(1));
+        heap.extend([10, 20, 30]);
+        assert!(!heap.mark_removed(5));
+        // 15 isn't there: its tombstone waits, then is dropped on its way past the minimum
+        assert!(heap.mark_removed(15));
+        assert_eq!(heap.garbage_len(), 1);
+        assert_eq!(heap.pop(), Some(10));
+        assert_eq!((heap.garbage_len(), heap.len()), (0, 2));
+        assert!(heap.mark_removed(25));
+        heap.compact();
+        assert_eq!((heap.garbage_len(), heap.len()), (0, 2));
+        assert_eq!(heap.pop(), Some(20));
+        assert_eq!(heap.pop(), Some(30));
+        assert!(heap.is_empty());
+    }
+}
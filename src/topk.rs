@@ -0,0 +1,177 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::{self, HashMap};
+use std::hash::Hash;
+
+use crate::FibonacciHeap;
+
+/// Which end of each group's ordering a [`GroupedTopK`] keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Retain {
+    Smallest,
+    Largest,
+}
+
+#[derive(Debug)]
+enum Group<T> {
+    // The retained elements sit in a heap whose top is the first one to evict.
+    Smallest(FibonacciHeap<Reverse<T>>),
+    Largest(FibonacciHeap<T>),
+}
+
+impl<T: PartialOrd> Group<T> {
+    fn insert(&mut self, value: T, limit: usize) {
+        match self {
+            Group::Smallest(heap) => drop(heap.push_bounded(Reverse(value), limit)),
+            Group::Largest(heap) => drop(heap.push_bounded(value, limit)),
+        }
+    }
+
+    // Best first: ascending for `Smallest`, descending for `Largest`.
+    fn into_sorted_vec(self) -> Vec<T> {
+        let mut vec: Vec<T> = match self {
+            Group::Smallest(heap) => heap.map(|Reverse(value)| value).collect(),
+            Group::Largest(heap) => heap.collect(),
+        };
+        vec.reverse();
+        vec
+    }
+}
+
+/// Streaming aggregator keeping the `limit` smallest (or largest) values seen for each key.
+///
+/// Each group is a heap bounded to `limit` elements, so memory stays O(groups × limit)
+/// however skewed the key distribution is, and an insert costs O(log limit) amortized.
+///
+/// # Example
+/// ```
+/// use fibheap::{GroupedTopK, Retain};
+///
+/// let mut slowest = GroupedTopK::new(2, Retain::Largest);
+/// for (page, latency) in [("/", 30), ("/a", 5), ("/", 80), ("/", 10), ("/a", 7)] {
+///     slowest.insert(page, latency);
+/// }
+/// let mut groups: Vec<_> = slowest.into_iter().collect();
+/// groups.sort();
+/// assert_eq!(groups, [("/", vec![80, 30]), ("/a", vec![7, 5])]);
+/// ```
+#[derive(Debug)]
+pub struct GroupedTopK<K, T> {
+    groups: HashMap<K, Group<T>>,
+    limit: usize,
+    retain: Retain,
+}
+
+impl<K: Hash + Eq, T: PartialOrd> GroupedTopK<K, T> {
+    pub fn new(limit: usize, retain: Retain) -> Self {
+        Self { groups: HashMap::new(), limit, retain }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn retain(&self) -> Retain {
+        self.retain
+    }
+
+    /// Number of distinct keys seen so far.
+    pub fn group_count(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn insert(&mut self, key: K, value: T) {
+        let retain = self.retain;
+        let group = self.groups.entry(key).or_insert_with(|| match retain {
+            Retain::Smallest => Group::Smallest(FibonacciHeap::new()),
+            Retain::Largest => Group::Largest(FibonacciHeap::new()),
+        });
+        group.insert(value, self.limit);
+    }
+}
+
+impl<K, T: PartialOrd> IntoIterator for GroupedTopK<K, T> {
+    type Item = (K, Vec<T>);
+    type IntoIter = Groups<K, T>;
+
+    /// Yields every key seen, in unspecified order, with its retained values sorted best first
+    /// (ascending for [`Retain::Smallest`], descending for [`Retain::Largest`]).
+    fn into_iter(self) -> Groups<K, T> {
+        Groups { groups: self.groups.into_iter() }
+    }
+}
+
+/// Iterator returned by [`GroupedTopK::into_iter`].
+#[derive(Debug)]
+pub struct Groups<K, T> {
+    groups: hash_map::IntoIter<K, Group<T>>,
+}
+
+impl<K, T: PartialOrd> Iterator for Groups<K, T> {
+    type Item = (K, Vec<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.groups.next().map(|(key, group)| (key, group.into_sorted_vec()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.groups.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+
+    fn brute_force(events: &[(u64, u64)], limit: usize, retain: Retain) -> Vec<(u64, Vec<u64>)> {
+        let mut groups: HashMap<u64, Vec<u64>> = HashMap::new();
+        for &(key, value) in events {
+            groups.entry(key).or_default().push(value);
+        }
+        let mut groups: Vec<_> = groups
+            .into_iter()
+            .map(|(key, mut values)| {
+                values.sort_unstable();
+                if retain == Retain::Largest {
+                    values.reverse();
+                }
+                values.truncate(limit);
+                (key, values)
+            })
+            .collect();
+        groups.sort_unstable();
+        groups
+    }
+
+    fn check(events: &[(u64, u64)], limit: usize, retain: Retain) {
+        let mut top = GroupedTopK::new(limit, retain);
+        for &(key, value) in events {
+            top.insert(key, value);
+        }
+        let mut groups: Vec<_> = top.into_iter().collect();
+        groups.sort_unstable();
+        assert_eq!(groups, brute_force(events, limit, retain));
+    }
+
+    #[test]
+    fn small_groups_and_zero_limit() {
+        let events = [(1, 5), (2, 3), (1, 1), (3, 9)];
+        check(&events, 5, Retain::Smallest);
+        check(&events, 5, Retain::Largest);
+        check(&events, 0, Retain::Smallest);
+        check(&events, 1, Retain::Largest);
+    }
+
+    #[test]
+    fn skewed_keys() {
+        let mut rng = Rng(0xa0761d6478bd642f);
+        let events: Vec<(u64, u64)> = (0..50_000)
+            .map(|_| {
+                let key = if rng.below(100) == 0 { rng.below(50) + 1 } else { 0 };
+                (key, rng.below(1000))
+            })
+            .collect();
+        check(&events, 10, Retain::Smallest);
+        check(&events, 10, Retain::Largest);
+    }
+}
@@ -0,0 +1,166 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{FibonacciHeap, Node};
+
+/// Inconsistency found by [`FibonacciHeap::validate`].
+///
+/// Paths locate a node: the index of its tree in the root list, then the index among the children at each level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// The node at `path` is smaller than its parent.
+    HeapOrder { path: Vec<usize> },
+    /// `len()` doesn't match the number of nodes.
+    Len { len: usize, nodes: usize },
+    /// The index of the minimum is outside the root list.
+    TopOutOfRange { top_index: usize, roots: usize },
+    /// The root at `root` is smaller than the one at `top_index`, which `peek` returns.
+    TopNotMinimal { top_index: usize, root: usize },
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HeapOrder { path } => write!(f, "node at {path:?} is smaller than its parent"),
+            Self::Len { len, nodes } => write!(f, "len is {len} but the heap holds {nodes} nodes"),
+            Self::TopOutOfRange { top_index, roots } => write!(f, "top index {top_index} is out of {roots} roots"),
+            Self::TopNotMinimal { top_index, root } => write!(f, "root {root} is smaller than the top at root {top_index}"),
+        }
+    }
+}
+
+impl core::error::Error for InvariantViolation {}
+
+impl<T: PartialOrd> FibonacciHeap<T> {
+    /// Checks the internal invariants in O(n): no node is smaller than its parent, the node count matches `len()`,
+    /// and the minimum tracked for `peek` is a root that no other root is smaller than.
+    ///
+    /// A correct heap always passes; a failure points at a bug, or at a `PartialOrd` implementation that isn't
+    /// a consistent order. Debug builds also check the cheap part (the tracked minimum) after each operation.
+    ///
+    /// # Example
+    /// ```
+    /// use fibheap::FibonacciHeap;
+    ///
+    /// let mut heap = FibonacciHeap::from_vec(vec![3, 1, 2]);
+    /// heap.pop();
+    /// assert_eq!(heap.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), InvariantViolation> {
+        if let Some(top) = self.roots.get(self.top_index) {
+            if let Some(root) = self.roots.iter().position(|root| root.value() < top.value()) {
+                return Err(InvariantViolation::TopNotMinimal { top_index: self.top_index, root });
+            }
+        } else if !self.roots.is_empty() {
+            return Err(InvariantViolation::TopOutOfRange { top_index: self.top_index, roots: self.roots.len() });
+        }
+
+        let mut nodes = 0;
+        // path to the node being inspected, and the nodes left to visit with their depth and index among siblings
+        let mut path = vec![];
+        let mut stack: Vec<(usize, usize, &Node<T>)> = self.roots.iter().enumerate().rev().map(|(ix, root)| (0, ix, root)).collect();
+        while let Some((depth, ix, node)) = stack.pop() {
+            nodes += 1;
+            path.truncate(depth);
+            path.push(ix);
+            for (child_ix, child) in node.children.iter().enumerate().rev() {
+                if child.value() < node.value() {
+                    path.push(child_ix);
+                    return Err(InvariantViolation::HeapOrder { path });
+                }
+                stack.push((depth + 1, child_ix, child));
+            }
+        }
+        if nodes != self.len {
+            return Err(InvariantViolation::Len { len: self.len, nodes });
+        }
+        Ok(())
+    }
+
+    // The cheap part of `validate`, asserted in debug builds at the end of operations:
+    // the minimum is a root, and with `scan_roots` no root is smaller. Scanning is left to operations
+    // that already visit every root, so that debug builds keep the same complexity.
+    pub(crate) fn debug_validate_top(&self, scan_roots: bool) {
+        if !cfg!(debug_assertions) || self.roots.is_empty() {
+            return;
+        }
+        if self.top_index >= self.roots.len() {
+            debug_validation_failed(format_args!("top index {} out of {} roots", self.top_index, self.roots.len()));
+        }
+        if self.len < self.roots.len() {
+            debug_validation_failed(format_args!("len {} below the {} roots", self.len, self.roots.len()));
+        }
+        if scan_roots {
+            let top = self.roots[self.top_index].value();
+            if let Some(root) = self.roots.iter().position(|root| root.value() < top) {
+                debug_validation_failed(format_args!("root {root} is smaller than the top at root {}", self.top_index));
+            }
+        }
+    }
+}
+
+// Reports a failed `debug_validate_top` check, also as a `warn` event with the `tracing` feature.
+#[cold]
+#[track_caller]
+fn debug_validation_failed(message: fmt::Arguments<'_>) -> ! {
+    #[cfg(feature = "tracing")]
+    tracing::warn!("fibheap: {message}");
+    panic!("fibheap: {message}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SparePool;
+
+    fn tree(value: i32, children: Vec<Node<i32>>) -> Node<i32> {
+        Node { value, children }
+    }
+
+    fn heap(roots: Vec<Node<i32>>, top_index: usize, len: usize) -> FibonacciHeap<i32> {
+        FibonacciHeap { roots, top_index, len, degrees: vec![], spare: SparePool::default(), max_links: usize::MAX, eager: false, counters: Default::default() }
+    }
+
+    #[test]
+    fn reports_each_violation() {
+        let valid = || vec![tree(1, vec![tree(4, vec![]), tree(2, vec![tree(3, vec![])])]), tree(5, vec![])];
+        assert_eq!(heap(valid(), 0, 5).validate(), Ok(()));
+        assert_eq!(heap(vec![], 0, 0).validate(), Ok(()));
+
+        let mut roots = valid();
+        roots[0].children[1].children[0].value = 0;
+        let error = heap(roots, 0, 5).validate().unwrap_err();
+        assert_eq!(error, InvariantViolation::HeapOrder { path: vec![0, 1, 0] });
+        assert_eq!(error.to_string(), "node at [0, 1, 0] is smaller than its parent");
+
+        assert_eq!(heap(valid(), 0, 4).validate(), Err(InvariantViolation::Len { len: 4, nodes: 5 }));
+        assert_eq!(heap(vec![], 0, 1).validate(), Err(InvariantViolation::Len { len: 1, nodes: 0 }));
+        assert_eq!(heap(valid(), 2, 5).validate(), Err(InvariantViolation::TopOutOfRange { top_index: 2, roots: 2 }));
+        assert_eq!(heap(valid(), 1, 5).validate(), Err(InvariantViolation::TopNotMinimal { top_index: 1, root: 0 }));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "is smaller than the top")]
+    fn debug_builds_check_the_top() {
+        // rock-paper-scissors: every value is beaten by the next one, so no order is consistent
+        #[derive(PartialEq)]
+        struct Hand(u8);
+
+        impl PartialOrd for Hand {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                use core::cmp::Ordering::*;
+                Some(match (other.0 + 3 - self.0) % 3 {
+                    0 => Equal,
+                    1 => Less,
+                    _ => Greater,
+                })
+            }
+        }
+
+        let mut heap = FibonacciHeap::new();
+        [0, 2, 1, 0].into_iter().for_each(|hand| heap.push(Hand(hand)));
+        heap.pop();
+    }
+}
@@ -0,0 +1,80 @@
+use core::cmp::Ordering;
+use core::mem;
+
+use wasm_bindgen::prelude::*;
+
+use crate::FibonacciHeap;
+
+// JS value with its priority; `seq` breaks ties in insertion order.
+struct Entry {
+    priority: f64,
+    seq: u64,
+    value: JsValue,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (self.priority, self.seq).partial_cmp(&(other.priority, other.seq))
+    }
+}
+
+/// Min-heap of JS values ordered by a numeric priority, exported to JS by the `wasm` feature.
+///
+/// Equal priorities pop in insertion order. `push` throws on a NaN priority.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct JsFibHeap {
+    heap: FibonacciHeap<Entry>,
+    next_seq: u64,
+}
+
+#[wasm_bindgen]
+impl JsFibHeap {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, priority: f64, value: JsValue) -> Result<(), JsError> {
+        if priority.is_nan() {
+            return Err(JsError::new("priority must not be NaN"));
+        }
+        self.heap.push(Entry { priority, seq: self.next_seq, value });
+        self.next_seq += 1;
+        Ok(())
+    }
+
+    /// Removes and returns the value with the smallest priority, or `undefined` if the heap is empty.
+    pub fn pop(&mut self) -> Option<JsValue> {
+        self.heap.pop().map(|entry| entry.value)
+    }
+
+    /// Returns the value with the smallest priority without removing it, or `undefined` if the heap is empty.
+    pub fn peek(&self) -> Option<JsValue> {
+        self.heap.peek().map(|entry| entry.value.clone())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Moves every entry of `other` into this heap, leaving `other` empty.
+    pub fn merge(&mut self, other: &mut JsFibHeap) {
+        // Keep later pushes behind the merged entries of equal priority.
+        self.next_seq = self.next_seq.max(other.next_seq);
+        self.heap.append(mem::take(&mut other.heap));
+    }
+
+    /// The values in ascending priority order; the heap is left as is.
+    #[wasm_bindgen(js_name = toArraySorted)]
+    pub fn to_array_sorted(&self) -> Vec<JsValue> {
+        self.heap.iter_sorted().map(|entry| entry.value.clone()).collect()
+    }
+}
@@ -0,0 +1,130 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::iter;
+
+use crate::FibonacciHeap;
+
+/// Yields, for each item of `iter` from the `k`-th on, the smallest of the last `k` items: a rolling minimum.
+///
+/// Nothing is yielded if `iter` has fewer than `k` items, like [`slice::windows`]. Among equal items the earliest in
+/// the window is yielded. Expired items are deleted lazily, once they reach the top of the heap, and the heap is
+/// pruned whenever it holds more than twice the window, so the whole run takes O(n log k). Items are only cloned to
+/// be yielded.
+///
+/// # Panics
+/// Panics if `k` is 0.
+///
+/// # Example
+/// ```
+/// let latencies = [12, 7, 9, 15, 3, 8];
+/// let minimums: Vec<_> = fibheap::window_min(latencies, 3).collect();
+/// assert_eq!(minimums, [7, 7, 3, 3]);
+/// ```
+pub fn window_min<T: PartialOrd + Clone>(iter: impl IntoIterator<Item = T>, k: usize) -> impl Iterator<Item = T> {
+    let mut window = Window::new(iter.into_iter().map(|item| (item, ())), k);
+    iter::from_fn(move || window.advance().map(|slot| slot.key.clone()))
+}
+
+/// Like [`window_min`], comparing items by the key `f` extracts, which is computed once per item.
+///
+/// # Example
+/// ```
+/// let requests = [("a", 12), ("b", 7), ("c", 9), ("d", 15)];
+/// let fastest: Vec<_> = fibheap::window_min_by_key(requests, 2, |&(_, ms)| ms).map(|(name, _)| name).collect();
+/// assert_eq!(fastest, ["b", "b", "c"]);
+/// ```
+pub fn window_min_by_key<T: Clone, K: PartialOrd>(
+    iter: impl IntoIterator<Item = T>,
+    k: usize,
+    mut f: impl FnMut(&T) -> K,
+) -> impl Iterator<Item = T> {
+    let mut window = Window::new(iter.into_iter().map(move |item| (f(&item), item)), k);
+    iter::from_fn(move || window.advance().map(|slot| slot.value.clone()))
+}
+
+// Items of the stream tagged with their position, ordered by key and then position.
+struct Slot<K, V> {
+    key: K,
+    index: usize,
+    value: V,
+}
+
+impl<K: PartialOrd, V> PartialEq for Slot<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl<K: PartialOrd, V> PartialOrd for Slot<K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.key.partial_cmp(&other.key)? {
+            Ordering::Equal => Some(self.index.cmp(&other.index)),
+            ord => Some(ord),
+        }
+    }
+}
+
+struct Window<I, K, V> {
+    iter: I,
+    k: usize,
+    // the items read so far that may still be in the window, and possibly some that have left it
+    heap: FibonacciHeap<Slot<K, V>>,
+    // number of items read
+    read: usize,
+}
+
+impl<I: Iterator<Item = (K, V)>, K: PartialOrd, V> Window<I, K, V> {
+    fn new(iter: I, k: usize) -> Self {
+        assert!(k > 0, "window size must be non-zero");
+        Self { iter, k, heap: FibonacciHeap::new(), read: 0 }
+    }
+
+    // Reads the next item, or the first `k` on the first call, and returns the minimum of the window ending there.
+    fn advance(&mut self) -> Option<&Slot<K, V>> {
+        loop {
+            let (key, value) = self.iter.next()?;
+            self.heap.push(Slot { key, index: self.read, value });
+            self.read += 1;
+            if self.read >= self.k {
+                break;
+            }
+        }
+        let start = self.read - self.k;
+        while self.heap.peek().is_some_and(|slot| slot.index < start) {
+            self.heap.pop();
+        }
+        // expired items below the top only leave by pruning, which costs O(1) per item read
+        if self.heap.len() > 2 * self.k {
+            let live: Vec<_> = self.heap.take_values().into_iter().filter(|slot| slot.index >= start).collect();
+            self.heap.bulk_push(live);
+        }
+        self.heap.peek()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn edge_cases() {
+        assert!(window_min(Vec::<i32>::new(), 1).next().is_none());
+        assert_eq!(window_min([3, 1, 2], 1).collect::<Vec<_>>(), [3, 1, 2]);
+        assert_eq!(window_min([3, 1, 2], 3).collect::<Vec<_>>(), [1]);
+        assert!(window_min([3, 1, 2], 4).next().is_none());
+        // a falling stream never expires anything at the top, so only pruning keeps the heap small
+        let mut window = Window::new((0..10_000).rev().map(|item| (item, ())), 10);
+        let mut max_len = 0;
+        while window.advance().is_some() {
+            max_len = max_len.max(window.heap.len());
+        }
+        assert!(max_len <= 20, "{max_len}");
+    }
+
+    #[test]
+    #[should_panic(expected = "window size must be non-zero")]
+    fn empty_window() {
+        let _ = window_min(vec![1], 0);
+    }
+}
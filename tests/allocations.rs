@@ -0,0 +1,217 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use fibheap::FibonacciHeap;
+
+// Counts allocations and live bytes of the current thread, so that concurrently running tests don't interfere.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+}
+
+fn record(allocations: usize, bytes: isize) {
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + allocations));
+    let _ = LIVE_BYTES.try_with(|live| live.set(live.get() + bytes));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(1, layout.size() as isize);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record(0, -(layout.size() as isize));
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(1, new_size as isize - layout.size() as isize);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations_in(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+fn live_bytes() -> isize {
+    LIVE_BYTES.with(Cell::get)
+}
+
+#[test]
+fn clone_from_reuses_allocations() {
+    let mut source = FibonacciHeap::from_vec((0..10_000u64).map(|i| i * 7919 % 10_007).collect());
+    source.pop();
+    let mut scratch = source.clone();
+
+    let fresh = allocations_in(|| drop(source.clone()));
+    assert!(fresh > 1000, "{fresh}");
+
+    for _ in 0..10 {
+        // nudge the source like a planner would between snapshots
+        let top = source.pop().unwrap();
+        source.push(top + 1);
+        let reused = allocations_in(|| scratch.clone_from(&source));
+        assert!(reused <= 10, "clone_from allocated {reused} times, clone() {fresh}");
+        assert_eq!(scratch.len(), source.len());
+        assert_eq!(scratch.peek(), source.peek());
+    }
+    assert_eq!(scratch.into_sorted_vec(), source.into_sorted_vec());
+}
+
+#[test]
+fn pushes_within_capacity_dont_allocate() {
+    let mut heap = FibonacciHeap::with_capacity(10_000);
+    let pushes = allocations_in(|| (0..10_000u64).rev().for_each(|i| heap.push(i * 7919 % 10_007)));
+    assert_eq!(pushes, 0);
+
+    heap.pop();
+    heap.reserve(5_000);
+    let pushes = allocations_in(|| (0..5_000u64).for_each(|i| heap.push(i)));
+    assert_eq!(pushes, 0);
+    assert_eq!(heap.len(), 14_999);
+}
+
+#[test]
+fn append_allocates_at_most_the_root_list() {
+    let heap = |seed: u64| {
+        let mut heap = FibonacciHeap::new();
+        for i in 0..10_000u64 {
+            heap.push(i * seed % 10_007);
+            if i % 4 == 0 {
+                heap.pop();
+            }
+        }
+        heap
+    };
+    let (mut a, b) = (heap(7919), heap(104_729));
+    let len = a.len() + b.len();
+    // at most one growth of the root list; the trees of `b` move over as they are
+    let allocations = allocations_in(|| a.append(b));
+    assert!(allocations <= 1, "{allocations}");
+    assert_eq!(a.len(), len);
+}
+
+#[test]
+fn steady_state_pops_reuse_allocations() {
+    let mut x = 0x9e3779b97f4a7c15u64;
+    let mut next = move || {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        x % 1_000_000
+    };
+    let mut heap = FibonacciHeap::from_vec((0..10_000).map(|_| next()).collect());
+    let mut churn = |heap: &mut FibonacciHeap<u64>| {
+        for _ in 0..50_000 {
+            heap.push(next());
+            heap.pop();
+        }
+    };
+    // Warm-up: the scratch table and the recycled children vectors settle at the sizes this workload needs.
+    for _ in 0..4 {
+        churn(&mut heap);
+    }
+    // Without reuse every pop allocates; what is left are rare growths of a children vector past any spare one.
+    let allocations = allocations_in(|| {
+        for _ in 0..4 {
+            churn(&mut heap);
+        }
+    });
+    assert!(allocations <= 20, "{allocations} allocations in 200 000 pops");
+    assert_eq!(heap.len(), 10_000);
+}
+
+#[cfg(feature = "allocator_api")]
+mod bump {
+    use super::*;
+    use allocator_api2::alloc::{AllocError, Allocator};
+    use fibheap::AllocFibonacciHeap;
+    use std::ptr::NonNull;
+
+    // Hands out consecutive chunks of a preallocated (leaked) buffer and never frees.
+    struct Bump {
+        buffer: NonNull<u8>,
+        len: usize,
+        used: Cell<usize>,
+    }
+
+    impl Bump {
+        fn new(len: usize) -> Self {
+            let buffer = NonNull::new(Box::leak(vec![0u8; len].into_boxed_slice()).as_mut_ptr()).unwrap();
+            Self { buffer, len, used: Cell::new(0) }
+        }
+    }
+
+    unsafe impl Allocator for Bump {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            let base = self.buffer.as_ptr() as usize;
+            let start = (base + self.used.get()).next_multiple_of(layout.align()) - base;
+            let end = start.checked_add(layout.size()).filter(|&end| end <= self.len).ok_or(AllocError)?;
+            self.used.set(end);
+            // SAFETY: `start <= end <= len`, so the chunk lies within the buffer.
+            let ptr = unsafe { self.buffer.add(start) };
+            Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+        }
+
+        unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {}
+    }
+
+    #[test]
+    fn alloc_heap_stays_in_its_allocator() {
+        let bump = Bump::new(1 << 24);
+        let global = allocations_in(|| {
+            let mut heap = AllocFibonacciHeap::new_in(&bump);
+            for i in 0..10_000u64 {
+                heap.push(i * 7919 % 10_007);
+                if i % 3 == 0 {
+                    heap.pop();
+                }
+            }
+            let mut other = AllocFibonacciHeap::new_in(&bump);
+            other.push(0);
+            heap.append(other);
+            let sorted = heap.into_vec();
+            assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+        });
+        assert_eq!(global, 0);
+        assert!(bump.used.get() > 0);
+    }
+}
+
+
+#[test]
+fn memory_usage_matches_allocator() {
+    let before = live_bytes();
+    let mut heap = FibonacciHeap::new();
+    let mut state = 0x2545f4914f6cdd1du64;
+    for round in 0..20_000u64 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        heap.push([state, round]);
+        if round % 3 == 0 {
+            heap.pop();
+        }
+    }
+    let usage = heap.memory_usage();
+    assert_eq!(usage.payload, heap.len() * 16);
+    assert_eq!(usage.total, usage.payload + usage.node_overhead + usage.unused_capacity);
+    // the allocator sees exactly the requested sizes
+    assert_eq!((live_bytes() - before) as usize, usage.total);
+
+    heap.shrink_to_fit();
+    let shrunk = heap.memory_usage();
+    assert!(shrunk.unused_capacity < usage.unused_capacity);
+    assert_eq!((live_bytes() - before) as usize, shrunk.total);
+    drop(heap);
+    assert_eq!(live_bytes(), before);
+}
@@ -0,0 +1,102 @@
+#![cfg(any(feature = "proptest", feature = "quickcheck"))]
+
+use fibheap::FibonacciHeap;
+
+fn pops_in_order<T: PartialOrd + Clone>(mut heap: FibonacciHeap<T>) -> bool {
+    let len = heap.len();
+    let mut popped: Vec<T> = vec![];
+    while let Some(value) = heap.pop() {
+        if popped.last().is_some_and(|last| *last > value) {
+            return false;
+        }
+        popped.push(value);
+    }
+    popped.len() == len
+}
+
+#[cfg(feature = "proptest")]
+mod strategy {
+    use super::*;
+    use fibheap::heap_strategy;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn pop_order(heap in heap_strategy(any::<i16>(), 0..300)) {
+            prop_assert!(pops_in_order(heap));
+        }
+
+        #[test]
+        fn size_in_range(heap in heap_strategy(0..10u8, 5..20)) {
+            prop_assert!((5..20).contains(&heap.len()));
+        }
+
+        #[test]
+        fn eager_matches_lazy(heap in heap_strategy(any::<i16>(), 0..100), ops in prop::collection::vec(op(), 0..300)) {
+            let mut lazy = heap.clone();
+            let mut eager = heap;
+            eager.set_eager_consolidation(true);
+            for op in ops {
+                match op {
+                    Op::Push(value) => {
+                        lazy.push(value);
+                        eager.push(value);
+                    }
+                    Op::Pop => prop_assert_eq!(lazy.pop(), eager.pop()),
+                    Op::Append(values) => {
+                        lazy.append(FibonacciHeap::from_vec(values.clone()));
+                        eager.append(FibonacciHeap::from_vec(values));
+                    }
+                }
+                prop_assert_eq!(lazy.validate(), Ok(()));
+                prop_assert_eq!(eager.validate(), Ok(()));
+                prop_assert_eq!(lazy.peek(), eager.peek());
+                prop_assert_eq!(lazy.len(), eager.len());
+                // distinct degrees leave at most one tree per bit of the length
+                prop_assert!(eager.root_count() <= eager.len().count_ones() as usize);
+            }
+            prop_assert_eq!(lazy.into_sorted_vec(), eager.into_sorted_vec());
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Push(i16),
+        Pop,
+        Append(Vec<i16>),
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            3 => any::<i16>().prop_map(Op::Push),
+            2 => Just(Op::Pop),
+            1 => prop::collection::vec(any::<i16>(), 0..20).prop_map(Op::Append),
+        ]
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+mod arbitrary {
+    use super::*;
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+
+    quickcheck! {
+        fn pop_order(heap: FibonacciHeap<i16>) -> bool {
+            pops_in_order(heap)
+        }
+    }
+
+    #[test]
+    fn shrinks_to_valid_smaller_heaps() {
+        let mut g = Gen::new(50);
+        for _ in 0..20 {
+            let heap = FibonacciHeap::<u8>::arbitrary(&mut g);
+            for shrunk in heap.shrink().take(100) {
+                assert!(shrunk.len() <= heap.len());
+                assert!(pops_in_order(shrunk));
+            }
+        }
+        let heap = FibonacciHeap::from_vec(vec![3u8, 1, 2]);
+        assert!(heap.shrink().any(|shrunk| shrunk.is_empty()));
+    }
+}
@@ -0,0 +1,127 @@
+// One suite of tests, generated for each heap with the shared public surface (`FibonacciHeap` and `PairingHeap`),
+// so that every behavior one of them promises is checked on the other too.
+#![cfg(feature = "std")]
+
+macro_rules! backend_suite {
+    ($name:ident, $heap:ident) => {
+        mod $name {
+            use fibheap::test_util::Tracker;
+            use fibheap::$heap;
+
+            // xorshift64, deterministic across runs
+            fn values(seed: u64, len: usize, range: u64) -> Vec<u64> {
+                let mut x = seed;
+                (0..len)
+                    .map(|_| {
+                        x ^= x << 13;
+                        x ^= x >> 7;
+                        x ^= x << 17;
+                        x % range
+                    })
+                    .collect()
+            }
+
+            #[test]
+            fn empty() {
+                let mut heap = $heap::<u32>::new();
+                assert!(heap.is_empty());
+                assert_eq!(heap.len(), 0);
+                assert_eq!(heap.peek(), None);
+                assert_eq!(heap.pop(), None);
+                assert_eq!(heap.iter().count(), 0);
+                assert!(heap.into_vec().is_empty());
+                assert!($heap::<u32>::default().is_empty());
+                assert!($heap::<u32>::from_vec(vec![]).is_empty());
+            }
+
+            #[test]
+            fn pops_in_order() {
+                let values = values(0x6a09e667f3bcc908, 2000, 300);
+                let mut heap = $heap::new();
+                values.iter().for_each(|&value| heap.push(value));
+                let mut sorted = values.clone();
+                sorted.sort_unstable();
+                assert_eq!(heap.len(), sorted.len());
+                assert_eq!(heap.peek(), sorted.first());
+                assert_eq!(heap.clone().into_sorted_vec(), sorted);
+                assert_eq!(heap.clone().into_vec(), sorted);
+                assert_eq!(heap.iter_sorted().copied().collect::<Vec<_>>(), sorted);
+                // the heap iterates by popping
+                assert_eq!(heap.collect::<Vec<_>>(), sorted);
+            }
+
+            #[test]
+            fn from_vec_and_iter() {
+                let values = values(0xbb67ae8584caa73b, 1000, 50);
+                let heap = $heap::from_vec(values.clone());
+                let mut seen: Vec<u64> = heap.iter().copied().collect();
+                seen.sort_unstable();
+                let mut sorted = values;
+                sorted.sort_unstable();
+                assert_eq!(seen, sorted);
+                assert_eq!(heap.peek(), sorted.first());
+                assert_eq!(heap.into_sorted_vec(), sorted);
+            }
+
+            #[test]
+            fn interleaved_with_appends() {
+                let mut heap = $heap::new();
+                // kept in descending order, so that the minimum is at the end
+                let mut model: Vec<u64> = vec![];
+                let insert = |model: &mut Vec<u64>, value| model.insert(model.partition_point(|&v| v > value), value);
+                for (round, value) in values(0x3c6ef372fe94f82b, 5000, 500).into_iter().enumerate() {
+                    match round % 7 {
+                        0..=2 => {
+                            heap.push(value);
+                            insert(&mut model, value);
+                        }
+                        3 => {
+                            let others = values(value + 1, (value % 20) as usize, 500);
+                            others.iter().for_each(|&other| insert(&mut model, other));
+                            heap.append($heap::from_vec(others));
+                        }
+                        _ => assert_eq!(heap.pop(), model.pop()),
+                    }
+                    assert_eq!(heap.len(), model.len());
+                    assert_eq!(heap.peek(), model.last());
+                }
+                model.reverse();
+                assert_eq!(heap.into_sorted_vec(), model);
+            }
+
+            #[test]
+            fn clear_clone_and_debug() {
+                let mut heap = $heap::from_vec(vec![3, 1, 2]);
+                let copy = heap.clone();
+                heap.clear();
+                assert!(heap.is_empty());
+                assert_eq!(copy.len(), 3);
+                assert!(format!("{copy:?}").contains('1'));
+                heap.push(5);
+                assert_eq!(heap.pop(), Some(5));
+            }
+
+            #[test]
+            fn drops_every_element_once() {
+                let tracker = Tracker::new();
+                let mut heap = $heap::new();
+                for value in values(0xa54ff53a5f1d36f1, 500, 100) {
+                    heap.push(tracker.track(value));
+                }
+                for _ in 0..100 {
+                    drop(heap.pop());
+                }
+                heap.append($heap::from_vec((0..50).map(|value| tracker.track(value)).collect()));
+                drop(heap.clone());
+                let half: Vec<_> = heap.by_ref().take(200).collect();
+                assert_eq!(tracker.live(), half.len() + heap.len());
+                drop(half);
+                drop(heap);
+                tracker.assert_balanced();
+            }
+        }
+    };
+}
+
+backend_suite!(fibonacci, FibonacciHeap);
+backend_suite!(pairing, PairingHeap);
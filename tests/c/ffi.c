@@ -0,0 +1,75 @@
+/* Exercises the C interface; run by tests/ffi.rs. */
+#include <assert.h>
+#include <math.h>
+#include <stdio.h>
+
+#include "fibheap.h"
+
+static void order_and_data(void) {
+    int items[] = {30, 10, 20, 0};
+    double keys[] = {3.0, 1.0, 2.0, -1.5};
+    fibheap_t *heap = fibheap_new();
+    assert(heap != NULL);
+    for (int i = 0; i < 4; i++) {
+        assert(fibheap_push(heap, keys[i], &items[i]) == FIBHEAP_STATUS_OK);
+    }
+    assert(fibheap_len(heap) == 4);
+
+    double key;
+    void *data;
+    assert(fibheap_peek(heap, &key, &data));
+    assert(key == -1.5 && data == &items[3]);
+    assert(fibheap_len(heap) == 4);
+
+    int expected[] = {0, 10, 20, 30};
+    for (int i = 0; i < 4; i++) {
+        assert(fibheap_pop(heap, &key, &data));
+        assert(*(int *)data == expected[i]);
+    }
+    assert(!fibheap_pop(heap, &key, &data));
+    assert(!fibheap_peek(heap, NULL, NULL));
+    assert(fibheap_len(heap) == 0);
+    fibheap_free(heap);
+}
+
+static void merge(void) {
+    fibheap_t *a = fibheap_new();
+    fibheap_t *b = fibheap_new();
+    for (int i = 0; i < 100; i++) {
+        assert(fibheap_push(i % 2 ? a : b, (double)(99 - i), NULL) == FIBHEAP_STATUS_OK);
+    }
+    assert(fibheap_merge(a, b) == FIBHEAP_STATUS_OK);
+    assert(fibheap_len(a) == 100 && fibheap_len(b) == 0);
+    assert(fibheap_merge(a, a) == FIBHEAP_STATUS_ALIASED);
+    for (int i = 0; i < 100; i++) {
+        double key;
+        assert(fibheap_pop(a, &key, NULL));
+        assert(key == (double)i);
+    }
+    fibheap_free(a);
+    fibheap_free(b);
+}
+
+static void misuse(void) {
+    fibheap_t *heap = fibheap_new();
+    assert(fibheap_push(heap, NAN, NULL) == FIBHEAP_STATUS_NAN);
+    assert(fibheap_len(heap) == 0);
+
+    assert(fibheap_push(NULL, 1.0, NULL) == FIBHEAP_STATUS_NULL);
+    assert(!fibheap_pop(NULL, NULL, NULL));
+    assert(!fibheap_peek(NULL, NULL, NULL));
+    assert(fibheap_len(NULL) == 0);
+    assert(fibheap_merge(heap, NULL) == FIBHEAP_STATUS_NULL);
+    assert(fibheap_merge(NULL, heap) == FIBHEAP_STATUS_NULL);
+    assert(fibheap_merge(NULL, NULL) == FIBHEAP_STATUS_NULL);
+    fibheap_free(NULL);
+    fibheap_free(heap);
+}
+
+int main(void) {
+    order_and_data();
+    merge();
+    misuse();
+    puts("ok");
+    return 0;
+}
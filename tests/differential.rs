@@ -0,0 +1,125 @@
+// Differential tests: random operation sequences run against `FibonacciHeap` and `BinaryHeap` side by side,
+// comparing every result. Failing cases shrink to a minimal operation trace.
+//
+// Each property runs 256 cases by default; proptest's `PROPTEST_CASES` variable raises that for longer runs:
+//
+//     PROPTEST_CASES=100000 cargo test --release --test differential
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use fibheap::FibonacciHeap;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Push(i64),
+    Pop,
+    PushPop(i64),
+    // a heap built from `values` by pushes, with its `pops` smallest elements popped to give it some trees
+    Append { values: Vec<i64>, pops: usize },
+    // replace the heap with `FibonacciHeap::from_vec(values)`
+    FromVec(Vec<i64>),
+    Extend(Vec<i64>),
+    AddAssign(Vec<i64>),
+    Consolidate,
+}
+
+// Few distinct values, so that ties are common, plus the odd extreme one.
+fn value() -> impl Strategy<Value = i64> {
+    prop_oneof![8 => -20i64..20, 1 => any::<i64>()]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        8 => value().prop_map(Op::Push),
+        6 => Just(Op::Pop),
+        1 => value().prop_map(Op::PushPop),
+        2 => (vec(value(), 0..40), 0..10usize).prop_map(|(values, pops)| Op::Append { values, pops }),
+        1 => vec(value(), 0..60).prop_map(Op::FromVec),
+        1 => vec(value(), 0..40).prop_map(Op::Extend),
+        1 => vec(value(), 0..10).prop_map(Op::AddAssign),
+        1 => Just(Op::Consolidate),
+    ]
+}
+
+fn run(ops: Vec<Op>, eager: bool) -> Result<(), TestCaseError> {
+    let mut heap = FibonacciHeap::new();
+    heap.set_eager_consolidation(eager);
+    let mut model = BinaryHeap::new();
+    for op in ops {
+        match op {
+            Op::Push(value) => {
+                heap.push(value);
+                model.push(Reverse(value));
+            }
+            Op::Pop => prop_assert_eq!(heap.pop(), model.pop().map(|Reverse(value)| value)),
+            Op::PushPop(value) => {
+                model.push(Reverse(value));
+                prop_assert_eq!(heap.push_pop(value), model.pop().unwrap().0);
+            }
+            Op::Append { values, pops } => {
+                let mut other = FibonacciHeap::new();
+                values.iter().for_each(|&value| other.push(value));
+                let mut sorted = values;
+                sorted.sort_unstable();
+                for expected in sorted.iter().take(pops) {
+                    prop_assert_eq!(other.pop(), Some(*expected));
+                }
+                heap.append(other);
+                model.extend(sorted.into_iter().skip(pops).map(Reverse));
+            }
+            Op::FromVec(values) => {
+                model = values.iter().copied().map(Reverse).collect();
+                heap = FibonacciHeap::from_vec(values);
+                heap.set_eager_consolidation(eager);
+            }
+            Op::Extend(values) => {
+                model.extend(values.iter().copied().map(Reverse));
+                heap.bulk_push(values);
+            }
+            Op::AddAssign(values) => {
+                model.extend(values.iter().copied().map(Reverse));
+                heap += values;
+            }
+            Op::Consolidate => heap.consolidate(),
+        }
+        prop_assert_eq!(heap.len(), model.len());
+        prop_assert_eq!(heap.peek(), model.peek().map(|Reverse(value)| value));
+        prop_assert_eq!(heap.validate(), Ok(()));
+    }
+    let drained: Vec<i64> = model.into_sorted_vec().into_iter().rev().map(|Reverse(value)| value).collect();
+    prop_assert_eq!(heap.into_sorted_vec(), drained);
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn matches_binary_heap(ops in vec(op(), 0..200)) {
+        run(ops, false)?;
+    }
+
+    #[test]
+    fn matches_binary_heap_eager(ops in vec(op(), 0..200)) {
+        run(ops, true)?;
+    }
+
+    // The descending variants are the ascending order reversed, duplicates included.
+    #[test]
+    fn descending_is_ascending_reversed(values in vec(value(), 0..200), pops in 0usize..20) {
+        let mut heap = FibonacciHeap::new();
+        for value in values {
+            heap.push(value);
+        }
+        for _ in 0..pops {
+            heap.pop();
+        }
+        let mut ascending = heap.clone().into_sorted_vec();
+        ascending.reverse();
+        prop_assert_eq!(heap.clone().into_sorted_vec_desc(), ascending.clone());
+        prop_assert_eq!(heap.drain_sorted_desc().collect::<Vec<_>>(), ascending);
+        prop_assert!(heap.is_empty());
+    }
+}
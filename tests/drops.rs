@@ -0,0 +1,127 @@
+// Every element a heap is given is dropped exactly once, whichever path it takes out: the heap's own drop,
+// consuming conversions, iterators dropped early, merges, and clones or comparisons that panic halfway.
+// Small enough to run under Miri (`cargo +nightly miri test --test drops`), which also checks the unsafe paths.
+#![cfg(feature = "std")]
+
+use std::panic::{self, AssertUnwindSafe};
+
+use fibheap::test_util::{Tracked, Tracker};
+use fibheap::FibonacciHeap;
+
+const LEN: u32 = if cfg!(miri) { 200 } else { 20_000 };
+
+type Heap = FibonacciHeap<Tracked<u32>>;
+
+// A heap of a permutation of `0..len` that has popped once, so it holds trees rather than a flat root list.
+fn consolidated(tracker: &Tracker, len: u32) -> Heap {
+    let mut heap = FibonacciHeap::new();
+    (0..len).for_each(|value| heap.push(tracker.track(value * 7919 % len)));
+    heap.pop();
+    heap
+}
+
+#[test]
+fn build_and_drop() {
+    let tracker = Tracker::new();
+    drop(consolidated(&tracker, LEN));
+    drop(FibonacciHeap::from_vec((0..LEN).map(|value| tracker.track(value)).collect()));
+    tracker.assert_balanced();
+    assert_eq!(tracker.created(), 2 * LEN as usize);
+}
+
+#[test]
+fn consuming_conversions() {
+    let tracker = Tracker::new();
+    let sorted = consolidated(&tracker, LEN).into_vec();
+    assert_eq!(sorted.len(), LEN as usize - 1);
+    assert_eq!(tracker.live(), sorted.len());
+    drop(sorted);
+    drop(consolidated(&tracker, LEN).into_sorted_vec_desc());
+    for piece in consolidated(&tracker, LEN).into_forest() {
+        drop(piece);
+    }
+    tracker.assert_balanced();
+}
+
+#[test]
+fn iterators_dropped_early() {
+    let tracker = Tracker::new();
+    let mut heap = consolidated(&tracker, LEN);
+    let mut drain = heap.drain_sorted_desc();
+    assert_eq!(drain.next().map(|tracked| tracked.value), Some(LEN - 1));
+    drop(drain);
+    assert_eq!(tracker.live(), 0);
+    assert!(heap.is_empty());
+
+    let mut heap = consolidated(&tracker, LEN);
+    // the heap iterates by popping
+    assert_eq!(heap.by_ref().take(10).count(), 10);
+    assert_eq!(heap.iter_sorted().take(10).count(), 10);
+    drop(heap);
+    tracker.assert_balanced();
+}
+
+#[test]
+fn merging_and_splitting() {
+    let tracker = Tracker::new();
+    let mut heap = consolidated(&tracker, LEN);
+    heap.append(consolidated(&tracker, LEN / 2));
+    heap.append(FibonacciHeap::new());
+    let pieces = heap.split_forest(4);
+    assert_eq!(tracker.live(), heap.len() + pieces.iter().map(FibonacciHeap::len).sum::<usize>());
+    drop(pieces);
+    heap += (0..10).map(|value| tracker.track(value)).collect::<Vec<_>>();
+    heap.modify_all(|tracked| tracked.value += 1);
+    heap.clear();
+    assert_eq!(tracker.live(), 0);
+    tracker.assert_balanced();
+}
+
+#[test]
+fn panicking_clone() {
+    let tracker = Tracker::new();
+    let heap = consolidated(&tracker, LEN);
+    for fuse in [1, 2, LEN as usize / 3, LEN as usize - 1] {
+        tracker.panic_on_clone(fuse);
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| heap.clone())).is_err(), "fuse {fuse}");
+        assert_eq!(tracker.live(), heap.len(), "fuse {fuse}");
+
+        let mut target = consolidated(&tracker, LEN / 2);
+        tracker.panic_on_clone(fuse);
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| target.clone_from(&heap))).is_err(), "fuse {fuse}");
+        drop(target);
+        assert_eq!(tracker.live(), heap.len(), "fuse {fuse}");
+    }
+    tracker.defuse();
+    drop(heap.clone());
+    drop(heap);
+    tracker.assert_balanced();
+}
+
+#[test]
+fn panicking_comparison() {
+    let operations: [fn(&mut Heap, &Tracker); 6] = [
+        |heap, _| drop(heap.pop()),
+        |heap, tracker| heap.push(tracker.track(0)),
+        |heap, tracker| heap.append(consolidated(tracker, 50)),
+        |heap, _| drop(heap.drain_sorted_desc()),
+        |heap, _| drop(std::mem::take(heap).into_sorted_vec_desc()),
+        |_, tracker| drop(FibonacciHeap::from_vec((0..50).map(|value| tracker.track(value)).collect())),
+    ];
+    let fuses: &[usize] = if cfg!(miri) { &[1, 7, 60] } else { &[1, 2, 7, 60, 300, 1000] };
+    for (op, operation) in operations.iter().enumerate() {
+        for &fuse in fuses {
+            let tracker = Tracker::new();
+            let mut heap = consolidated(&tracker, 200);
+            tracker.panic_on_comparison(fuse);
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| operation(&mut heap, &tracker)));
+            tracker.defuse();
+            // whatever was lost to the panic is dropped, and the rest is still in a usable heap
+            assert_eq!(tracker.live(), heap.len(), "operation {op}, fuse {fuse}");
+            let sorted = heap.into_sorted_vec();
+            assert!(sorted.windows(2).all(|pair| pair[0] <= pair[1]));
+            drop(sorted);
+            tracker.assert_balanced();
+        }
+    }
+}
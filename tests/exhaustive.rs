@@ -0,0 +1,144 @@
+// Exhaustive checks of small heaps: every sequence of a few pushes, pops and appends, run step by step
+// against a sorted `Vec` and `validate()`, both lazily and with eager consolidation.
+//
+// A heap only ever compares its elements, so two sequences whose values are ordered the same way behave the same.
+// Instead of drawing values from a fixed domain, each push either repeats a value that is in the heap or places
+// a new one below, between or above them (while fewer than `MAX_DISTINCT` distinct values are in the heap),
+// which visits every ordering exactly once. Appends meld in a copy of the heap itself, as is or after one pop,
+// so that melded heaps have trees without bringing new values in.
+//
+// Sequences are enumerated by increasing length, so the first failure reported is a shortest one. Sequences of up to 6
+// operations are checked by default; those of up to 9 (about 12M states per mode) take under a minute in release mode
+// and are left to CI:
+//
+//     cargo test --release --test exhaustive -- --ignored
+#![cfg(not(target_arch = "wasm32"))]
+
+use fibheap::{assert_heap_eq, assert_valid, FibonacciHeap};
+
+const MAX_DISTINCT: usize = 4;
+// Room for every new value to be placed strictly between two others.
+const START: u64 = 1 << 40;
+const STEP: u64 = 1 << 32;
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Push(u64),
+    Pop,
+    AppendSelf,
+    AppendSelfPopped,
+}
+
+struct Checker {
+    eager: bool,
+    trace: Vec<Op>,
+    states: u64,
+}
+
+impl Checker {
+    // The operations tried after reaching `model`: every distinct ordering of a pushed value, and the rest
+    // only on a non-empty heap, where they do something.
+    fn ops(model: &[u64]) -> Vec<Op> {
+        let mut distinct = model.to_vec();
+        distinct.dedup();
+        let mut ops: Vec<Op> = distinct.iter().map(|&value| Op::Push(value)).collect();
+        if distinct.len() < MAX_DISTINCT {
+            match (distinct.first(), distinct.last()) {
+                (Some(&min), Some(&max)) => {
+                    ops.push(Op::Push(min - STEP));
+                    ops.extend(distinct.windows(2).map(|pair| Op::Push(pair[0] + (pair[1] - pair[0]) / 2)));
+                    ops.push(Op::Push(max + STEP));
+                }
+                _ => ops.push(Op::Push(START)),
+            }
+        }
+        if !model.is_empty() {
+            ops.extend([Op::Pop, Op::AppendSelf, Op::AppendSelfPopped]);
+        }
+        ops
+    }
+
+    fn apply(&self, op: Op, heap: &mut FibonacciHeap<u64>, model: &mut Vec<u64>) {
+        match op {
+            Op::Push(value) => {
+                heap.push(value);
+                model.insert(model.partition_point(|&other| other <= value), value);
+            }
+            Op::Pop => assert_eq!(heap.pop(), Some(model.remove(0)), "{}", self.context()),
+            Op::AppendSelf => {
+                heap.append(heap.clone());
+                let copy = model.clone();
+                merge(model, &copy);
+            }
+            Op::AppendSelfPopped => {
+                let mut other = heap.clone();
+                let mut other_model = model.clone();
+                assert_eq!(other.pop(), Some(other_model.remove(0)), "{}", self.context());
+                heap.append(other);
+                merge(model, &other_model);
+            }
+        }
+    }
+
+    fn check(&self, heap: &FibonacciHeap<u64>, model: &[u64], drain: bool) {
+        assert_valid!(*heap, "{}", self.context());
+        assert_eq!(heap.len(), model.len(), "{}", self.context());
+        assert_eq!(heap.peek(), model.first(), "{}", self.context());
+        if drain {
+            assert_heap_eq!(*heap, model, "{}", self.context());
+        }
+    }
+
+    fn context(&self) -> String {
+        format!("eager: {}, after {:?}", self.eager, self.trace)
+    }
+
+    // Runs every sequence of exactly `remaining` more operations from `heap`, checking each step.
+    fn explore(&mut self, heap: &FibonacciHeap<u64>, model: &[u64], remaining: usize) {
+        if remaining == 0 {
+            return;
+        }
+        for op in Self::ops(model) {
+            let mut heap = heap.clone();
+            let mut model = model.to_vec();
+            self.trace.push(op);
+            self.apply(op, &mut heap, &mut model);
+            // shorter sequences were drained by the previous rounds
+            self.check(&heap, &model, remaining == 1);
+            self.states += 1;
+            self.explore(&heap, &model, remaining - 1);
+            self.trace.pop();
+        }
+    }
+}
+
+fn merge(model: &mut Vec<u64>, other: &[u64]) {
+    model.extend_from_slice(other);
+    model.sort_unstable();
+}
+
+// Checks every sequence of up to `max_ops` operations, shortest first, and returns the number of states visited.
+fn check_all(max_ops: usize, eager: bool) -> u64 {
+    let mut checker = Checker { eager, trace: vec![], states: 0 };
+    let mut empty = FibonacciHeap::new();
+    empty.set_eager_consolidation(eager);
+    for len in 1..=max_ops {
+        checker.explore(&empty, &[], len);
+    }
+    checker.states
+}
+
+#[test]
+fn all_sequences_of_6() {
+    for eager in [false, true] {
+        assert_eq!(check_all(6, eager), 21_432);
+    }
+}
+
+#[test]
+#[ignore = "slow; run with --release"]
+fn all_sequences_of_9() {
+    for eager in [false, true] {
+        println!("eager: {eager}, {} states", check_all(9, eager));
+    }
+}
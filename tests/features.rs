@@ -0,0 +1,10 @@
+// The test helpers need no dependencies of their own and stay out of the default build; the crate only enables
+// them for its own tests, through a dev-dependency on itself.
+#[test]
+fn test_util_is_optional() {
+    let manifest = include_str!("../Cargo.toml");
+    assert!(manifest.lines().any(|line| line == "test-util = []"));
+    assert!(!manifest.lines().any(|line| line.starts_with("default =") && line.contains("test-util")));
+    let (normal, _) = manifest.split_once("[dev-dependencies]").unwrap();
+    assert!(!normal.contains("features = [\"test-util\"]"));
+}
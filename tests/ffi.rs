@@ -0,0 +1,36 @@
+#![cfg(all(feature = "ffi", unix))]
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+// Builds the crate as a static library, links tests/c/ffi.c against it through the generated header and runs it.
+#[test]
+fn c_program() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let out = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let target_dir = out.join("ffi");
+    let status = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+        .current_dir(root)
+        .args(["rustc", "--lib", "--features", "ffi", "--crate-type", "staticlib", "--target-dir"])
+        .arg(&target_dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let exe = out.join("ffi_test");
+    let status = Command::new(env::var("CC").unwrap_or_else(|_| "cc".into()))
+        .arg(root.join("tests/c/ffi.c"))
+        .arg("-I")
+        .arg(root.join("include"))
+        .arg(target_dir.join("debug/libfibheap.a"))
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&exe)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let output = Command::new(&exe).output().unwrap();
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(output.stdout, b"ok\n");
+}
@@ -0,0 +1,56 @@
+#![cfg(all(feature = "std", not(target_arch = "wasm32")))]
+
+use std::io::Write;
+
+use assert_cmd::Command;
+
+const WORDS: &str = "pear\napple\nfig\napple\nbanana\nfig\ncherry\n";
+const NUMBERS: &str = "10\n-2.5\n3\n10\n1e2\n0\n3\n";
+
+fn fibsort(args: &[&str], input: &str) -> String {
+    let output = Command::cargo_bin("fibsort").unwrap().args(args).write_stdin(input).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn sorts_lines() {
+    assert_eq!(fibsort(&[], WORDS), "apple\napple\nbanana\ncherry\nfig\nfig\npear\n");
+    assert_eq!(fibsort(&["--reverse"], WORDS), "pear\nfig\nfig\ncherry\nbanana\napple\napple\n");
+    assert_eq!(fibsort(&["--unique"], WORDS), "apple\nbanana\ncherry\nfig\npear\n");
+    assert_eq!(fibsort(&["--unique", "--reverse"], WORDS), "pear\nfig\ncherry\nbanana\napple\n");
+    assert_eq!(fibsort(&[], ""), "");
+}
+
+#[test]
+fn sorts_numbers() {
+    assert_eq!(fibsort(&[], NUMBERS), "-2.5\n0\n10\n10\n1e2\n3\n3\n");
+    assert_eq!(fibsort(&["--numeric"], NUMBERS), "-2.5\n0\n3\n3\n10\n10\n1e2\n");
+    assert_eq!(fibsort(&["-n", "-r", "-u"], NUMBERS), "1e2\n10\n3\n0\n-2.5\n");
+}
+
+#[test]
+fn top_k() {
+    assert_eq!(fibsort(&["--numeric", "--top-k", "3"], NUMBERS), "-2.5\n0\n3\n");
+    assert_eq!(fibsort(&["--numeric", "--top-k", "3", "--reverse"], NUMBERS), "1e2\n10\n10\n");
+    assert_eq!(fibsort(&["--numeric", "--top-k", "3", "--unique", "--reverse"], NUMBERS), "1e2\n10\n3\n");
+    assert_eq!(fibsort(&["--top-k", "0"], WORDS), "");
+    assert_eq!(fibsort(&["--top-k", "100"], WORDS), fibsort(&[], WORDS));
+}
+
+#[test]
+fn reads_a_file() {
+    let path = std::env::temp_dir().join(format!("fibsort-{}.txt", std::process::id()));
+    std::fs::File::create(&path).unwrap().write_all(WORDS.as_bytes()).unwrap();
+    let sorted = fibsort(&["-u", path.to_str().unwrap()], "ignored\n");
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(sorted, "apple\nbanana\ncherry\nfig\npear\n");
+}
+
+#[test]
+fn reports_errors() {
+    let mut fibsort = Command::cargo_bin("fibsort").unwrap();
+    fibsort.arg("--numeric").write_stdin("1\ntwo\n").assert().failure().code(2).stderr("fibsort: not a number: \"two\"\n");
+    let mut fibsort = Command::cargo_bin("fibsort").unwrap();
+    fibsort.args(["--top-k", "x"]).assert().failure().stderr("fibsort: invalid --top-k: x\n");
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "instrument")]
+
+use fibheap::FibonacciHeap;
+
+// xorshift, to keep the workload the same from run to run
+fn next(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+// Each link removes a root, and each pop adds at most the degree of the popped root, O(log n), so the links a pop
+// pays for stay logarithmic on average however pushes and pops are interleaved.
+#[test]
+fn links_per_pop_stay_logarithmic() {
+    let mut heap = FibonacciHeap::new();
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    let (mut pops, mut max_len) = (0u64, 0);
+    for round in 0..200_000u64 {
+        // the length drifts up to a few thousand and back down
+        let push_bias = if round % 100_000 < 50_000 { 6 } else { 4 };
+        if next(&mut state) % 10 < push_bias || heap.is_empty() {
+            heap.push(next(&mut state) % 1_000_000);
+        } else {
+            heap.pop();
+            pops += 1;
+        }
+        max_len = max_len.max(heap.len());
+    }
+    let counters = heap.counters();
+    let log_n = (max_len as f64).log2();
+    let links_per_pop = counters.links as f64 / pops as f64;
+    assert!(links_per_pop <= 2.0 * log_n + 2.0, "{links_per_pop} links per pop with up to {max_len} elements: {counters:?}");
+    assert!(counters.comparisons >= counters.links);
+    assert!(counters.root_scans <= pops);
+    assert!(counters.allocations <= counters.links);
+
+    heap.reset_counters();
+    assert_eq!(heap.counters(), Default::default());
+}
+
+#[test]
+fn counters_survive_append_and_clone() {
+    let mut a = FibonacciHeap::from_vec((0..100).collect());
+    a.pop();
+    let mut b = FibonacciHeap::from_vec((100..200).collect());
+    b.pop();
+    let links = a.counters().links + b.counters().links;
+    a.append(b);
+    assert_eq!(a.counters().links, links);
+    assert_eq!(a.clone().counters(), a.counters());
+}
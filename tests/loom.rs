@@ -0,0 +1,110 @@
+// Loom model checks of the blocking wrappers: every interleaving of their locks, waits and notifications, for a
+// few threads. Lost wakeups show up as loom's deadlock error.
+//
+//     RUSTFLAGS="--cfg loom" cargo test --release --features sync --test loom
+#![cfg(all(loom, feature = "sync"))]
+
+use std::sync::mpsc::{RecvError, RecvTimeoutError};
+use std::time::Duration;
+
+use fibheap::{priority_channel, SharedFibonacciHeap};
+use loom::thread;
+
+// Longer than any model run, so only the modelled timeout, which may expire whenever the lock is released, fires.
+const TIMEOUT: Duration = Duration::from_secs(3600);
+
+#[test]
+fn producer_and_two_consumers_receive_each_element_once() {
+    loom::model(|| {
+        let heap = SharedFibonacciHeap::new();
+        let consumers: Vec<_> = (0..2)
+            .map(|_| {
+                let heap = heap.clone();
+                thread::spawn(move || {
+                    let mut popped = vec![];
+                    while let Some(value) = heap.pop_blocking() {
+                        popped.push(value);
+                    }
+                    popped
+                })
+            })
+            .collect();
+        let producer = {
+            let heap = heap.clone();
+            thread::spawn(move || {
+                heap.push(2);
+                heap.push(1);
+            })
+        };
+        producer.join().unwrap();
+        heap.close();
+
+        let mut popped: Vec<_> = consumers.into_iter().flat_map(|c| c.join().unwrap()).collect();
+        popped.sort_unstable();
+        assert_eq!(popped, [1, 2]);
+    });
+}
+
+#[test]
+fn close_races_with_waiter() {
+    loom::model(|| {
+        let heap = SharedFibonacciHeap::<i32>::new();
+        let waiter = {
+            let heap = heap.clone();
+            thread::spawn(move || heap.pop_blocking())
+        };
+        heap.close();
+        assert_eq!(waiter.join().unwrap(), None);
+    });
+}
+
+#[test]
+fn disconnect_races_with_receiver() {
+    loom::model(|| {
+        let (tx, rx) = priority_channel();
+        let sender = thread::spawn(move || {
+            tx.send(1).unwrap();
+            // dropping the last sender must wake a receiver that found the channel empty
+        });
+        assert_eq!(rx.recv(), Ok(1));
+        assert_eq!(rx.recv(), Err(RecvError));
+        sender.join().unwrap();
+    });
+}
+
+#[test]
+fn push_races_with_pop_timeout_expiry() {
+    loom::model(|| {
+        let heap = SharedFibonacciHeap::new();
+        let waiter = {
+            let heap = heap.clone();
+            thread::spawn(move || heap.pop_timeout(TIMEOUT))
+        };
+        heap.push(1);
+        // the element is taken by the waiter, or left behind if it timed out first: never lost or duplicated
+        match waiter.join().unwrap() {
+            Some(value) => assert_eq!((value, heap.len()), (1, 0)),
+            None => assert_eq!(heap.try_pop(), Some(1)),
+        }
+    });
+}
+
+#[test]
+fn send_races_with_recv_timeout_expiry() {
+    loom::model(|| {
+        let (tx, rx) = priority_channel();
+        let receiver = thread::spawn(move || {
+            let first = rx.recv_timeout(TIMEOUT);
+            (first, rx)
+        });
+        tx.send(1).unwrap();
+        let (first, rx) = receiver.join().unwrap();
+        match first {
+            Ok(value) => assert_eq!(value, 1),
+            Err(error) => {
+                assert_eq!(error, RecvTimeoutError::Timeout);
+                assert_eq!(rx.try_recv(), Ok(1));
+            }
+        }
+    });
+}
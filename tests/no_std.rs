@@ -0,0 +1,24 @@
+// Exercises the alloc-only API from a crate without the std prelude. This links against the library built with std,
+// as every test does; CI builds the library itself for a target without std.
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use fibheap::FibonacciHeap;
+
+#[test]
+fn core_api_without_std() {
+    let mut heap = FibonacciHeap::from_vec(vec![5u32, 3, 8]);
+    heap.push(1);
+    let mut other = FibonacciHeap::new();
+    other.push(4);
+    heap.append(other);
+    assert_eq!(heap.pop(), Some(1));
+    assert_eq!(heap.iter_sorted().copied().collect::<Vec<_>>(), [3, 4, 5, 8]);
+    assert_eq!(heap.iter().count(), 4);
+    assert_eq!(fibheap::sort(vec![2, 1]), [1, 2]);
+    assert_eq!(heap.into_sorted_vec(), [3, 4, 5, 8]);
+}
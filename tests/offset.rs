@@ -0,0 +1,68 @@
+// Lazy offsets against an eager reference that shifts every element as soon as it is asked to: random sequences of
+// pushes, pops, shifts and shifted appends must pop the same values in the same order.
+#![cfg(not(target_arch = "wasm32"))]
+
+use fibheap::OffsetFibonacciHeap;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Push(i64),
+    Pop,
+    AddAll(i64),
+    // a heap built by these operations, appended with every element shifted by the delta
+    AppendWithOffset(Vec<Op>, i64),
+    Clear,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let leaf = prop_oneof![
+        8 => (-50i64..50).prop_map(Op::Push),
+        4 => Just(Op::Pop),
+        4 => (-20i64..20).prop_map(Op::AddAll),
+        1 => Just(Op::Clear),
+    ];
+    leaf.prop_recursive(2, 64, 16, |inner| (vec(inner, 0..16), -20i64..20).prop_map(|(ops, delta)| Op::AppendWithOffset(ops, delta)))
+}
+
+// Runs `ops` on both; the reference is kept sorted and shifted eagerly.
+fn run(ops: &[Op], heap: &mut OffsetFibonacciHeap<i64>, eager: &mut Vec<i64>) -> Result<(), TestCaseError> {
+    for op in ops {
+        match op {
+            Op::Push(value) => {
+                heap.push(*value);
+                eager.insert(eager.partition_point(|x| x <= value), *value);
+            }
+            Op::Pop => prop_assert_eq!(heap.pop(), (!eager.is_empty()).then(|| eager.remove(0))),
+            Op::AddAll(delta) => {
+                heap.add_all(*delta);
+                eager.iter_mut().for_each(|x| *x += delta);
+            }
+            Op::AppendWithOffset(ops, delta) => {
+                let (mut other, mut other_eager) = (OffsetFibonacciHeap::new(), vec![]);
+                run(ops, &mut other, &mut other_eager)?;
+                heap.append_with_offset(other, *delta);
+                eager.extend(other_eager.iter().map(|x| x + delta));
+                eager.sort_unstable();
+            }
+            Op::Clear => {
+                heap.clear();
+                eager.clear();
+            }
+        }
+        prop_assert_eq!(heap.len(), eager.len());
+        prop_assert_eq!(heap.peek_value(), eager.first().copied());
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn matches_eager_shifts(ops in vec(op(), 0..100)) {
+        let (mut heap, mut eager) = (OffsetFibonacciHeap::new(), vec![]);
+        run(&ops, &mut heap, &mut eager)?;
+        let drained: Vec<_> = std::iter::from_fn(|| heap.pop()).collect();
+        prop_assert_eq!(drained, eager);
+    }
+}
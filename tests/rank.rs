@@ -0,0 +1,30 @@
+// `rank` and `select` against a sorted dump of the heap after random workloads.
+#![cfg(not(target_arch = "wasm32"))]
+
+use fibheap::FibonacciHeap;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn matches_sorted_dump(values in vec(-50i32..50, 0..300), pops in 0usize..50, others in vec(-50i32..50, 0..100)) {
+        let mut heap = FibonacciHeap::new();
+        for value in values {
+            heap.push(value);
+        }
+        for _ in 0..pops {
+            heap.pop();
+        }
+        let mut other = FibonacciHeap::from_vec(others);
+        other.pop();
+        heap.append(other);
+
+        let sorted = heap.clone().into_sorted_vec();
+        for k in 0..=sorted.len() {
+            prop_assert_eq!(heap.select(k), sorted.get(k));
+        }
+        for probe in -51..=51 {
+            prop_assert_eq!(heap.rank(&probe), sorted.partition_point(|&v| v < probe));
+        }
+    }
+}
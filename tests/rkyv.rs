@@ -0,0 +1,57 @@
+#![cfg(feature = "rkyv")]
+
+use fibheap::{ArchivedFibonacciHeap, FibonacciHeap};
+use rkyv::rancor::Error;
+use rkyv::Archived;
+
+type ArchivedHeap = ArchivedFibonacciHeap<Archived<u64>>;
+
+fn shaped_heap() -> FibonacciHeap<u64> {
+    let mut heap = FibonacciHeap::new();
+    for i in 0..2000u64 {
+        heap.push(i * 7919 % 1009);
+        if i % 3 == 0 {
+            heap.pop();
+        }
+    }
+    heap
+}
+
+#[test]
+fn round_trip() {
+    for heap in [FibonacciHeap::new(), FibonacciHeap::from_vec(vec![4, 4, 1]), shaped_heap()] {
+        let bytes = rkyv::to_bytes::<Error>(&heap).unwrap();
+        let archived = rkyv::access::<ArchivedHeap, Error>(&bytes).unwrap();
+        let back: FibonacciHeap<u64> = rkyv::deserialize::<_, Error>(archived).unwrap();
+        assert_eq!(back, heap);
+        assert_eq!(back.into_sorted_vec(), heap.into_sorted_vec());
+    }
+}
+
+#[test]
+fn read_archived_in_place() {
+    let heap = shaped_heap();
+    let bytes = rkyv::to_bytes::<Error>(&heap).unwrap();
+    let archived = rkyv::access::<ArchivedHeap, Error>(&bytes).unwrap();
+    assert_eq!(archived.len(), heap.len());
+    assert!(!archived.is_empty());
+
+    let mut archived_values: Vec<u64> = archived.iter().map(|value| value.to_native()).collect();
+    let mut values: Vec<u64> = heap.iter().copied().collect();
+    archived_values.sort_unstable();
+    values.sort_unstable();
+    assert_eq!(archived_values, values);
+}
+
+#[test]
+fn rejects_malformed_archives() {
+    let bytes = rkyv::to_bytes::<Error>(&FibonacciHeap::from_vec(vec![1u64, 2, 3])).unwrap();
+    let mut bytes = bytes.to_vec();
+    assert!(rkyv::access::<ArchivedHeap, Error>(&bytes[..4]).is_err());
+    // the archived root ends with the element count: claim more elements than there are bytes
+    let len = bytes.len();
+    bytes[len - 4..].copy_from_slice(&1000u32.to_le_bytes());
+    let mut aligned = rkyv::util::AlignedVec::<16>::new();
+    aligned.extend_from_slice(&bytes);
+    assert!(rkyv::access::<ArchivedHeap, Error>(&aligned).is_err());
+}
@@ -0,0 +1,148 @@
+#![cfg(feature = "serde")]
+
+use fibheap::{assert_heap_eq, replay, FibonacciHeap, Op, RecordingHeap};
+
+#[test]
+fn json_round_trip() {
+    let heap = FibonacciHeap::from_vec(vec![3, 1, 2, 1]);
+    let json = serde_json::to_string(&heap).unwrap();
+    // a plain ascending sequence, with no trace of the internal structure
+    assert_eq!(json, "[1,1,2,3]");
+    let back: FibonacciHeap<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, heap);
+
+    let unsorted: FibonacciHeap<i32> = serde_json::from_str("[5,4,5]").unwrap();
+    assert_heap_eq!(unsorted, [4, 5, 5]);
+
+    let empty: FibonacciHeap<i32> = serde_json::from_str("[]").unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(serde_json::to_string(&empty).unwrap(), "[]");
+}
+
+#[test]
+fn nan_is_an_error() {
+    use fibheap::DecodeError;
+
+    // JSON has no NaN, so go through bincode
+    let bytes = bincode::serialize(&vec![2.0, 1.0, f64::NAN]).unwrap();
+    let error = bincode::deserialize::<FibonacciHeap<f64>>(&bytes).unwrap_err();
+    assert_eq!(error.to_string(), DecodeError::Incomparable { index: 2 }.to_string());
+    let floats: FibonacciHeap<f64> = bincode::deserialize(&bincode::serialize(&vec![2.0, 1.0]).unwrap()).unwrap();
+    assert_heap_eq!(floats, [1.0, 2.0]);
+}
+
+#[test]
+fn bincode_round_trip() {
+    let mut heap = FibonacciHeap::new();
+    for i in 0..1000u32 {
+        heap.push(i % 37);
+        if i % 5 == 0 {
+            heap.pop();
+        }
+    }
+    let bytes = bincode::serialize(&heap).unwrap();
+    // same encoding as the sorted elements
+    let mut sorted = heap.clone().into_sorted_vec();
+    assert_eq!(bytes, bincode::serialize(&sorted).unwrap());
+
+    let back: FibonacciHeap<u32> = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(back, heap);
+    sorted.reverse();
+    let from_vec: FibonacciHeap<u32> = bincode::deserialize(&bincode::serialize(&sorted).unwrap()).unwrap();
+    assert_eq!(from_vec, heap);
+
+    let empty = FibonacciHeap::<String>::new();
+    let back: FibonacciHeap<String> = bincode::deserialize(&bincode::serialize(&empty).unwrap()).unwrap();
+    assert!(back.is_empty());
+}
+
+#[test]
+fn recorded_ops_round_trip() {
+    let mut heap = RecordingHeap::new();
+    heap.bulk_push([5, 3, 8]);
+    heap.pop();
+    let mut other = RecordingHeap::new();
+    other.push(1);
+    heap.append(other);
+    let json = serde_json::to_string(heap.ops()).unwrap();
+    assert_eq!(json, r#"[{"BulkPush":[5,3,8]},"Pop",{"Append":[{"Push":1}]}]"#);
+    let ops: Vec<Op<i32>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(ops, heap.ops());
+    assert_eq!(replay(&ops), *heap.heap());
+}
+
+#[test]
+fn options_reject_hostile_input() {
+    use bincode::Options;
+    use fibheap::{DecodeError, DeserializeOptions};
+
+    let options = DeserializeOptions { max_len: Some(4), validate: true };
+    let bincode = || bincode::options().with_fixint_encoding();
+    let decode = |bytes: &[u8]| options.deserialize::<u32, _>(&mut bincode::Deserializer::from_slice(bytes, bincode()));
+
+    // a length prefix claiming 2^60 elements, with nothing behind it, is refused before reserving anything
+    let forged = (1u64 << 60).to_le_bytes();
+    let error = decode(&forged).unwrap_err().to_string();
+    assert_eq!(error, DecodeError::TooLong { max_len: 4 }.to_string());
+
+    let ok = bincode().serialize(&vec![1u32, 2, 2, 7]).unwrap();
+    assert_heap_eq!(decode(&ok).unwrap(), [1, 2, 2, 7]);
+    let long = bincode().serialize(&vec![1u32, 2, 3, 4, 5]).unwrap();
+    assert!(decode(&long).is_err());
+    let unordered = bincode().serialize(&vec![1u32, 3, 2]).unwrap();
+    assert_eq!(decode(&unordered).unwrap_err().to_string(), DecodeError::Unordered { index: 2 }.to_string());
+    // truncated input is the format's own error
+    assert!(decode(&ok[..ok.len() - 1]).is_err());
+
+    // without a length prefix, reading stops at the first element past the limit
+    let json = |text: &str| options.deserialize::<f64, _>(&mut serde_json::Deserializer::from_str(text));
+    assert!(json("[1,2,3,4,5,6]").unwrap_err().to_string().starts_with("heap has more than 4 elements"));
+    assert_eq!(json("[0.5,1.5]").unwrap().len(), 2);
+
+    // NaN is comparable with nothing, so it can't be in order
+    let nan = vec![1.0, f64::NAN];
+    let bytes = bincode().serialize(&nan).unwrap();
+    let decoded = options.deserialize::<f64, _>(&mut bincode::Deserializer::from_slice(&bytes, bincode()));
+    assert!(decoded.unwrap_err().to_string().contains("out of ascending order"));
+
+    // the default options accept what the plain impl does
+    let plain = DeserializeOptions::default().deserialize::<u32, _>(&mut serde_json::Deserializer::from_str("[3,1,2]"));
+    assert_heap_eq!(plain.unwrap(), [1, 2, 3]);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod fuzz {
+    use bincode::Options;
+    use fibheap::{DeserializeOptions, FibonacciHeap};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    const OPTIONS: DeserializeOptions = DeserializeOptions { max_len: Some(64), validate: true };
+
+    proptest! {
+        // Random bytes either decode into a valid heap within the limit or come back as an error, without panicking.
+        #[test]
+        fn random_bytes_never_panic(bytes in vec(any::<u8>(), 0..256)) {
+            let bincode = bincode::options().with_fixint_encoding().allow_trailing_bytes();
+            let decoded = OPTIONS.deserialize::<u32, _>(&mut bincode::Deserializer::from_slice(&bytes, bincode));
+            if let Ok(heap) = decoded {
+                prop_assert_eq!(heap.validate(), Ok(()));
+                prop_assert!(heap.len() <= 64);
+            }
+            if let Ok(heap) = OPTIONS.deserialize::<u32, _>(&mut serde_json::Deserializer::from_slice(&bytes)) {
+                prop_assert!(heap.len() <= 64);
+            }
+        }
+
+        // Whatever `Serialize` writes passes validation unchanged.
+        #[test]
+        fn round_trip_validates(values in vec(any::<u32>(), 0..=64)) {
+            let heap = FibonacciHeap::from_vec(values);
+            let bytes = bincode::serialize(&heap).unwrap();
+            let back = OPTIONS.deserialize(&mut bincode::Deserializer::from_slice(&bytes, bincode::options().with_fixint_encoding()));
+            prop_assert_eq!(back.unwrap(), heap.clone());
+            let json = serde_json::to_string(&heap).unwrap();
+            prop_assert_eq!(OPTIONS.deserialize(&mut serde_json::Deserializer::from_str(&json)).unwrap(), heap);
+        }
+    }
+}
@@ -0,0 +1,54 @@
+// `FibonacciHeapSet` against a `BTreeSet` model: membership after every operation, and pop order.
+#![cfg(all(feature = "std", not(target_arch = "wasm32")))]
+
+use std::collections::BTreeSet;
+
+use fibheap::FibonacciHeapSet;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Insert(u8),
+    Remove(u8),
+    Pop,
+    Meld(Vec<u8>),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    // few distinct values, so that duplicates and reinsertions are common
+    prop_oneof![
+        4 => (0u8..40).prop_map(Op::Insert),
+        3 => (0u8..40).prop_map(Op::Remove),
+        2 => Just(Op::Pop),
+        1 => vec(0u8..40, 0..30).prop_map(Op::Meld),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn matches_btree_set(ops in vec(op(), 0..400)) {
+        let mut set = FibonacciHeapSet::new();
+        let mut model = BTreeSet::new();
+        for op in ops {
+            match op {
+                Op::Insert(value) => prop_assert_eq!(set.insert(value), model.insert(value)),
+                Op::Remove(value) => prop_assert_eq!(set.remove(&value), model.remove(&value)),
+                Op::Pop => prop_assert_eq!(set.pop(), model.pop_first()),
+                Op::Meld(values) => {
+                    model.extend(values.iter().copied());
+                    set.meld(values.into_iter().collect());
+                }
+            }
+            prop_assert_eq!(set.len(), model.len());
+            prop_assert_eq!(set.peek(), model.first());
+            for value in 0..40 {
+                prop_assert_eq!(set.contains(&value), model.contains(&value));
+            }
+        }
+        while let Some(value) = set.pop() {
+            prop_assert_eq!(Some(value), model.pop_first());
+        }
+        prop_assert!(model.is_empty());
+    }
+}
@@ -0,0 +1,63 @@
+// Soak tests: long generated workloads run against `FibonacciHeap` and `BinaryHeap` side by side, validating the heap
+// after every operation. A failure names the seed and step, and `workload(seed, len)` regenerates the operations.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use fibheap::test_util::workload;
+use fibheap::{assert_heap_eq, assert_valid, FibonacciHeap, Op};
+
+type Model = BinaryHeap<Reverse<u64>>;
+
+// Applies `op` to both, comparing what they return.
+fn apply(op: &Op<u64>, heap: &mut FibonacciHeap<u64>, model: &mut Model) {
+    match op {
+        Op::Push(value) => {
+            heap.push(*value);
+            model.push(Reverse(*value));
+        }
+        Op::BulkPush(values) => {
+            heap.bulk_push(values.iter().copied());
+            model.extend(values.iter().copied().map(Reverse));
+        }
+        Op::Pop => assert_eq!(heap.pop(), model.pop().map(|r| r.0)),
+        Op::PushPop(value) => {
+            model.push(Reverse(*value));
+            assert_eq!(heap.push_pop(*value), model.pop().unwrap().0);
+        }
+        Op::ReplaceTop(value) => {
+            let old = model.pop().map(|r| r.0);
+            model.push(Reverse(*value));
+            assert_eq!(heap.replace_top(*value), old);
+        }
+        Op::Append(ops) => {
+            let (mut other, mut other_model) = (FibonacciHeap::new(), Model::new());
+            for op in ops {
+                apply(op, &mut other, &mut other_model);
+            }
+            heap.append(other);
+            model.append(&mut other_model);
+        }
+        Op::Consolidate => heap.consolidate(),
+        Op::Clear => {
+            heap.clear();
+            model.clear();
+        }
+        Op::SetMaxLinksPerPop(max) => heap.set_max_links_per_pop(*max),
+        Op::SetEagerConsolidation(eager) => heap.set_eager_consolidation(*eager),
+    }
+}
+
+#[test]
+fn generated_workloads() {
+    for seed in 0..20 {
+        let (mut heap, mut model) = (FibonacciHeap::new(), Model::new());
+        for (step, op) in workload(seed, 2000).iter().enumerate() {
+            apply(op, &mut heap, &mut model);
+            assert_valid!(heap, "seed {seed}, step {step}: {op:?}");
+            assert_eq!(heap.len(), model.len());
+        }
+        let expected: Vec<u64> = model.into_sorted_vec().into_iter().rev().map(|r| r.0).collect();
+        assert_heap_eq!(heap, expected, "seed {seed}");
+    }
+}
@@ -0,0 +1,71 @@
+// `from_sorted_runs` against sorting the concatenated runs, also after further pushes and appends.
+#![cfg(not(target_arch = "wasm32"))]
+
+use fibheap::{assert_valid, FibonacciHeap};
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+fn sorted_runs() -> impl Strategy<Value = Vec<Vec<i32>>> {
+    vec(vec(-50i32..50, 0..40), 0..12).prop_map(|mut runs| {
+        runs.iter_mut().for_each(|run| run.sort_unstable());
+        runs
+    })
+}
+
+proptest! {
+    #[test]
+    fn pops_in_global_order(runs in sorted_runs(), pushed in vec(-50i32..50, 0..20), others in sorted_runs()) {
+        let mut expected: Vec<i32> = runs.iter().chain(&others).flatten().chain(&pushed).copied().collect();
+        expected.sort_unstable();
+
+        let mut heap = FibonacciHeap::from_sorted_runs(runs);
+        assert_valid!(heap);
+        pushed.into_iter().for_each(|value| heap.push(value));
+        heap.append(FibonacciHeap::from_sorted_runs(others));
+        prop_assert_eq!(heap.len(), expected.len());
+        prop_assert_eq!(heap.into_sorted_vec(), expected);
+    }
+}
+
+#[test]
+fn one_long_run() {
+    // a single chain far deeper than recursion over the tree could go
+    let mut heap = FibonacciHeap::from_sorted_runs(vec![(0..1_000_000).collect()]);
+    assert_eq!(heap.peek(), Some(&0));
+    assert_eq!(heap.clone().pop(), Some(0));
+    heap.push(-1);
+    assert_eq!(heap.into_sorted_vec(), (-1..1_000_000).collect::<Vec<_>>());
+    assert!(FibonacciHeap::<i32>::from_sorted_runs(vec![vec![], vec![]]).is_empty());
+}
+
+#[test]
+fn deep_run_teardown_and_copies() {
+    let run = || FibonacciHeap::from_sorted_runs(vec![(0..1_000_000u64).collect()]);
+    let heap = run();
+    assert!(heap.stats().max_depth > 400_000);
+
+    let copy = heap.clone();
+    let mut target = FibonacciHeap::from_vec(vec![7, 8, 9]);
+    target.clone_from(&heap);
+    // cloning over a heap of the same deep shape reuses its nodes
+    target.clone_from(&copy);
+    assert_eq!(target.iter().count(), 1_000_000);
+    drop(copy);
+
+    let mut cleared = run();
+    cleared.clear();
+    assert!(cleared.is_empty());
+    cleared.clone_from(&heap);
+    assert_eq!(cleared.len(), 1_000_000);
+    cleared.clear();
+
+    drop(target);
+    drop(heap);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "not sorted")]
+fn unsorted_run() {
+    FibonacciHeap::from_sorted_runs(vec![vec![1, 2], vec![3, 1]]);
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "tracing")]
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use fibheap::FibonacciHeap;
+use tracing::Level;
+
+// Collects what the subscriber writes, so that the events can be checked.
+#[derive(Clone, Default)]
+struct Output(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn events(max_level: Level, f: impl FnOnce()) -> Vec<String> {
+    let output = Output::default();
+    let writer = output.clone();
+    let subscriber = tracing_subscriber::fmt().with_max_level(max_level).without_time().with_writer(move || writer.clone()).finish();
+    tracing::subscriber::with_default(subscriber, f);
+    let text = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+    text.lines().map(str::to_owned).collect()
+}
+
+#[test]
+fn scripted_workload() {
+    let events = events(Level::TRACE, || {
+        let mut heap = FibonacciHeap::new();
+        heap.push(5);
+        heap.push(7);
+        heap.push(3);
+        heap.append(FibonacciHeap::from_vec((10..30).collect()));
+        assert_eq!(heap.pop(), Some(3));
+    });
+    let expected = [
+        "TRACE fibheap: push len=1 roots=1 top_changed=true",
+        "TRACE fibheap: push len=2 roots=2 top_changed=false",
+        "TRACE fibheap: push len=3 roots=3 top_changed=true",
+        "TRACE fibheap: append len=23 moved=20 roots=23 top_changed=false",
+        "TRACE fibheap: consolidation roots_before=22 links=19 roots=3 budget_exhausted=false",
+        "TRACE fibheap: pop len=22 promoted=0 links=19 roots=3",
+    ];
+    assert_eq!(events, expected);
+}
+
+#[test]
+fn large_consolidations_are_debug_events() {
+    let workload = || {
+        let mut heap = FibonacciHeap::new();
+        heap.bulk_push(0..10_000);
+        heap.pop();
+        for i in 0..100 {
+            heap.push(i);
+        }
+        heap.pop();
+    };
+    let events = events(Level::DEBUG, workload);
+    assert_eq!(events, ["DEBUG fibheap: large consolidation roots_before=9999 links=9991 roots=8 budget_exhausted=false"]);
+}
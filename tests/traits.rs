@@ -0,0 +1,129 @@
+// Auto traits and standard impls that users rely on without naming them; losing one is a breaking change.
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::rc::Rc;
+
+use fibheap::*;
+use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+assert_impl_all!(FibonacciHeap<u32>: Send, Sync, Clone, Debug, Default);
+assert_impl_all!(Iter<'static, u32>: Send, Sync, Clone, Debug);
+assert_impl_all!(IterSorted<'static, u32>: Send, Sync, Clone, Debug);
+assert_impl_all!(NodeRef<'static, u32>: Send, Sync, Copy, Debug);
+assert_impl_all!(CursorMut<'static, u32>: Send, Sync, Debug);
+assert_impl_all!(DisplayTree<'static, u32, fn(&u32, &mut std::fmt::Formatter<'_>) -> std::fmt::Result>: Send, Sync, Clone, Debug);
+assert_impl_all!(HeapStats: Send, Sync, Copy, Debug, Default, PartialEq);
+assert_impl_all!(HeapMemoryUsage: Send, Sync, Copy, Debug, Default, Eq);
+assert_impl_all!(InvariantViolation: Send, Sync, Clone, Debug, Eq);
+assert_impl_all!(CheckpointId: Send, Sync, Copy, Debug, Eq, Ord, Hash);
+assert_impl_all!(CheckpointFibonacciHeap<u32>: Send, Sync, Debug, Default);
+assert_impl_all!(CowFibonacciHeap<u32>: Send, Sync, Clone, Debug, Default);
+assert_impl_all!(PinnedFibonacciHeap<u32>: Send, Sync, Debug);
+assert_impl_all!(TombstoneFibonacciHeap<u32>: Send, Sync, Debug, Default);
+assert_impl_all!(PairingHeap<u32>: Send, Sync, Clone, Debug);
+assert_impl_all!(PersistentFibonacciHeap<u32>: Clone, Debug, Default);
+assert_impl_all!(ObservedFibonacciHeap<u32>: Debug, Default);
+assert_impl_all!(OffsetFibonacciHeap<i64>: Debug, Default);
+assert_impl_all!(Op<u32>: Send, Sync, Clone, Debug, PartialEq);
+assert_impl_all!(IndexedFibonacciHeap<Vec<u64>>: Send, Sync, Debug);
+
+// Shared nodes and groups, and a hook that may hold an `Rc`, can't cross threads.
+assert_not_impl_any!(PersistentFibonacciHeap<u32>: Send, Sync);
+assert_not_impl_any!(ObservedFibonacciHeap<u32>: Send, Sync);
+assert_not_impl_any!(OffsetFibonacciHeap<i64>: Send, Sync);
+// The heaps own their elements, so they are only as thread-safe as `T`.
+assert_not_impl_any!(FibonacciHeap<Rc<u32>>: Send, Sync);
+assert_not_impl_any!(PinnedFibonacciHeap<Rc<u32>>: Send, Sync);
+assert_not_impl_any!(CowFibonacciHeap<std::cell::Cell<u32>>: Sync);
+
+#[cfg(feature = "std")]
+mod std_types {
+    use super::*;
+
+    assert_impl_all!(TimerKey: Send, Sync, Copy, Debug, Eq, Ord, Hash);
+    assert_impl_all!(TimerQueue<u32>: Send, Sync, Debug, Default);
+    assert_impl_all!(GroupedTopK<u32, u32>: Send, Sync, Debug);
+    assert_impl_all!(Groups<u32, u32>: Send, Sync, Debug);
+    assert_not_impl_any!(Groups<u32, u32>: Clone);
+    assert_impl_all!(CountedFibonacciHeap<u32>: Send, Sync, Debug);
+    assert_impl_all!(FibonacciHeapSet<u32>: Send, Sync, Debug, Default);
+    assert_impl_all!(LazyKeyedHeap<u32, u64>: Send, Sync, Debug, Default);
+}
+
+#[cfg(feature = "sync")]
+mod sync_types {
+    use super::*;
+    use std::cell::Cell;
+
+    // `Cell` is `Send` but not `Sync`: the lock is what makes sharing sound.
+    assert_impl_all!(SharedFibonacciHeap<Cell<u32>>: Send, Sync, Clone);
+    assert_impl_all!(MultiQueue<Cell<u32>>: Send, Sync);
+    assert_impl_all!(Sender<Cell<u32>>: Send, Sync, Clone, Debug);
+    assert_impl_all!(Receiver<Cell<u32>>: Send, Sync, Debug);
+    assert_not_impl_any!(SharedFibonacciHeap<Rc<u32>>: Send, Sync);
+    assert_not_impl_any!(Receiver<u32>: Clone);
+}
+
+#[cfg(feature = "async")]
+mod async_types {
+    use super::*;
+
+    assert_impl_all!(AsyncFibonacciHeap<std::cell::Cell<u32>>: Send, Sync, Clone);
+    assert_impl_all!(Pop<'static, u32>: Send, Sync, Debug);
+}
+
+#[test]
+fn keys_in_hash_sets() {
+    let mut heap = CheckpointFibonacciHeap::new();
+    heap.push(1);
+    let ids: Vec<_> = (0..10).map(|_| heap.checkpoint()).collect();
+    let set: HashSet<_> = ids.iter().copied().collect();
+    assert_eq!(set.len(), 10);
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(set.contains(&ids[3]));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn timer_keys_in_hash_sets() {
+    use std::time::{Duration, Instant};
+
+    let now = Instant::now();
+    let mut timers = TimerQueue::new();
+    let keys: Vec<_> = (0..10).map(|i| timers.schedule(now + Duration::from_millis(10 - i), i)).collect();
+    let mut cancelled: HashSet<TimerKey> = keys.iter().copied().step_by(2).collect();
+    assert_eq!(cancelled.len(), 5);
+    for key in &cancelled {
+        timers.cancel(*key);
+    }
+    // ordered by scheduling, not by deadline
+    assert_eq!(keys.iter().max(), keys.last());
+    cancelled.retain(|key| *key > keys[5]);
+    assert_eq!(cancelled.len(), 2);
+    assert_eq!(timers.len(), 5);
+}
+
+#[test]
+fn debug_without_debug_elements() {
+    struct Opaque(u32);
+    impl PartialEq for Opaque {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+    impl PartialOrd for Opaque {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            self.0.partial_cmp(&other.0)
+        }
+    }
+
+    let heap = FibonacciHeap::from_vec((0..5).map(Opaque).collect());
+    assert_eq!(format!("{:?}", heap.iter()), "Iter { remaining: 5, .. }");
+    let mut sorted = heap.iter_sorted();
+    sorted.next();
+    assert!(format!("{sorted:?}").starts_with("IterSorted {"));
+    let tree = heap.display_tree_with(|value, f| write!(f, "{}", value.0)).max_depth(2);
+    assert_eq!(format!("{tree:?}"), format!("DisplayTree {{ max_depth: 2, max_children: {}, .. }}", usize::MAX));
+}
@@ -0,0 +1,49 @@
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+// Run with `wasm-pack test --node -- --features wasm`.
+
+use fibheap::JsFibHeap;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn js(value: &str) -> JsValue {
+    JsValue::from_str(value)
+}
+
+#[wasm_bindgen_test]
+fn push_pop_peek() {
+    let mut heap = JsFibHeap::new();
+    assert_eq!(heap.pop(), None);
+    assert_eq!(heap.peek(), None);
+    for (priority, value) in [(3.0, "c"), (1.0, "a"), (2.0, "b"), (1.0, "a2")] {
+        heap.push(priority, js(value)).unwrap();
+    }
+    assert_eq!(heap.length(), 4);
+    assert_eq!(heap.peek(), Some(js("a")));
+    assert_eq!(heap.to_array_sorted(), [js("a"), js("a2"), js("b"), js("c")]);
+    assert_eq!(heap.length(), 4);
+    assert_eq!(heap.pop(), Some(js("a")));
+    assert_eq!(heap.pop(), Some(js("a2")));
+    assert_eq!(heap.length(), 2);
+}
+
+#[wasm_bindgen_test]
+fn rejects_nan() {
+    let mut heap = JsFibHeap::new();
+    assert!(heap.push(f64::NAN, JsValue::NULL).is_err());
+    assert_eq!(heap.length(), 0);
+    heap.push(0.0, JsValue::NULL).unwrap();
+    assert_eq!(heap.pop(), Some(JsValue::NULL));
+}
+
+#[wasm_bindgen_test]
+fn merge() {
+    let (mut left, mut right) = (JsFibHeap::new(), JsFibHeap::new());
+    left.push(2.0, js("l")).unwrap();
+    right.push(0.0, js("r0")).unwrap();
+    right.push(2.0, js("r2")).unwrap();
+    left.merge(&mut right);
+    assert_eq!(right.length(), 0);
+    left.push(2.0, js("late")).unwrap();
+    assert_eq!(left.to_array_sorted(), [js("r0"), js("l"), js("r2"), js("late")]);
+}
@@ -0,0 +1,22 @@
+// Rolling minimums against the brute-force O(nk) computation over every window.
+#![cfg(not(target_arch = "wasm32"))]
+
+use fibheap::{window_min, window_min_by_key};
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn matches_brute_force(stream in vec(-20i32..20, 0..300), k in 1usize..40) {
+        let expected: Vec<i32> = stream.windows(k).map(|window| *window.iter().min().unwrap()).collect();
+        prop_assert_eq!(window_min(stream.iter().copied(), k).collect::<Vec<_>>(), expected);
+    }
+
+    // Ties on the key go to the earliest item of the window, as with `Iterator::min_by_key`.
+    #[test]
+    fn by_key_matches_brute_force(keys in vec(0u8..8, 0..300), k in 1usize..40) {
+        let stream: Vec<(u8, usize)> = keys.into_iter().zip(0..).collect();
+        let expected: Vec<_> = stream.windows(k).map(|window| *window.iter().min_by_key(|item| item.0).unwrap()).collect();
+        prop_assert_eq!(window_min_by_key(stream, k, |item| item.0).collect::<Vec<_>>(), expected);
+    }
+}